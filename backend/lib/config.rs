@@ -1,15 +1,73 @@
-use std::env;
+use std::{env, fs, num::NonZeroUsize, path::PathBuf, time::Duration};
 
 use anyhow::Context;
+use chrono::TimeDelta;
+use url::Url;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+use crate::{
+    domain::website::models::quota::Quotas,
+    outbound::{
+        builder_script::{BuilderScript, LOVABLE_SCRIPT},
+        llm::{LlmConfig, LlmPricing, LlmProvider},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Config {
     pub server_port: String,
     pub database_url: String,
     pub jwks: String,
     pub webdriver_address: String,
-    pub lovable_user: String,
-    pub lovable_password: String,
+    pub site_generator: SiteGeneratorConfig,
+    pub notifier: NotifierConfig,
+    pub preview: PreviewConfig,
+    /// Directory the object storage keeps screenshots in.
+    pub object_storage_dir: PathBuf,
+    pub render_javascript: bool,
+    pub llm: LlmConfig,
+    pub quotas: Quotas,
+    /// Users allowed to use the admin API, by JWT subject.
+    pub admin_user_ids: Vec<String>,
+}
+
+/// How landing pages are generated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SiteGeneratorConfig {
+    /// Through the Lovable web UI, logged in with these credentials and driven by `script`. At
+    /// most `max_sessions` browsers are driven at once, and they are closed after
+    /// `session_idle_timeout` unused.
+    Lovable {
+        user: String,
+        password: String,
+        script: BuilderScript,
+        max_sessions: NonZeroUsize,
+        session_idle_timeout: Duration,
+    },
+    /// From the bundled templates, hosted as previews. The copy is written by the LLM when
+    /// `llm_copy` is set.
+    Static { llm_copy: bool },
+}
+
+/// How website events reach the connected clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotifierConfig {
+    /// In process, only clients connected to this instance receive its events.
+    Local,
+    /// Through Postgres `NOTIFY`, clients connected to any instance receive all events.
+    Postgres,
+}
+
+/// Hosting of generated static sites under `/preview/{slug}/`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreviewConfig {
+    /// Directory the generated sites are written to and served from.
+    pub dir: PathBuf,
+    /// Url the server is reachable at, used to build the preview urls sent to prospects.
+    pub public_base_url: Url,
+    /// `None` if previews never expire.
+    pub ttl: Option<TimeDelta>,
+    /// HTML of the banner shown on top of every preview.
+    pub banner_text: String,
 }
 
 const DATABASE_URL_KEY: &str = "DATABASE_URL";
@@ -18,6 +76,41 @@ const JWKS_KEY: &str = "JWKS";
 const WEBDRIVER_ADDRESS_KEY: &str = "WEBDRIVER_ADDRESS";
 const LOVABLE_USER_KEY: &str = "LOVABLE_USER";
 const LOVABLE_PASSWORD_KEY: &str = "LOVABLE_PASSWORD";
+const SITE_BUILDER_SCRIPT_KEY: &str = "SITE_BUILDER_SCRIPT";
+const LOVABLE_MAX_SESSIONS_KEY: &str = "LOVABLE_MAX_SESSIONS";
+const LOVABLE_SESSION_IDLE_SECS_KEY: &str = "LOVABLE_SESSION_IDLE_SECS";
+const SITE_GENERATOR_KEY: &str = "SITE_GENERATOR";
+const EVENT_NOTIFIER_KEY: &str = "EVENT_NOTIFIER";
+const STATIC_SITE_OUTPUT_DIR_KEY: &str = "STATIC_SITE_OUTPUT_DIR";
+const STATIC_SITE_LLM_COPY_KEY: &str = "STATIC_SITE_LLM_COPY";
+const PUBLIC_BASE_URL_KEY: &str = "PUBLIC_BASE_URL";
+const PREVIEW_TTL_DAYS_KEY: &str = "PREVIEW_TTL_DAYS";
+const PREVIEW_BANNER_TEXT_KEY: &str = "PREVIEW_BANNER_TEXT";
+const OBJECT_STORAGE_DIR_KEY: &str = "OBJECT_STORAGE_DIR";
+const RENDER_JAVASCRIPT_KEY: &str = "RENDER_JAVASCRIPT";
+const LLM_PROVIDER_KEY: &str = "LLM_PROVIDER";
+const LLM_MODEL_KEY: &str = "LLM_MODEL";
+const LLM_TEMPERATURE_KEY: &str = "LLM_TEMPERATURE";
+const LLM_BASE_URL_KEY: &str = "LLM_BASE_URL";
+const LLM_API_KEY_KEY: &str = "LLM_API_KEY";
+const LLM_TIMEOUT_SECS_KEY: &str = "LLM_TIMEOUT_SECS";
+const LLM_TOKEN_BUDGET_KEY: &str = "LLM_TOKEN_BUDGET";
+const LLM_PROMPT_PRICE_PER_MTOK_KEY: &str = "LLM_PROMPT_PRICE_PER_MTOK";
+const LLM_COMPLETION_PRICE_PER_MTOK_KEY: &str = "LLM_COMPLETION_PRICE_PER_MTOK";
+const QUOTA_LLM_USD_PER_MONTH_KEY: &str = "QUOTA_LLM_USD_PER_MONTH";
+const QUOTA_GENERATIONS_PER_MONTH_KEY: &str = "QUOTA_GENERATIONS_PER_MONTH";
+const QUOTA_WEBSITES_PER_MONTH_KEY: &str = "QUOTA_WEBSITES_PER_MONTH";
+const ADMIN_USER_IDS_KEY: &str = "ADMIN_USER_IDS";
+const OPENAI_API_KEY_KEY: &str = "OPENAI_API_KEY";
+const ANTHROPIC_API_KEY_KEY: &str = "ANTHROPIC_API_KEY";
+
+const DEFAULT_STATIC_SITE_OUTPUT_DIR: &str = "generated_sites";
+const DEFAULT_LOVABLE_SESSION_IDLE_SECS: u64 = 900;
+const DEFAULT_OBJECT_STORAGE_DIR: &str = "object_storage";
+const DEFAULT_PREVIEW_BANNER_TEXT: &str = "Website preview by Mithrilforge";
+const DEFAULT_LLM_MODEL: &str = "o3";
+const DEFAULT_LLM_TIMEOUT_SECS: u64 = 300;
+const DEFAULT_LLM_TOKEN_BUDGET: usize = 60_000;
 
 fn get_from_env_or_settings(settings: &config::Config, key: &str) -> String {
     env::var(key).unwrap_or_else(|_| {
@@ -27,6 +120,178 @@ fn get_from_env_or_settings(settings: &config::Config, key: &str) -> String {
     })
 }
 
+fn get_optional_from_env_or_settings(settings: &config::Config, key: &str) -> Option<String> {
+    env::var(key).ok().or_else(|| settings.get(key).ok())
+}
+
+fn llm_config_from_env_or_settings(settings: &config::Config) -> anyhow::Result<LlmConfig> {
+    let provider: LlmProvider = get_optional_from_env_or_settings(settings, LLM_PROVIDER_KEY)
+        .as_deref()
+        .unwrap_or("openai")
+        .parse()?;
+    let model = get_optional_from_env_or_settings(settings, LLM_MODEL_KEY)
+        .unwrap_or_else(|| DEFAULT_LLM_MODEL.to_string());
+    let temperature = get_optional_from_env_or_settings(settings, LLM_TEMPERATURE_KEY)
+        .map(|value| value.parse())
+        .transpose()
+        .with_context(|| format!("{LLM_TEMPERATURE_KEY} must be a number"))?;
+    let base_url = get_optional_from_env_or_settings(settings, LLM_BASE_URL_KEY);
+    let provider_api_key = match provider {
+        LlmProvider::OpenAi => env::var(OPENAI_API_KEY_KEY).ok(),
+        LlmProvider::Anthropic => env::var(ANTHROPIC_API_KEY_KEY).ok(),
+        LlmProvider::OpenAiCompatible => None,
+    };
+    let api_key = get_optional_from_env_or_settings(settings, LLM_API_KEY_KEY).or(provider_api_key);
+    let timeout_secs = get_optional_from_env_or_settings(settings, LLM_TIMEOUT_SECS_KEY)
+        .map(|value| value.parse())
+        .transpose()
+        .with_context(|| format!("{LLM_TIMEOUT_SECS_KEY} must be a number of seconds"))?
+        .unwrap_or(DEFAULT_LLM_TIMEOUT_SECS);
+    let token_budget = get_optional_from_env_or_settings(settings, LLM_TOKEN_BUDGET_KEY)
+        .map(|value| value.parse())
+        .transpose()
+        .with_context(|| format!("{LLM_TOKEN_BUDGET_KEY} must be a number of tokens"))?
+        .unwrap_or(DEFAULT_LLM_TOKEN_BUDGET);
+    let price = |key: &str| {
+        get_optional_from_env_or_settings(settings, key)
+            .map(|value| value.parse::<f64>())
+            .transpose()
+            .with_context(|| format!("{key} must be a price in USD per million tokens"))
+    };
+    let pricing = match (
+        price(LLM_PROMPT_PRICE_PER_MTOK_KEY)?,
+        price(LLM_COMPLETION_PRICE_PER_MTOK_KEY)?,
+    ) {
+        (Some(prompt_per_mtok), Some(completion_per_mtok)) => Some(LlmPricing {
+            prompt_per_mtok,
+            completion_per_mtok,
+        }),
+        (None, None) => None,
+        _ => anyhow::bail!(
+            "{LLM_PROMPT_PRICE_PER_MTOK_KEY} and {LLM_COMPLETION_PRICE_PER_MTOK_KEY} must be set together"
+        ),
+    };
+    Ok(LlmConfig {
+        provider,
+        model,
+        temperature,
+        base_url,
+        api_key,
+        timeout: Duration::from_secs(timeout_secs),
+        token_budget,
+        pricing,
+    })
+}
+
+/// Reads the [BuilderScript] from the file at `SITE_BUILDER_SCRIPT`, or else the bundled Lovable
+/// one.
+fn builder_script_from_env_or_settings(settings: &config::Config) -> anyhow::Result<BuilderScript> {
+    let script = match get_optional_from_env_or_settings(settings, SITE_BUILDER_SCRIPT_KEY) {
+        Some(path) => fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {SITE_BUILDER_SCRIPT_KEY} {path}"))?,
+        None => LOVABLE_SCRIPT.to_string(),
+    };
+    let script: BuilderScript = serde_json::from_str(&script)
+        .with_context(|| format!("invalid {SITE_BUILDER_SCRIPT_KEY}"))?;
+    script
+        .validate()
+        .map_err(|e| anyhow::anyhow!("invalid {SITE_BUILDER_SCRIPT_KEY}: {e}"))?;
+    Ok(script)
+}
+
+/// Lovable credentials are only required when Lovable generates the sites.
+fn site_generator_from_env_or_settings(
+    settings: &config::Config,
+) -> anyhow::Result<SiteGeneratorConfig> {
+    let site_generator = get_optional_from_env_or_settings(settings, SITE_GENERATOR_KEY)
+        .unwrap_or_else(|| "lovable".to_string());
+    match site_generator.as_str() {
+        "lovable" => Ok(SiteGeneratorConfig::Lovable {
+            user: get_from_env_or_settings(settings, LOVABLE_USER_KEY),
+            password: get_from_env_or_settings(settings, LOVABLE_PASSWORD_KEY),
+            script: builder_script_from_env_or_settings(settings)?,
+            max_sessions: get_optional_from_env_or_settings(settings, LOVABLE_MAX_SESSIONS_KEY)
+                .map(|value| value.parse())
+                .transpose()
+                .with_context(|| format!("{LOVABLE_MAX_SESSIONS_KEY} must be a positive number"))?
+                .unwrap_or(NonZeroUsize::MIN),
+            session_idle_timeout: Duration::from_secs(
+                get_optional_from_env_or_settings(settings, LOVABLE_SESSION_IDLE_SECS_KEY)
+                    .map(|value| value.parse())
+                    .transpose()
+                    .with_context(|| {
+                        format!("{LOVABLE_SESSION_IDLE_SECS_KEY} must be a number of seconds")
+                    })?
+                    .unwrap_or(DEFAULT_LOVABLE_SESSION_IDLE_SECS),
+            ),
+        }),
+        "static" => Ok(SiteGeneratorConfig::Static {
+            llm_copy: get_optional_from_env_or_settings(settings, STATIC_SITE_LLM_COPY_KEY)
+                .map(|value| value.parse())
+                .transpose()
+                .with_context(|| format!("{STATIC_SITE_LLM_COPY_KEY} must be true or false"))?
+                .unwrap_or(true),
+        }),
+        other => anyhow::bail!("unknown {SITE_GENERATOR_KEY} {other}, expected lovable or static"),
+    }
+}
+
+fn notifier_from_env_or_settings(settings: &config::Config) -> anyhow::Result<NotifierConfig> {
+    let notifier = get_optional_from_env_or_settings(settings, EVENT_NOTIFIER_KEY)
+        .unwrap_or_else(|| "local".to_string());
+    match notifier.as_str() {
+        "local" => Ok(NotifierConfig::Local),
+        "postgres" => Ok(NotifierConfig::Postgres),
+        other => anyhow::bail!("unknown {EVENT_NOTIFIER_KEY} {other}, expected local or postgres"),
+    }
+}
+
+fn preview_config_from_env_or_settings(
+    settings: &config::Config,
+    server_port: &str,
+) -> anyhow::Result<PreviewConfig> {
+    let mut public_base_url = get_optional_from_env_or_settings(settings, PUBLIC_BASE_URL_KEY)
+        .unwrap_or_else(|| format!("http://localhost:{server_port}/"));
+    // Without the trailing slash, joining the preview path would replace the last segment.
+    if !public_base_url.ends_with('/') {
+        public_base_url.push('/');
+    }
+    let public_base_url = Url::parse(&public_base_url)
+        .with_context(|| format!("{PUBLIC_BASE_URL_KEY} must be a url"))?;
+    let ttl = get_optional_from_env_or_settings(settings, PREVIEW_TTL_DAYS_KEY)
+        .map(|value| value.parse())
+        .transpose()
+        .with_context(|| format!("{PREVIEW_TTL_DAYS_KEY} must be a number of days"))?
+        .map(TimeDelta::days);
+    Ok(PreviewConfig {
+        dir: get_optional_from_env_or_settings(settings, STATIC_SITE_OUTPUT_DIR_KEY)
+            .unwrap_or_else(|| DEFAULT_STATIC_SITE_OUTPUT_DIR.to_string())
+            .into(),
+        public_base_url,
+        ttl,
+        banner_text: get_optional_from_env_or_settings(settings, PREVIEW_BANNER_TEXT_KEY)
+            .unwrap_or_else(|| DEFAULT_PREVIEW_BANNER_TEXT.to_string()),
+    })
+}
+
+/// Quotas left out of the configuration are unlimited.
+fn quotas_from_env_or_settings(settings: &config::Config) -> anyhow::Result<Quotas> {
+    let limit = |key: &str| {
+        get_optional_from_env_or_settings(settings, key)
+            .map(|value| value.parse::<i64>())
+            .transpose()
+            .with_context(|| format!("{key} must be a whole number"))
+    };
+    Ok(Quotas {
+        llm_usd_per_month: get_optional_from_env_or_settings(settings, QUOTA_LLM_USD_PER_MONTH_KEY)
+            .map(|value| value.parse())
+            .transpose()
+            .with_context(|| format!("{QUOTA_LLM_USD_PER_MONTH_KEY} must be an amount in USD"))?,
+        generations_per_month: limit(QUOTA_GENERATIONS_PER_MONTH_KEY)?,
+        websites_per_month: limit(QUOTA_WEBSITES_PER_MONTH_KEY)?,
+    })
+}
+
 impl Config {
     pub fn from_config() -> anyhow::Result<Config> {
         let settings = config::Config::builder()
@@ -37,15 +302,43 @@ impl Config {
         let database_url = get_from_env_or_settings(&settings, DATABASE_URL_KEY);
         let jwks = get_from_env_or_settings(&settings, JWKS_KEY);
         let webdriver_address = get_from_env_or_settings(&settings, WEBDRIVER_ADDRESS_KEY);
-        let lovable_user = get_from_env_or_settings(&settings, LOVABLE_USER_KEY);
-        let lovable_password = get_from_env_or_settings(&settings, LOVABLE_PASSWORD_KEY);
+        let site_generator = site_generator_from_env_or_settings(&settings)?;
+        let notifier = notifier_from_env_or_settings(&settings)?;
+        let preview = preview_config_from_env_or_settings(&settings, &server_port)?;
+        let object_storage_dir =
+            get_optional_from_env_or_settings(&settings, OBJECT_STORAGE_DIR_KEY)
+                .unwrap_or_else(|| DEFAULT_OBJECT_STORAGE_DIR.to_string())
+                .into();
+        let render_javascript = get_optional_from_env_or_settings(&settings, RENDER_JAVASCRIPT_KEY)
+            .map(|value| value.parse())
+            .transpose()
+            .with_context(|| format!("{RENDER_JAVASCRIPT_KEY} must be true or false"))?
+            .unwrap_or(true);
+        let llm = llm_config_from_env_or_settings(&settings)?;
+        let quotas = quotas_from_env_or_settings(&settings)?;
+        let admin_user_ids = get_optional_from_env_or_settings(&settings, ADMIN_USER_IDS_KEY)
+            .map(|value| {
+                value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|id| !id.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
         Ok(Config {
             server_port,
             database_url,
             jwks,
             webdriver_address,
-            lovable_user,
-            lovable_password,
+            site_generator,
+            notifier,
+            preview,
+            object_storage_dir,
+            render_javascript,
+            llm,
+            quotas,
+            admin_user_ids,
         })
     }
 }
@@ -2,4 +2,17 @@
    Module `models` specifies the canonical data structures comprising the domain.
 */
 
+pub mod automation_failure;
+pub mod business_profile;
+pub mod event;
+pub mod landing_page;
+pub mod object_storage;
+pub mod outbox;
+pub mod page;
+pub mod preview;
+pub mod prompt;
+pub mod quota;
+pub mod screenshot;
+pub mod usage;
+pub mod webhook;
 pub mod website;
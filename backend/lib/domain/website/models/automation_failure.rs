@@ -0,0 +1,76 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// What was captured from the browser when a run of the site generator automation failed, to
+/// diagnose it without reproducing the run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AutomationFailure {
+    pub id: i64,
+    pub website_id: i64,
+    /// Identifies the automation run in the logs.
+    pub run_id: Uuid,
+    pub error: String,
+    /// The url the browser was on, `None` if it could not be read.
+    pub url: Option<String>,
+    pub has_page_source: bool,
+    pub has_screenshot: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The fields required to record an [AutomationFailure]. Artifacts the browser could not
+/// provide are `None`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecordAutomationFailureRequest {
+    pub website_id: i64,
+    pub run_id: Uuid,
+    pub error: String,
+    pub url: Option<String>,
+    pub page_source: Option<String>,
+    /// PNG image of the browser viewport.
+    pub screenshot: Option<Vec<u8>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AutomationArtifact {
+    /// PNG image of the browser viewport.
+    Screenshot,
+    /// HTML of the page the browser was on.
+    PageSource,
+}
+
+impl Display for AutomationArtifact {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AutomationArtifact::Screenshot => write!(f, "screenshot"),
+            AutomationArtifact::PageSource => write!(f, "page source"),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum RecordAutomationFailureError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum ListAutomationFailuresError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum GetAutomationArtifactError {
+    #[error("no {artifact} was captured for automation failure {id}")]
+    NotFound {
+        id: i64,
+        artifact: AutomationArtifact,
+    },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
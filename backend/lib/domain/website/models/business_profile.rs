@@ -0,0 +1,175 @@
+use std::str::FromStr;
+
+use phonenumber::{Mode, country};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use ts_rs::TS;
+use url::Url;
+
+/// What we know about the business behind a [super::website::Website], used to personalise
+/// pitches and generated pages.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Default, Deserialize, Serialize, TS)]
+pub struct BusinessProfile {
+    #[serde(default)]
+    pub address: Option<PostalAddress>,
+    /// Phone numbers in E.164 format, e.g. `+493012345678`.
+    #[serde(default)]
+    pub phone_numbers: Vec<String>,
+    #[serde(default)]
+    pub opening_hours: Vec<OpeningHours>,
+    /// Industry category, e.g. `Bakery` or `Dentist`.
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub services: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Default, Deserialize, Serialize, TS)]
+pub struct PostalAddress {
+    #[serde(default)]
+    pub street_address: Option<String>,
+    #[serde(default)]
+    pub postal_code: Option<String>,
+    #[serde(default)]
+    pub locality: Option<String>,
+    #[serde(default)]
+    pub region: Option<String>,
+    /// ISO 3166-1 alpha-2 code when known, otherwise the country name as found on the site.
+    #[serde(default)]
+    pub country: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum DayOfWeek {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl DayOfWeek {
+    pub const ALL: [DayOfWeek; 7] = [
+        DayOfWeek::Monday,
+        DayOfWeek::Tuesday,
+        DayOfWeek::Wednesday,
+        DayOfWeek::Thursday,
+        DayOfWeek::Friday,
+        DayOfWeek::Saturday,
+        DayOfWeek::Sunday,
+    ];
+}
+
+impl FromStr for DayOfWeek {
+    type Err = ();
+
+    /// Accepts full English names, schema.org urls (`https://schema.org/Monday`) and the two
+    /// letter abbreviations used by `openingHours` (`Mo`, `Tu`, ...).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let day = s.rsplit('/').next().unwrap_or(s).trim().to_lowercase();
+        let day = match day.get(..2) {
+            Some("mo") => DayOfWeek::Monday,
+            Some("tu") => DayOfWeek::Tuesday,
+            Some("we") => DayOfWeek::Wednesday,
+            Some("th") => DayOfWeek::Thursday,
+            Some("fr") => DayOfWeek::Friday,
+            Some("sa") => DayOfWeek::Saturday,
+            Some("su") => DayOfWeek::Sunday,
+            _ => return Err(()),
+        };
+        Ok(day)
+    }
+}
+
+/// The hours a business is open on a given day, as `HH:MM` local time.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, TS)]
+pub struct OpeningHours {
+    pub day_of_week: DayOfWeek,
+    pub opens: String,
+    pub closes: String,
+}
+
+impl BusinessProfile {
+    pub fn is_complete(&self) -> bool {
+        self.address.is_some()
+            && !self.phone_numbers.is_empty()
+            && !self.opening_hours.is_empty()
+            && self.category.is_some()
+            && !self.services.is_empty()
+    }
+
+    /// Fills the fields missing in `self` with the ones of `other`.
+    pub fn or(self, other: BusinessProfile) -> BusinessProfile {
+        fn or_vec<T>(a: Vec<T>, b: Vec<T>) -> Vec<T> {
+            if a.is_empty() { b } else { a }
+        }
+        BusinessProfile {
+            address: self.address.or(other.address),
+            phone_numbers: or_vec(self.phone_numbers, other.phone_numbers),
+            opening_hours: or_vec(self.opening_hours, other.opening_hours),
+            category: self.category.or(other.category),
+            services: or_vec(self.services, other.services),
+        }
+    }
+
+    /// Normalises every phone number to E.164, dropping the ones that are not valid. National
+    /// numbers are resolved with the country of the address, or else the country code top level
+    /// domain of `source_address`.
+    pub fn with_normalized_phone_numbers(self, source_address: &Url) -> BusinessProfile {
+        let region = self
+            .address
+            .as_ref()
+            .and_then(|address| address.country.as_deref())
+            .and_then(|country| country::Id::from_str(&country.to_uppercase()).ok())
+            .or_else(|| {
+                let tld = source_address.host_str()?.rsplit('.').next()?;
+                let code = match tld {
+                    "uk" => "GB".to_string(),
+                    tld => tld.to_uppercase(),
+                };
+                country::Id::from_str(&code).ok()
+            });
+        let mut phone_numbers: Vec<String> = Vec::new();
+        for raw in self.phone_numbers.iter() {
+            if let Some(number) = normalize_phone_number(raw, region)
+                && !phone_numbers.contains(&number)
+            {
+                phone_numbers.push(number);
+            }
+        }
+        BusinessProfile {
+            phone_numbers,
+            ..self
+        }
+    }
+}
+
+/// Formats `raw` as E.164, or `None` if it is not a valid phone number.
+pub fn normalize_phone_number(raw: &str, region: Option<country::Id>) -> Option<String> {
+    let raw = raw.trim().trim_start_matches("tel:");
+    let raw = match raw.strip_prefix("00") {
+        Some(international) => format!("+{international}"),
+        None => raw.to_string(),
+    };
+    let number = phonenumber::parse(region, &raw).ok()?;
+    phonenumber::is_valid(&number).then(|| number.format().mode(Mode::E164).to_string())
+}
+
+#[derive(Debug, Error)]
+pub enum ExtractBusinessProfileError {
+    #[error("failed to get the business profile from the llm")]
+    FailedToFetchProfile,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum UpdateBusinessProfileError {
+    #[error("website with id {id} not found")]
+    NotFound { id: i64 },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
@@ -0,0 +1,38 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use ts_rs::TS;
+
+use super::website::WebsiteEvent;
+
+/// Version of the [EventEnvelope] and [WebsiteEvent] schema. Bump it on changes clients have to
+/// handle.
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
+
+/// A [WebsiteEvent] as kept in the event log and sent to clients. Event ids only grow, so
+/// clients can ask for the events after the last one they saw.
+#[derive(Clone, Debug, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct EventEnvelope {
+    #[ts(type = "number")]
+    pub event_id: i64,
+    pub schema_version: u32,
+    pub occurred_at: DateTime<Utc>,
+    #[ts(type = "number")]
+    pub website_id: i64,
+    /// The owner of the website, `None` for websites added before owners were tracked.
+    pub tenant: Option<String>,
+    pub event: WebsiteEvent,
+}
+
+#[derive(Debug, Error)]
+pub enum AppendEventError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum GetEventsError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
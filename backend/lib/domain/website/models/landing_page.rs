@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Marketing copy for a generated landing page.
+#[derive(Clone, Debug, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub struct LandingPageCopy {
+    pub headline: String,
+    pub subheadline: String,
+    /// A short paragraph about the business.
+    pub about: String,
+    /// Label of the primary call to action button.
+    pub call_to_action: String,
+    #[serde(default)]
+    pub services: Vec<ServiceCopy>,
+    /// Version of the [super::prompt::PromptName::LandingPageCopy] prompt that wrote the copy.
+    #[serde(default)]
+    pub prompt_version: Option<i32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ServiceCopy {
+    pub name: String,
+    pub description: String,
+}
+
+#[derive(Debug, Error)]
+pub enum WriteCopyError {
+    #[error("failed to get the landing page copy from the llm")]
+    FailedToWriteCopy,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
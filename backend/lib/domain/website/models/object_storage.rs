@@ -0,0 +1,21 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum PutObjectError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum GetObjectError {
+    #[error("object {key} not found")]
+    NotFound { key: String },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum DeleteObjectError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
@@ -0,0 +1,20 @@
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+use super::website::WebsiteEvent;
+
+/// A [WebsiteEvent] written in the same transaction as the change it describes, waiting to be
+/// published.
+#[derive(Clone, Debug)]
+pub struct OutboxEntry {
+    /// Entries are committed in id order.
+    pub id: i64,
+    pub event: WebsiteEvent,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Error)]
+pub enum OutboxError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
@@ -0,0 +1,118 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use url::Url;
+
+use super::website::RenderMode;
+
+/// A single page downloaded while crawling a [super::website::Website], kept between runs so
+/// that re-crawls can be conditional and re-extractions can skip the network entirely.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PageSnapshot {
+    pub url: Url,
+    pub status: u16,
+    pub headers: BTreeMap<String, String>,
+    pub body_hash: String,
+    pub body: String,
+    pub fetched_at: DateTime<Utc>,
+    /// Whether `body` is the DOM rendered by the browser rather than the served HTML.
+    pub rendered: bool,
+}
+
+impl PageSnapshot {
+    pub fn new(url: Url, status: u16, headers: BTreeMap<String, String>, body: String) -> Self {
+        Self {
+            url,
+            status,
+            headers,
+            body_hash: hash_body(&body),
+            body,
+            fetched_at: Utc::now(),
+            rendered: false,
+        }
+    }
+
+    /// Replaces the served HTML with the DOM rendered by the browser.
+    pub fn with_rendered_body(self, body: String) -> Self {
+        Self {
+            body_hash: hash_body(&body),
+            body,
+            rendered: true,
+            ..self
+        }
+    }
+
+    /// The `ETag` validator returned by the server, if any.
+    pub fn etag(&self) -> Option<&str> {
+        self.headers.get("etag").map(String::as_str)
+    }
+
+    /// The `Last-Modified` validator returned by the server, if any.
+    pub fn last_modified(&self) -> Option<&str> {
+        self.headers.get("last-modified").map(String::as_str)
+    }
+}
+
+/// Hex encoded SHA-256 of a page body.
+pub fn hash_body(body: &str) -> String {
+    format!("{:x}", Sha256::digest(body.as_bytes()))
+}
+
+/// The result of crawling a website: every internal page plus the external links found on the
+/// landing page.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CrawledWebsite {
+    pub pages: Vec<PageSnapshot>,
+    pub external_links: Vec<Url>,
+    pub render_mode: RenderMode,
+}
+
+impl CrawledWebsite {
+    /// One section per page, followed by a section listing the related links.
+    pub fn sections(&self) -> Vec<String> {
+        let mut sections: Vec<String> = self
+            .pages
+            .iter()
+            .map(|page| format!("==== {} ==== \n{}\n\n", page.url, page.body))
+            .collect();
+        let mut related_links = String::from("\n\n==== Related links ==== \n");
+        for external_link in self.external_links.iter() {
+            related_links.push_str(external_link.as_ref());
+            related_links.push('\n');
+        }
+        sections.push(related_links);
+        sections
+    }
+
+    /// Concatenates every page and the related links into the text sent for contact extraction.
+    pub fn full_content(&self) -> String {
+        self.sections().concat()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CrawlWebsiteError {
+    #[error("invalid url {source_address}")]
+    InvalidUrl { source_address: String },
+    #[error("failed to fetch content from {url}")]
+    FailedToFetchContent { url: Url },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum GetPagesError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum SavePagesError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    #[error("failed to start transaction")]
+    FailedTransaction(sqlx::Error),
+}
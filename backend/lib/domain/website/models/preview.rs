@@ -0,0 +1,57 @@
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+/// A generated site hosted under `/preview/{slug}/`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Preview {
+    pub slug: String,
+    pub website_id: i64,
+    pub created_at: DateTime<Utc>,
+    /// `None` if the preview never expires.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl Preview {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+/// The fields required to host a generated site. Hosting a slug again replaces its expiry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CreatePreviewRequest {
+    pub slug: String,
+    pub website_id: i64,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Error)]
+pub enum CreatePreviewError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum ListPreviewsError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum DeletePreviewError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum GetPreviewError {
+    #[error("preview {slug} not found")]
+    NotFound { slug: String },
+    #[error("preview {slug} expired at {expires_at}")]
+    Expired {
+        slug: String,
+        expires_at: DateTime<Utc>,
+    },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
@@ -0,0 +1,193 @@
+use std::{collections::BTreeMap, fmt, str::FromStr, sync::LazyLock};
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use ts_rs::TS;
+
+use super::website::Website;
+
+static VARIABLE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{([a-z_]+)\}").unwrap());
+
+/// The prompts sent to the LLM and the site builder.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize, sqlx::Type, TS,
+)]
+#[sqlx(type_name = "prompt_name", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PromptName {
+    ContactExtraction,
+    BusinessProfileExtraction,
+    LandingPage,
+    LandingPageCopy,
+}
+
+impl PromptName {
+    pub const ALL: [PromptName; 4] = [
+        PromptName::ContactExtraction,
+        PromptName::BusinessProfileExtraction,
+        PromptName::LandingPage,
+        PromptName::LandingPageCopy,
+    ];
+
+    /// The variables a template of this prompt may reference, as `{name}`.
+    pub fn variables(&self) -> &'static [&'static str] {
+        match self {
+            PromptName::ContactExtraction | PromptName::BusinessProfileExtraction => {
+                &["website_source_address", "website_content"]
+            }
+            PromptName::LandingPage | PromptName::LandingPageCopy => &[
+                "website_source_address",
+                "contact_name",
+                "business_category",
+                "services",
+            ],
+        }
+    }
+
+    /// The template the prompt store is seeded with, from the `prompts` directory.
+    pub fn default_template(&self) -> &'static str {
+        match self {
+            PromptName::ContactExtraction => include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/prompts/contact_extraction.txt"
+            )),
+            PromptName::BusinessProfileExtraction => include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/prompts/business_profile_extraction.txt"
+            )),
+            PromptName::LandingPage => include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/prompts/landing_page.txt"
+            )),
+            PromptName::LandingPageCopy => include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/prompts/landing_page_copy.txt"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for PromptName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PromptName::ContactExtraction => write!(f, "contact_extraction"),
+            PromptName::BusinessProfileExtraction => write!(f, "business_profile_extraction"),
+            PromptName::LandingPage => write!(f, "landing_page"),
+            PromptName::LandingPageCopy => write!(f, "landing_page_copy"),
+        }
+    }
+}
+
+impl FromStr for PromptName {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PromptName::ALL
+            .into_iter()
+            .find(|name| name.to_string() == s)
+            .ok_or(())
+    }
+}
+
+/// A version of a prompt. Editing a prompt stores a new version, the latest one is used.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PromptTemplate {
+    pub name: PromptName,
+    pub version: i32,
+    pub template: String,
+    /// The admin who stored this version, `None` for the seeded ones.
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PromptTemplate {
+    /// Replaces every `{variable}` with its value in `variables`. Variables without a value are
+    /// replaced with an empty string.
+    pub fn render(&self, variables: &BTreeMap<&str, String>) -> String {
+        VARIABLE
+            .replace_all(&self.template, |captures: &regex::Captures| {
+                variables.get(&captures[1]).cloned().unwrap_or_default()
+            })
+            .into_owned()
+    }
+}
+
+/// The variables of the [PromptName::LandingPage] and [PromptName::LandingPageCopy] prompts,
+/// taken from the stored contact and business profile of `website`.
+pub fn landing_page_variables(website: &Website) -> BTreeMap<&'static str, String> {
+    let business_profile = website.business_profile.clone().unwrap_or_default();
+    BTreeMap::from([
+        ("website_source_address", website.source_address.clone()),
+        (
+            "contact_name",
+            website.contact_name.clone().unwrap_or_default(),
+        ),
+        (
+            "business_category",
+            business_profile.category.unwrap_or_default(),
+        ),
+        ("services", business_profile.services.join(", ")),
+    ])
+}
+
+/// The fields required to store a new version of a prompt.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CreatePromptVersionRequest {
+    pub name: PromptName,
+    pub template: String,
+    pub created_by: String,
+}
+
+impl CreatePromptVersionRequest {
+    /// # Errors
+    ///
+    /// - [CreatePromptVersionError::UnknownVariable] if `template` references a variable that
+    ///   `name` does not provide.
+    pub fn new(
+        name: PromptName,
+        template: &str,
+        created_by: &str,
+    ) -> Result<Self, CreatePromptVersionError> {
+        if template.trim().is_empty() {
+            return Err(CreatePromptVersionError::Empty);
+        }
+        if let Some(variable) = VARIABLE
+            .captures_iter(template)
+            .map(|captures| captures[1].to_string())
+            .find(|variable| !name.variables().contains(&variable.as_str()))
+        {
+            return Err(CreatePromptVersionError::UnknownVariable { name, variable });
+        }
+        Ok(Self {
+            name,
+            template: template.to_string(),
+            created_by: created_by.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum GetPromptError {
+    #[error("prompt {name} not found")]
+    NotFound { name: PromptName },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum ListPromptsError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum CreatePromptVersionError {
+    #[error("prompt template is empty")]
+    Empty,
+    #[error("prompt {name} has no variable {variable}")]
+    UnknownVariable { name: PromptName, variable: String },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
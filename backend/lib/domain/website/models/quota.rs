@@ -0,0 +1,105 @@
+use std::fmt;
+
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+use thiserror::Error;
+
+/// Monthly limits applied to every owner. `None` means unlimited.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct Quotas {
+    /// Estimated LLM spend in USD.
+    pub llm_usd_per_month: Option<f64>,
+    /// Site generations started.
+    pub generations_per_month: Option<i64>,
+    /// Websites added.
+    pub websites_per_month: Option<i64>,
+}
+
+/// What an owner consumed since the start of the current month.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct MonthlyUsage {
+    pub llm_usd: f64,
+    pub generations: i64,
+    pub websites: i64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuotaKind {
+    LlmSpend,
+    Generations,
+    Websites,
+}
+
+impl fmt::Display for QuotaKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuotaKind::LlmSpend => write!(f, "llm spend"),
+            QuotaKind::Generations => write!(f, "generations"),
+            QuotaKind::Websites => write!(f, "websites"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Error)]
+#[error("monthly {kind} quota exceeded, used {used} of {limit}")]
+pub struct QuotaExceeded {
+    pub kind: QuotaKind,
+    pub limit: f64,
+    pub used: f64,
+}
+
+impl Quotas {
+    /// Fails with the first of `kinds` whose limit `usage` has reached, i.e. when one more
+    /// website, generation or LLM call would go over the quota.
+    pub fn check(&self, usage: &MonthlyUsage, kinds: &[QuotaKind]) -> Result<(), QuotaExceeded> {
+        for kind in kinds {
+            let (limit, used) = match kind {
+                QuotaKind::LlmSpend => (self.llm_usd_per_month, usage.llm_usd),
+                QuotaKind::Generations => (
+                    self.generations_per_month.map(|limit| limit as f64),
+                    usage.generations as f64,
+                ),
+                QuotaKind::Websites => (
+                    self.websites_per_month.map(|limit| limit as f64),
+                    usage.websites as f64,
+                ),
+            };
+            if let Some(limit) = limit
+                && used >= limit
+            {
+                return Err(QuotaExceeded {
+                    kind: *kind,
+                    limit,
+                    used,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Midnight UTC of the first day of the month of `now`, when monthly quotas reset.
+pub fn start_of_month(now: DateTime<Utc>) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0)
+        .single()
+        .unwrap_or(now)
+}
+
+#[derive(Debug, Error)]
+pub enum CheckQuotaError {
+    #[error(transparent)]
+    Exceeded(#[from] QuotaExceeded),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum GetMonthlyUsageError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum RecordGenerationError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
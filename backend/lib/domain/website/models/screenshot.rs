@@ -0,0 +1,148 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use ts_rs::TS;
+
+/// Which site of a [super::website::Website] a [Screenshot] shows.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize, sqlx::Type,
+)]
+#[sqlx(type_name = "screenshot_subject", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ScreenshotSubject {
+    /// The site at the source address.
+    Original,
+    /// The site we generated for it.
+    Generated,
+}
+
+impl Display for ScreenshotSubject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScreenshotSubject::Original => write!(f, "original"),
+            ScreenshotSubject::Generated => write!(f, "generated"),
+        }
+    }
+}
+
+/// The browser window size a [Screenshot] is taken at.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize, sqlx::Type, TS,
+)]
+#[sqlx(type_name = "viewport", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum Viewport {
+    Desktop,
+    Mobile,
+}
+
+impl Viewport {
+    pub const ALL: [Viewport; 2] = [Viewport::Desktop, Viewport::Mobile];
+
+    /// Width and height in CSS pixels.
+    pub fn size(&self) -> (u32, u32) {
+        match self {
+            Viewport::Desktop => (1440, 900),
+            Viewport::Mobile => (390, 844),
+        }
+    }
+}
+
+impl Display for Viewport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Viewport::Desktop => write!(f, "desktop"),
+            Viewport::Mobile => write!(f, "mobile"),
+        }
+    }
+}
+
+/// A PNG screenshot and its thumbnail, as captured by the browser.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CapturedScreenshot {
+    pub image: Vec<u8>,
+    pub thumbnail: Vec<u8>,
+}
+
+/// A screenshot of a site, whose images are kept in the object storage. Capturing it again
+/// replaces it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Screenshot {
+    pub website_id: i64,
+    pub subject: ScreenshotSubject,
+    pub viewport: Viewport,
+    /// The url the screenshot was taken of.
+    pub url: String,
+    pub image_key: String,
+    pub thumbnail_key: String,
+    pub captured_at: DateTime<Utc>,
+}
+
+/// The fields required to store a [Screenshot] whose images were put in the object storage.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SaveScreenshotRequest {
+    pub website_id: i64,
+    pub subject: ScreenshotSubject,
+    pub viewport: Viewport,
+    pub url: String,
+    pub image_key: String,
+    pub thumbnail_key: String,
+}
+
+impl Screenshot {
+    /// The object storage keys of the image and thumbnail of a screenshot.
+    pub fn keys(
+        website_id: i64,
+        subject: ScreenshotSubject,
+        viewport: Viewport,
+    ) -> (String, String) {
+        let prefix = format!("screenshots/{website_id}/{subject}-{viewport}");
+        (format!("{prefix}.png"), format!("{prefix}-thumbnail.png"))
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CaptureScreenshotError {
+    #[error("failed to capture a screenshot of {url}")]
+    FailedToCapture {
+        url: String,
+        #[source]
+        source: anyhow::Error,
+    },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum SaveScreenshotError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum GetScreenshotsError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum GetComparisonError {
+    #[error("website with id {id} not found")]
+    NotFound { id: i64 },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum GetScreenshotImageError {
+    #[error("no {viewport} screenshot of the {subject} site of website {website_id}")]
+    NotFound {
+        website_id: i64,
+        subject: ScreenshotSubject,
+        viewport: Viewport,
+    },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
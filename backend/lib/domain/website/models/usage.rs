@@ -0,0 +1,99 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use ts_rs::TS;
+
+/// How an LLM call made while processing a [super::website::Website] ended.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, sqlx::Type,
+)]
+#[sqlx(type_name = "llm_call_outcome", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum LlmCallOutcome {
+    Success,
+    /// The model answered without calling the requested tool.
+    NoToolCall,
+    /// The request failed or the provider returned an error.
+    Failed,
+}
+
+/// A single LLM call, recorded to account for the cost of each lead.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LlmUsage {
+    pub website_id: i64,
+    pub owner_id: Option<String>,
+    /// What the call was for, e.g. the name of the tool the model was asked to call.
+    pub operation: String,
+    pub provider: String,
+    pub model: String,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub latency_ms: i64,
+    /// `None` when no pricing is configured for the model.
+    pub estimated_cost_usd: Option<f64>,
+    pub outcome: LlmCallOutcome,
+}
+
+/// The usage of `owner_id` between `from` (inclusive) and `to` (exclusive).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GetUsageRequest {
+    pub owner_id: String,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+}
+
+/// Aggregated LLM usage.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, TS)]
+pub struct UsageTotals {
+    #[ts(type = "number")]
+    pub calls: i64,
+    #[ts(type = "number")]
+    pub failed_calls: i64,
+    #[ts(type = "number")]
+    pub prompt_tokens: i64,
+    #[ts(type = "number")]
+    pub completion_tokens: i64,
+    pub estimated_cost_usd: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, TS)]
+pub struct WebsiteUsage {
+    #[ts(type = "number")]
+    pub website_id: i64,
+    pub source_address: String,
+    #[serde(flatten)]
+    pub totals: UsageTotals,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, TS)]
+pub struct ModelUsage {
+    pub provider: String,
+    pub model: String,
+    #[serde(flatten)]
+    pub totals: UsageTotals,
+}
+
+/// LLM usage of an owner over a date range, overall and broken down by website and by model.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, TS)]
+pub struct UsageReport {
+    pub total: UsageTotals,
+    pub websites: Vec<WebsiteUsage>,
+    pub models: Vec<ModelUsage>,
+}
+
+#[derive(Debug, Error)]
+pub enum RecordUsageError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum GetUsageError {
+    #[error("invalid range, {from} is not before {to}")]
+    InvalidRange {
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
@@ -0,0 +1,232 @@
+use std::{net::IpAddr, time::Duration};
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use thiserror::Error;
+use ts_rs::TS;
+use url::Url;
+use uuid::Uuid;
+
+use super::website::WebsiteEventType;
+
+/// How long to wait before retrying a failed delivery, after each failed attempt. Deliveries
+/// still failing after the last one are given up.
+pub const RETRY_DELAYS: [Duration; 6] = [
+    Duration::from_secs(10),
+    Duration::from_secs(60),
+    Duration::from_secs(5 * 60),
+    Duration::from_secs(30 * 60),
+    Duration::from_secs(2 * 60 * 60),
+    Duration::from_secs(6 * 60 * 60),
+];
+
+/// An endpoint of a tenant that website events are POSTed to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Webhook {
+    pub id: i64,
+    /// The owner of the websites whose events are delivered.
+    pub tenant: String,
+    pub url: Url,
+    pub event_types: Vec<WebsiteEventType>,
+    /// Key of the HMAC-SHA256 signature of every delivery.
+    pub secret: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Webhook {
+    /// Signs a delivery of `body` sent at `timestamp`, in seconds since the epoch. Receivers
+    /// compute the HMAC-SHA256 of `{timestamp}.{body}` with the secret and compare.
+    pub fn signature(&self, timestamp: i64, body: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.secret.as_bytes())
+            .expect("HMAC accepts keys of any size");
+        mac.update(format!("{timestamp}.{body}").as_bytes());
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+}
+
+/// Whether deliveries may be sent to `address`. Loopback, private, link-local and other
+/// addresses not reachable from the internet are refused, so webhooks cannot reach the internal
+/// network of the server.
+pub fn is_public_address(address: IpAddr) -> bool {
+    match address {
+        IpAddr::V4(v4) => {
+            let [first, second, ..] = v4.octets();
+            !(v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_documentation()
+                || v4.is_multicast()
+                // Shared address space, 100.64.0.0/10.
+                || (first == 100 && second & 0xc0 == 64)
+                || first == 0)
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_public_address(IpAddr::V4(v4));
+            }
+            let first = v6.segments()[0];
+            !(v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                // Unique local, fc00::/7.
+                || first & 0xfe00 == 0xfc00
+                // Link-local, fe80::/10.
+                || first & 0xffc0 == 0xfe80)
+        }
+    }
+}
+
+/// The fields required to create a [Webhook].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CreateWebhookRequest {
+    pub tenant: String,
+    pub url: Url,
+    pub event_types: Vec<WebsiteEventType>,
+    pub secret: String,
+}
+
+impl CreateWebhookRequest {
+    /// A random secret is generated when `secret` is `None`.
+    pub fn new(
+        tenant: &str,
+        url: Url,
+        event_types: Vec<WebsiteEventType>,
+        secret: Option<String>,
+    ) -> Self {
+        Self {
+            tenant: tenant.to_string(),
+            url,
+            event_types,
+            secret: secret.unwrap_or_else(|| {
+                format!(
+                    "whsec_{}{}",
+                    Uuid::new_v4().simple(),
+                    Uuid::new_v4().simple()
+                )
+            }),
+        }
+    }
+}
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, sqlx::Type, TS,
+)]
+#[sqlx(type_name = "webhook_delivery_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    /// Not sent yet, or to be retried.
+    Pending,
+    Succeeded,
+    /// Every attempt failed.
+    Failed,
+}
+
+/// One POST of an event, or of a [WebhookPing], to a [Webhook], with the outcome of its last
+/// attempt.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub webhook_id: i64,
+    /// `None` for the deliveries of [WebhookPing]s.
+    pub event_id: Option<i64>,
+    /// The delivery this one replays.
+    pub replay_of: Option<i64>,
+    /// The JSON body, sent as is.
+    pub payload: String,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    /// `None` while the delivery is not scheduled.
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub last_response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+impl WebhookDelivery {
+    /// When to retry when the next attempt fails, `None` to give up. Pings are only attempted
+    /// once.
+    pub fn retry_at(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.event_id?;
+        let delay = RETRY_DELAYS.get(usize::try_from(self.attempts).ok()?)?;
+        Some(now + *delay)
+    }
+}
+
+/// The body of the deliveries sent to check that a [Webhook] is reachable.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, TS)]
+#[serde(tag = "type")]
+#[ts(export)]
+pub enum WebhookPing {
+    Ping {
+        #[ts(type = "number")]
+        webhook_id: i64,
+        occurred_at: DateTime<Utc>,
+    },
+}
+
+/// The fields required to create a [WebhookDelivery] that is not scheduled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CreateWebhookDeliveryRequest {
+    pub webhook_id: i64,
+    pub event_id: Option<i64>,
+    pub replay_of: Option<i64>,
+    pub payload: String,
+}
+
+/// The outcome of sending a [WebhookDelivery].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WebhookAttempt {
+    pub status: DeliveryStatus,
+    pub response_status: Option<u16>,
+    pub error: Option<String>,
+    pub next_attempt_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Error)]
+pub enum CreateWebhookError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum ListWebhooksError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum GetWebhookError {
+    #[error("webhook {id} not found")]
+    NotFound { id: i64 },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum GetWebhookDeliveryError {
+    #[error("webhook delivery {id} not found")]
+    NotFound { id: i64 },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum WebhookDeliveryError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum SendWebhookError {
+    #[error("webhook responded with status {status}")]
+    Rejected { status: u16 },
+    #[error("webhook host resolves to the non-public address {address}")]
+    ForbiddenAddress { address: IpAddr },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
@@ -1,55 +1,321 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use ts_rs::TS;
 use url::Url;
 
+use super::{business_profile::BusinessProfile, quota::QuotaExceeded};
+
 /// A uniquely identifiable website
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Default)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, Default, TS)]
 pub struct Website {
+    #[ts(type = "number")]
     pub id: i64,
     pub source_address: String,
+    /// The user who added the website, `None` for websites added before owners were tracked.
+    pub owner_id: Option<String>,
     pub contact_email: Option<String>,
     pub contact_name: Option<String>,
+    pub contact_phone: Option<String>,
+    pub contact_email_method: Option<ExtractionMethod>,
+    pub contact_name_method: Option<ExtractionMethod>,
+    pub contact_phone_method: Option<ExtractionMethod>,
+    pub contact_prompt_version: Option<i32>,
+    pub social_links: Option<Social>,
     pub generated_website_link: Option<String>,
     pub generated_website_name: Option<String>,
+    pub generated_website_prompt_version: Option<i32>,
+    pub render_mode: Option<RenderMode>,
+    pub business_profile: Option<BusinessProfile>,
+}
+
+/// How the pages of a [Website] were obtained by the last crawl.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, sqlx::Type, TS,
+)]
+#[sqlx(type_name = "render_mode", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum RenderMode {
+    /// Plain HTTP requests, the HTML is used as served.
+    Static,
+    /// The static HTML had too little visible text, so pages were rendered in the browser.
+    Browser,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, TS)]
 pub struct ContactEvent {
+    #[ts(type = "number")]
     pub website_id: i64,
     pub contact: Contact,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, TS)]
 pub struct GeneratedWebsiteEvent {
+    #[ts(type = "number")]
     pub website_id: i64,
     pub generated_website: GeneratedWebsite,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, TS)]
+pub struct WebsiteDeletedEvent {
+    #[ts(type = "number")]
+    pub website_id: i64,
+}
+
+/// A step of the pipeline run for every added or refreshed [Website].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStage {
+    Crawl,
+    ContactExtraction,
+    BusinessProfile,
+    Generation,
+}
+
+impl PipelineStage {
+    /// Shown to users when the stage fails for a reason they cannot act on.
+    pub fn failure_message(&self) -> &'static str {
+        match self {
+            PipelineStage::Crawl => "the website could not be crawled",
+            PipelineStage::ContactExtraction => "the contact details could not be extracted",
+            PipelineStage::BusinessProfile => "the business profile could not be extracted",
+            PipelineStage::Generation => "the landing page could not be generated",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, TS)]
+pub struct StageEvent {
+    #[ts(type = "number")]
+    pub website_id: i64,
+    pub stage: PipelineStage,
+}
+
+/// Sent periodically while a stage runs.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, TS)]
+pub struct StageProgressEvent {
+    #[ts(type = "number")]
+    pub website_id: i64,
+    pub stage: PipelineStage,
+    #[ts(type = "number")]
+    pub elapsed_secs: u64,
+    /// Pages downloaded so far, only set while crawling.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pages_crawled: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, TS)]
+pub struct StageFailedEvent {
+    #[ts(type = "number")]
+    pub website_id: i64,
+    pub stage: PipelineStage,
+    /// Why the stage failed, safe to show to users.
+    pub message: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, TS)]
 pub struct Contact {
     pub contact_email: Option<String>,
     pub contact_name: Option<String>,
+    #[serde(default)]
+    pub contact_phone: Option<String>,
+    #[serde(default)]
+    pub contact_email_method: Option<ExtractionMethod>,
+    #[serde(default)]
+    pub contact_name_method: Option<ExtractionMethod>,
+    #[serde(default)]
+    pub contact_phone_method: Option<ExtractionMethod>,
+    /// Version of the [super::prompt::PromptName::ContactExtraction] prompt that produced the
+    /// contact, `None` if the LLM was not used.
+    #[serde(default)]
+    pub prompt_version: Option<i32>,
+    #[serde(default)]
+    pub social_links: Option<Social>,
+}
+
+impl Contact {
+    /// Fills the fields missing in `self` with the ones of `other`.
+    pub fn or(self, other: Contact) -> Contact {
+        let uses_other = (self.contact_email.is_none() && other.contact_email.is_some())
+            || (self.contact_name.is_none() && other.contact_name.is_some())
+            || (self.contact_phone.is_none() && other.contact_phone.is_some())
+            || (self.social_links.is_none() && other.social_links.is_some());
+        let prompt_version = if uses_other {
+            other.prompt_version
+        } else {
+            self.prompt_version
+        };
+        let (contact_email, contact_email_method) = match self.contact_email {
+            Some(email) => (Some(email), self.contact_email_method),
+            None => (other.contact_email, other.contact_email_method),
+        };
+        let (contact_name, contact_name_method) = match self.contact_name {
+            Some(name) => (Some(name), self.contact_name_method),
+            None => (other.contact_name, other.contact_name_method),
+        };
+        let (contact_phone, contact_phone_method) = match self.contact_phone {
+            Some(phone) => (Some(phone), self.contact_phone_method),
+            None => (other.contact_phone, other.contact_phone_method),
+        };
+        Contact {
+            contact_email,
+            contact_name,
+            contact_phone,
+            contact_email_method,
+            contact_name_method,
+            contact_phone_method,
+            prompt_version,
+            social_links: self.social_links.or(other.social_links),
+        }
+    }
+
+    /// Replaces the fields of `self` with the ones of `previous` corrected by the owner, so
+    /// corrections survive extracting the contact again.
+    pub fn with_manual_fields(self, previous: &Contact) -> Contact {
+        let manual = |method: Option<ExtractionMethod>| method == Some(ExtractionMethod::Manual);
+        let mut contact = self;
+        if manual(previous.contact_email_method) {
+            contact.contact_email = previous.contact_email.clone();
+            contact.contact_email_method = previous.contact_email_method;
+        }
+        if manual(previous.contact_name_method) {
+            contact.contact_name = previous.contact_name.clone();
+            contact.contact_name_method = previous.contact_name_method;
+        }
+        if manual(previous.contact_phone_method) {
+            contact.contact_phone = previous.contact_phone.clone();
+            contact.contact_phone_method = previous.contact_phone_method;
+        }
+        contact
+    }
+}
+
+/// The rule that produced a [Contact] field.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, sqlx::Type, TS,
+)]
+#[sqlx(type_name = "extraction_method", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ExtractionMethod {
+    /// A `mailto:` link.
+    Mailto,
+    /// A `tel:` link.
+    Tel,
+    /// schema.org JSON-LD, e.g. an `Organization` or `LocalBusiness`.
+    JsonLd,
+    /// schema.org microdata attributes.
+    Microdata,
+    /// The impressum or legal notice page.
+    Impressum,
+    /// A plain or obfuscated (`name [at] domain`) address in the page text.
+    Text,
+    /// Extracted by the LLM.
+    Llm,
+    /// Corrected by the owner.
+    Manual,
 }
 
 impl Website {
-    pub fn new(id: i64, source_address: &str) -> Self {
+    pub fn new(id: i64, source_address: &str, owner_id: Option<String>) -> Self {
         Self {
             id,
             source_address: source_address.to_string(),
+            owner_id,
             ..Default::default()
         }
     }
+
+    /// The stored [Contact].
+    pub fn contact(&self) -> Contact {
+        Contact {
+            contact_email: self.contact_email.clone(),
+            contact_name: self.contact_name.clone(),
+            contact_phone: self.contact_phone.clone(),
+            contact_email_method: self.contact_email_method,
+            contact_name_method: self.contact_name_method,
+            contact_phone_method: self.contact_phone_method,
+            prompt_version: self.contact_prompt_version,
+            social_links: self.social_links.clone(),
+        }
+    }
+}
+
+/// Where the pipeline of a [Website] is at, derived from the last of its events of
+/// [WebsiteStatus::EVENT_TYPES].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, TS)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum WebsiteStatus {
+    /// No stage was started yet.
+    Pending,
+    Running {
+        stage: PipelineStage,
+    },
+    /// The pipeline stopped at `stage`.
+    Failed {
+        stage: PipelineStage,
+        message: String,
+    },
+    /// A site was generated.
+    Completed,
+}
+
+impl WebsiteStatus {
+    pub const EVENT_TYPES: [WebsiteEventType; 3] = [
+        WebsiteEventType::StageStarted,
+        WebsiteEventType::StageFailed,
+        WebsiteEventType::GeneratedWebsite,
+    ];
+
+    /// Websites generated before events were logged have no event, but are completed.
+    pub fn new(website: &Website, last_event: Option<&WebsiteEvent>) -> Self {
+        match last_event {
+            Some(WebsiteEvent::StageStarted(StageEvent { stage, .. })) => {
+                WebsiteStatus::Running { stage: *stage }
+            }
+            Some(WebsiteEvent::StageFailed(failure)) => WebsiteStatus::Failed {
+                stage: failure.stage,
+                message: failure.message.clone(),
+            },
+            Some(WebsiteEvent::GeneratedWebsite(_)) => WebsiteStatus::Completed,
+            _ if website.generated_website_link.is_some() => WebsiteStatus::Completed,
+            _ => WebsiteStatus::Pending,
+        }
+    }
+}
+
+/// What the last crawl of a [Website] stored.
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, TS)]
+pub struct CrawlSummary {
+    #[ts(type = "number")]
+    pub pages: i64,
+    /// Pages whose body is the DOM rendered by the browser.
+    #[ts(type = "number")]
+    pub rendered_pages: i64,
+    /// When the last page was downloaded, `None` if the website was never crawled.
+    pub last_fetched_at: Option<DateTime<Utc>>,
+}
+
+/// A [Website] along with the state of its pipeline and crawl.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WebsiteDetail {
+    pub website: Website,
+    pub status: WebsiteStatus,
+    pub crawl: CrawlSummary,
 }
 
 /// The fields required by the domain to create an [Website].
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct CreateWebsiteRequest {
     pub source_address: Url,
+    pub owner_id: String,
 }
 
 impl CreateWebsiteRequest {
-    pub fn new(source_address: Url) -> Self {
-        Self { source_address }
+    pub fn new(source_address: Url, owner_id: &str) -> Self {
+        Self {
+            source_address,
+            owner_id: owner_id.to_string(),
+        }
     }
 }
 
@@ -61,8 +327,8 @@ pub enum CreateWebsiteError {
     InvalidUrl { source_address: String },
     #[error("website with source address {source_address} already exists")]
     Duplicate { source_address: Url },
-    #[error("failed to send notification")]
-    NotificationFailed,
+    #[error(transparent)]
+    QuotaExceeded(#[from] QuotaExceeded),
     #[error(transparent)]
     Unknown(#[from] anyhow::Error),
 }
@@ -73,13 +339,143 @@ pub enum GetWebsitesError {
     Unknown(#[from] anyhow::Error),
 }
 
-#[derive(Clone, Serialize, Debug)]
+#[derive(Debug, Error)]
+pub enum GetWebsiteError {
+    #[error("website with id {id} not found")]
+    NotFound { id: i64 },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+/// Corrections of the [Contact] of a [Website] made by its owner. `None` leaves a field
+/// unchanged, `Some(None)` clears it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CorrectContactRequest {
+    pub owner_id: String,
+    pub website_id: i64,
+    pub contact_email: Option<Option<String>>,
+    pub contact_name: Option<Option<String>>,
+    pub contact_phone: Option<Option<String>>,
+}
+
+impl CorrectContactRequest {
+    /// Applies the corrections to `contact`, recording the corrected fields as
+    /// [ExtractionMethod::Manual], cleared ones included.
+    pub fn apply(&self, contact: Contact) -> Contact {
+        let correct = |correction: &Option<Option<String>>,
+                       value: Option<String>,
+                       method: Option<ExtractionMethod>| match correction {
+            Some(corrected) => (corrected.clone(), Some(ExtractionMethod::Manual)),
+            None => (value, method),
+        };
+        let (contact_email, contact_email_method) = correct(
+            &self.contact_email,
+            contact.contact_email,
+            contact.contact_email_method,
+        );
+        let (contact_name, contact_name_method) = correct(
+            &self.contact_name,
+            contact.contact_name,
+            contact.contact_name_method,
+        );
+        let (contact_phone, contact_phone_method) = correct(
+            &self.contact_phone,
+            contact.contact_phone,
+            contact.contact_phone_method,
+        );
+        Contact {
+            contact_email,
+            contact_name,
+            contact_phone,
+            contact_email_method,
+            contact_name_method,
+            contact_phone_method,
+            ..contact
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CorrectContactError {
+    #[error("website with id {id} not found")]
+    NotFound { id: i64 },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum DeleteWebsiteError {
+    #[error("website with id {id} not found")]
+    NotFound { id: i64 },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Clone, Deserialize, Serialize, Debug, TS)]
 #[serde(tag = "type")]
 pub enum WebsiteEvent {
-    FetchingContact,
-    WebsiteAdded(Website),
+    WebsiteAdded(Box<Website>),
     FetchedContact(ContactEvent),
     GeneratedWebsite(GeneratedWebsiteEvent),
+    StageStarted(StageEvent),
+    StageProgress(StageProgressEvent),
+    /// The pipeline stopped at this stage.
+    StageFailed(StageFailedEvent),
+    /// The owner corrected the contact.
+    ContactUpdated(ContactEvent),
+    /// The website was deleted, along with everything stored about it.
+    WebsiteDeleted(WebsiteDeletedEvent),
+}
+
+/// The kind of a [WebsiteEvent], as found in its `type` field.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize, sqlx::Type, TS,
+)]
+#[sqlx(type_name = "website_event_type", rename_all = "snake_case")]
+pub enum WebsiteEventType {
+    WebsiteAdded,
+    FetchedContact,
+    GeneratedWebsite,
+    StageStarted,
+    StageProgress,
+    StageFailed,
+    ContactUpdated,
+    WebsiteDeleted,
+}
+
+impl WebsiteEvent {
+    pub fn event_type(&self) -> WebsiteEventType {
+        match self {
+            WebsiteEvent::WebsiteAdded(_) => WebsiteEventType::WebsiteAdded,
+            WebsiteEvent::FetchedContact(_) => WebsiteEventType::FetchedContact,
+            WebsiteEvent::GeneratedWebsite(_) => WebsiteEventType::GeneratedWebsite,
+            WebsiteEvent::StageStarted(_) => WebsiteEventType::StageStarted,
+            WebsiteEvent::StageProgress(_) => WebsiteEventType::StageProgress,
+            WebsiteEvent::StageFailed(_) => WebsiteEventType::StageFailed,
+            WebsiteEvent::ContactUpdated(_) => WebsiteEventType::ContactUpdated,
+            WebsiteEvent::WebsiteDeleted(_) => WebsiteEventType::WebsiteDeleted,
+        }
+    }
+
+    /// Ephemeral events are only sent to the clients connected when they happen. They are not
+    /// logged, so they are neither replayed nor delivered to webhooks.
+    pub fn is_ephemeral(&self) -> bool {
+        matches!(self, WebsiteEvent::StageProgress(_))
+    }
+
+    /// The website the event is about.
+    pub fn website_id(&self) -> i64 {
+        match self {
+            WebsiteEvent::WebsiteAdded(website) => website.id,
+            WebsiteEvent::FetchedContact(event) => event.website_id,
+            WebsiteEvent::GeneratedWebsite(event) => event.website_id,
+            WebsiteEvent::StageStarted(event) => event.website_id,
+            WebsiteEvent::StageProgress(event) => event.website_id,
+            WebsiteEvent::StageFailed(event) => event.website_id,
+            WebsiteEvent::ContactUpdated(event) => event.website_id,
+            WebsiteEvent::WebsiteDeleted(event) => event.website_id,
+        }
+    }
 }
 
 #[derive(Debug, Error)]
@@ -89,21 +485,33 @@ pub enum WebsiteEventError {
 }
 
 #[derive(Debug, Error)]
-pub enum WebsiteAiError {
+pub enum ExtractContactError {
     #[error(transparent)]
     Unknown(#[from] anyhow::Error),
-    #[error("failed to init openai")]
-    FailedToInitOpeanAi,
-    #[error("failed to fetch content")]
-    FailedToFetchContent,
-    #[error("failed to get name and owner from chatgpt")]
+    #[error("failed to get name and owner from the llm")]
     FailedToFetchContact,
+}
+
+#[derive(Debug, Error)]
+pub enum GenerateWebsiteError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
     #[error("webdriver failed")]
     WebdriverError(fantoccini::error::CmdError),
+    #[error("automation {step} failed")]
+    AutomationStepFailed {
+        step: String,
+        #[source]
+        source: fantoccini::error::CmdError,
+    },
+    #[error("no webdriver session available")]
+    WebdriverUnavailable(#[source] fantoccini::error::NewSessionError),
 }
 
 #[derive(Debug, Error)]
 pub enum UpdateContactError {
+    #[error("website with id {id} not found")]
+    NotFound { id: i64 },
     #[error(transparent)]
     Unknown(#[from] anyhow::Error),
     #[error("failed to start transaction")]
@@ -112,20 +520,34 @@ pub enum UpdateContactError {
 
 #[derive(Debug, Error)]
 pub enum UpdateGeneratedWebsiteError {
+    #[error("website with id {id} not found")]
+    NotFound { id: i64 },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum UpdateRenderModeError {
     #[error(transparent)]
     Unknown(#[from] anyhow::Error),
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, PartialOrd, Eq, Ord)]
+/// The social and review pages linked from a [Website].
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Default, TS)]
 pub struct Social {
-    instagram: Option<String>,
-    facebook: Option<String>,
-    google_maps: Option<String>,
-    google_reviews: Option<String>,
+    pub instagram: Option<String>,
+    pub facebook: Option<String>,
+    pub google_maps: Option<String>,
+    #[serde(alias = "google_review")]
+    pub google_reviews: Option<String>,
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, PartialOrd, Eq, Ord)]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, PartialOrd, Eq, Ord, TS)]
 pub struct GeneratedWebsite {
     pub name: String,
     pub url: Url,
+    /// Version of the [super::prompt::PromptName::LandingPage] prompt the site was generated
+    /// from.
+    #[serde(default)]
+    pub prompt_version: Option<i32>,
 }
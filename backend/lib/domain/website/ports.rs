@@ -8,14 +8,51 @@
    since the application is expected to always run in a multithreaded environment.
 */
 
-use std::future::Future;
+use std::{future::Future, time::Duration};
 
-use tokio::sync::broadcast::Receiver;
+use chrono::{DateTime, Utc};
+use tokio::sync::{broadcast::Receiver, watch};
+use uuid::Uuid;
 
-use super::models::website::{
-    Contact, ContactEvent, CreateWebsiteError, CreateWebsiteRequest, GeneratedWebsite,
-    GeneratedWebsiteEvent, GetWebsitesError, UpdateContactError, UpdateGeneratedWebsiteError,
-    Website, WebsiteAiError, WebsiteEvent, WebsiteEventError,
+use super::models::{
+    automation_failure::{
+        AutomationArtifact, AutomationFailure, GetAutomationArtifactError,
+        ListAutomationFailuresError, RecordAutomationFailureError, RecordAutomationFailureRequest,
+    },
+    business_profile::{BusinessProfile, ExtractBusinessProfileError, UpdateBusinessProfileError},
+    event::{AppendEventError, EventEnvelope, GetEventsError},
+    landing_page::{LandingPageCopy, WriteCopyError},
+    object_storage::{DeleteObjectError, GetObjectError, PutObjectError},
+    outbox::{OutboxEntry, OutboxError},
+    page::{CrawlWebsiteError, CrawledWebsite, GetPagesError, PageSnapshot, SavePagesError},
+    preview::{
+        CreatePreviewError, CreatePreviewRequest, DeletePreviewError, GetPreviewError,
+        ListPreviewsError, Preview,
+    },
+    prompt::{
+        CreatePromptVersionError, CreatePromptVersionRequest, GetPromptError, ListPromptsError,
+        PromptName, PromptTemplate,
+    },
+    quota::{GetMonthlyUsageError, MonthlyUsage, RecordGenerationError},
+    screenshot::{
+        CaptureScreenshotError, CapturedScreenshot, GetComparisonError, GetScreenshotImageError,
+        GetScreenshotsError, SaveScreenshotError, SaveScreenshotRequest, Screenshot,
+        ScreenshotSubject, Viewport,
+    },
+    usage::{GetUsageError, GetUsageRequest, LlmUsage, RecordUsageError, UsageReport},
+    webhook::{
+        CreateWebhookDeliveryRequest, CreateWebhookError, CreateWebhookRequest,
+        GetWebhookDeliveryError, GetWebhookError, ListWebhooksError, SendWebhookError, Webhook,
+        WebhookAttempt, WebhookDelivery, WebhookDeliveryError,
+    },
+    website::{
+        Contact, ContactEvent, CorrectContactError, CorrectContactRequest, CrawlSummary,
+        CreateWebsiteError, CreateWebsiteRequest, DeleteWebsiteError, ExtractContactError,
+        GenerateWebsiteError, GeneratedWebsite, GeneratedWebsiteEvent, GetWebsiteError,
+        GetWebsitesError, RenderMode, StageEvent, StageFailedEvent, StageProgressEvent,
+        UpdateContactError, UpdateGeneratedWebsiteError, UpdateRenderModeError, Website,
+        WebsiteDeletedEvent, WebsiteDetail, WebsiteEvent, WebsiteEventError, WebsiteEventType,
+    },
 };
 
 /// `WebsiteService` is the public API for the website domain.
@@ -33,18 +70,245 @@ pub trait WebsiteService: Clone + Send + Sync + 'static {
         req: &CreateWebsiteRequest,
     ) -> impl Future<Output = Result<Website, CreateWebsiteError>> + Send;
 
-    /// Get all the [Website], sorted by date.
-    fn get_websites(&self) -> impl Future<Output = Result<Vec<Website>, GetWebsitesError>> + Send;
+    /// Get the [Website]s of `owner_id`, sorted by date. Websites added before they had an owner
+    /// belong to nobody and are never listed.
+    fn get_websites(
+        &self,
+        owner_id: &str,
+    ) -> impl Future<Output = Result<Vec<Website>, GetWebsitesError>> + Send;
+
+    /// Get a [Website] by id.
+    ///
+    /// # Errors
+    ///
+    /// - [GetWebsiteError::NotFound] if no [Website] with `website_id` exists.
+    fn get_website(
+        &self,
+        website_id: i64,
+    ) -> impl Future<Output = Result<Website, GetWebsiteError>> + Send;
+
+    /// Re-run contact extraction and generation for an existing [Website] of `owner_id`, reusing
+    /// its stored pages and only re-downloading the ones that changed.
+    ///
+    /// # Errors
+    ///
+    /// - [GetWebsiteError::NotFound] if `owner_id` has no [Website] with `website_id`.
+    fn refresh_website(
+        &self,
+        owner_id: &str,
+        website_id: i64,
+    ) -> impl Future<Output = Result<Website, GetWebsiteError>> + Send;
+
+    /// Get a [Website] of `owner_id` along with the state of its pipeline and crawl.
+    ///
+    /// # Errors
+    ///
+    /// - [GetWebsiteError::NotFound] if `owner_id` has no [Website] with `website_id`.
+    fn get_website_detail(
+        &self,
+        owner_id: &str,
+        website_id: i64,
+    ) -> impl Future<Output = Result<WebsiteDetail, GetWebsiteError>> + Send;
+
+    /// Apply the corrections of its owner to the [Contact] of a [Website], returning the
+    /// corrected [Website]. Corrected fields are kept when the contact is extracted again.
+    ///
+    /// # Errors
+    ///
+    /// - [CorrectContactError::NotFound] if `req.owner_id` has no [Website] with
+    ///   `req.website_id`.
+    fn correct_contact(
+        &self,
+        req: &CorrectContactRequest,
+    ) -> impl Future<Output = Result<Website, CorrectContactError>> + Send;
+
+    /// Delete a [Website] of `owner_id`, along with its pages, previews, screenshots, automation
+    /// failures and logged events.
+    ///
+    /// # Errors
+    ///
+    /// - [DeleteWebsiteError::NotFound] if `owner_id` has no [Website] with `website_id`.
+    fn delete_website(
+        &self,
+        owner_id: &str,
+        website_id: i64,
+    ) -> impl Future<Output = Result<(), DeleteWebsiteError>> + Send;
+
+    /// Get the LLM usage of an owner over a date range.
+    ///
+    /// # Errors
+    ///
+    /// - [GetUsageError::InvalidRange] if `req.from` is not before `req.to`.
+    fn get_usage(
+        &self,
+        req: &GetUsageRequest,
+    ) -> impl Future<Output = Result<UsageReport, GetUsageError>> + Send;
+
+    /// Get the version in use of every prompt.
+    fn list_prompts(
+        &self,
+    ) -> impl Future<Output = Result<Vec<PromptTemplate>, ListPromptsError>> + Send;
+
+    /// Get every version of a prompt, newest first.
+    ///
+    /// # Errors
+    ///
+    /// - [GetPromptError::NotFound] if the prompt was never stored.
+    fn get_prompt_versions(
+        &self,
+        name: PromptName,
+    ) -> impl Future<Output = Result<Vec<PromptTemplate>, GetPromptError>> + Send;
+
+    /// Store a new version of a prompt, used from then on.
+    fn create_prompt_version(
+        &self,
+        req: &CreatePromptVersionRequest,
+    ) -> impl Future<Output = Result<PromptTemplate, CreatePromptVersionError>> + Send;
+
+    /// Get the hosted preview of a generated site.
+    ///
+    /// # Errors
+    ///
+    /// - [GetPreviewError::NotFound] if no site is hosted under `slug`.
+    /// - [GetPreviewError::Expired] if the preview expired.
+    fn get_preview(
+        &self,
+        slug: &str,
+    ) -> impl Future<Output = Result<Preview, GetPreviewError>> + Send;
+
+    /// Get the failures captured from the site generator automation for a website, newest first.
+    fn list_automation_failures(
+        &self,
+        website_id: i64,
+    ) -> impl Future<Output = Result<Vec<AutomationFailure>, ListAutomationFailuresError>> + Send;
+
+    /// Get an artifact of an [AutomationFailure].
+    ///
+    /// # Errors
+    ///
+    /// - [GetAutomationArtifactError::NotFound] if the failure does not exist or the artifact
+    ///   could not be captured.
+    fn get_automation_artifact(
+        &self,
+        id: i64,
+        artifact: AutomationArtifact,
+    ) -> impl Future<Output = Result<Vec<u8>, GetAutomationArtifactError>> + Send;
+
+    /// Get the latest screenshots of the original and generated sites of the websites.
+    fn get_screenshots(
+        &self,
+        website_ids: &[i64],
+    ) -> impl Future<Output = Result<Vec<Screenshot>, GetScreenshotsError>> + Send;
+
+    /// Get the latest screenshots of the original and generated sites of a [Website] of
+    /// `owner_id`.
+    ///
+    /// # Errors
+    ///
+    /// - [GetComparisonError::NotFound] if `owner_id` has no [Website] with `website_id`.
+    fn get_comparison(
+        &self,
+        owner_id: &str,
+        website_id: i64,
+    ) -> impl Future<Output = Result<Vec<Screenshot>, GetComparisonError>> + Send;
+
+    /// Get the PNG image of a [Screenshot] of a [Website] of `owner_id`, or its thumbnail.
+    ///
+    /// # Errors
+    ///
+    /// - [GetScreenshotImageError::NotFound] if no such screenshot was captured, or the
+    ///   [Website] belongs to another owner.
+    fn get_screenshot_image(
+        &self,
+        owner_id: &str,
+        website_id: i64,
+        subject: ScreenshotSubject,
+        viewport: Viewport,
+        thumbnail: bool,
+    ) -> impl Future<Output = Result<Vec<u8>, GetScreenshotImageError>> + Send;
+
+    /// Get up to `limit` events about the websites of `tenant` emitted after `last_event_id`,
+    /// oldest first.
+    fn get_events_after(
+        &self,
+        tenant: &str,
+        last_event_id: i64,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<EventEnvelope>, GetEventsError>> + Send;
+
     /// Get a receiver to subscribe to sse
-    fn get_receiver(&self) -> Receiver<WebsiteEvent>;
+    fn get_receiver(&self) -> Receiver<EventEnvelope>;
+
+    /// Subscribe a [Webhook] to the events of the websites of `req.tenant`.
+    fn create_webhook(
+        &self,
+        req: &CreateWebhookRequest,
+    ) -> impl Future<Output = Result<Webhook, CreateWebhookError>> + Send;
+
+    /// Get the [Webhook]s of a tenant, oldest first.
+    fn list_webhooks(
+        &self,
+        tenant: &str,
+    ) -> impl Future<Output = Result<Vec<Webhook>, ListWebhooksError>> + Send;
+
+    /// Delete a [Webhook] of a tenant, along with its deliveries.
+    ///
+    /// # Errors
+    ///
+    /// - [GetWebhookError::NotFound] if `tenant` has no [Webhook] with `webhook_id`.
+    fn delete_webhook(
+        &self,
+        tenant: &str,
+        webhook_id: i64,
+    ) -> impl Future<Output = Result<(), GetWebhookError>> + Send;
+
+    /// Get the latest deliveries of a [Webhook] of a tenant, newest first.
+    ///
+    /// # Errors
+    ///
+    /// - [GetWebhookError::NotFound] if `tenant` has no [Webhook] with `webhook_id`.
+    fn list_webhook_deliveries(
+        &self,
+        tenant: &str,
+        webhook_id: i64,
+    ) -> impl Future<Output = Result<Vec<WebhookDelivery>, GetWebhookError>> + Send;
+
+    /// Send a ping to a [Webhook] of a tenant right away, returning the delivery with its
+    /// outcome.
+    ///
+    /// # Errors
+    ///
+    /// - [GetWebhookError::NotFound] if `tenant` has no [Webhook] with `webhook_id`.
+    fn test_webhook(
+        &self,
+        tenant: &str,
+        webhook_id: i64,
+    ) -> impl Future<Output = Result<WebhookDelivery, GetWebhookError>> + Send;
+
+    /// Send the payload of a delivery again as a new delivery, returning it with its outcome.
+    /// Failed replays of events are retried.
+    ///
+    /// # Errors
+    ///
+    /// - [GetWebhookDeliveryError::NotFound] if `tenant` has no [Webhook] with `webhook_id`, or
+    ///   it has no delivery with `delivery_id`.
+    fn replay_webhook_delivery(
+        &self,
+        tenant: &str,
+        webhook_id: i64,
+        delivery_id: i64,
+    ) -> impl Future<Output = Result<WebhookDelivery, GetWebhookDeliveryError>> + Send;
 }
 
 /// `WebsiteRepository` represents a store of website data.
 ///
+/// Changes clients are notified of MUST add their [WebsiteEvent] to the [OutboxRepository] in the
+/// same transaction.
+///
 /// External modules must conform to this contract – the domain is not concerned with the
 /// implementation details or underlying technology of any external code.
 pub trait WebsiteRepository: Clone + Send + Sync + 'static {
-    /// Asynchronously persist a new [Website].
+    /// Asynchronously persist a new [Website], along with a [WebsiteEvent::WebsiteAdded].
     ///
     /// # Errors
     ///
@@ -54,26 +318,409 @@ pub trait WebsiteRepository: Clone + Send + Sync + 'static {
         &self,
         req: &CreateWebsiteRequest,
     ) -> impl Future<Output = Result<Website, CreateWebsiteError>> + Send;
-    fn get_websites(&self) -> impl Future<Output = Result<Vec<Website>, GetWebsitesError>> + Send;
+
+    /// Get the [Website]s of `owner_id`, oldest first. Websites without an owner are left out.
+    fn get_websites(
+        &self,
+        owner_id: &str,
+    ) -> impl Future<Output = Result<Vec<Website>, GetWebsitesError>> + Send;
+
+    /// Asynchronously fetch a single [Website].
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [GetWebsiteError::NotFound] if no [Website] with `website_id` exists.
+    fn get_website(
+        &self,
+        website_id: i64,
+    ) -> impl Future<Output = Result<Website, GetWebsiteError>> + Send;
+
+    /// Persist the [Contact] of a [Website], along with a [WebsiteEvent::FetchedContact].
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [UpdateContactError::NotFound] if no [Website] with `website_id` exists.
     fn update_contact(
         &self,
         website_id: i64,
         contact: &Contact,
     ) -> impl Future<Output = Result<(), UpdateContactError>> + Send;
 
+    /// Persist the [Contact] of a [Website] corrected by its owner, along with a
+    /// [WebsiteEvent::ContactUpdated].
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [CorrectContactError::NotFound] if no [Website] with `website_id` exists.
+    fn correct_contact(
+        &self,
+        website_id: i64,
+        contact: &Contact,
+    ) -> impl Future<Output = Result<(), CorrectContactError>> + Send;
+
+    /// Delete a [Website] and everything stored about it, along with a
+    /// [WebsiteEvent::WebsiteDeleted].
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [DeleteWebsiteError::NotFound] if no [Website] with `website_id` exists.
+    fn delete_website(
+        &self,
+        website_id: i64,
+    ) -> impl Future<Output = Result<(), DeleteWebsiteError>> + Send;
+
+    /// Persist the [GeneratedWebsite] of a [Website], along with a
+    /// [WebsiteEvent::GeneratedWebsite].
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [UpdateGeneratedWebsiteError::NotFound] if no [Website] with `website_id`
+    ///   exists.
     fn update_generated_website(
         &self,
         website_id: i64,
         generated_website: &GeneratedWebsite,
     ) -> impl Future<Output = Result<(), UpdateGeneratedWebsiteError>> + Send;
+
+    /// Get the pages stored by the last crawl of a [Website].
+    fn get_pages(
+        &self,
+        website_id: i64,
+    ) -> impl Future<Output = Result<Vec<PageSnapshot>, GetPagesError>> + Send;
+
+    /// Summarize the pages stored by the last crawl of a [Website].
+    fn get_crawl_summary(
+        &self,
+        website_id: i64,
+    ) -> impl Future<Output = Result<CrawlSummary, GetPagesError>> + Send;
+
+    /// Persist the pages of a crawl, replacing any previous snapshot of the same url and removing
+    /// the pages the crawl did not find.
+    fn save_pages(
+        &self,
+        website_id: i64,
+        pages: &[PageSnapshot],
+    ) -> impl Future<Output = Result<(), SavePagesError>> + Send;
+
+    /// Persist the [BusinessProfile] of a [Website].
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [UpdateBusinessProfileError::NotFound] if no [Website] with `website_id`
+    ///   exists.
+    fn update_business_profile(
+        &self,
+        website_id: i64,
+        business_profile: &BusinessProfile,
+    ) -> impl Future<Output = Result<(), UpdateBusinessProfileError>> + Send;
+
+    /// Record how the pages of a [Website] were obtained by its last crawl.
+    fn update_render_mode(
+        &self,
+        website_id: i64,
+        render_mode: RenderMode,
+    ) -> impl Future<Output = Result<(), UpdateRenderModeError>> + Send;
+}
+
+/// `UsageRepository` stores the LLM calls and site generations made while processing websites.
+pub trait UsageRepository: Clone + Send + Sync + 'static {
+    fn record_llm_usage(
+        &self,
+        usage: &LlmUsage,
+    ) -> impl Future<Output = Result<(), RecordUsageError>> + Send;
+
+    /// Aggregate the calls recorded for `req.owner_id` between `req.from` and `req.to`.
+    fn get_llm_usage(
+        &self,
+        req: &GetUsageRequest,
+    ) -> impl Future<Output = Result<UsageReport, GetUsageError>> + Send;
+
+    /// Record that a site generation was started for `website`.
+    fn record_generation(
+        &self,
+        website: &Website,
+    ) -> impl Future<Output = Result<(), RecordGenerationError>> + Send;
+
+    /// Sum the LLM spend, generations and websites of `owner_id` since `since`.
+    fn get_monthly_usage(
+        &self,
+        owner_id: &str,
+        since: DateTime<Utc>,
+    ) -> impl Future<Output = Result<MonthlyUsage, GetMonthlyUsageError>> + Send;
+}
+
+/// `PromptRepository` stores the versions of every [PromptTemplate].
+pub trait PromptRepository: Clone + Send + Sync + 'static {
+    /// Get the latest version of a prompt.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [GetPromptError::NotFound] if the prompt has no version.
+    fn get_prompt(
+        &self,
+        name: PromptName,
+    ) -> impl Future<Output = Result<PromptTemplate, GetPromptError>> + Send;
+
+    fn list_prompts(
+        &self,
+    ) -> impl Future<Output = Result<Vec<PromptTemplate>, ListPromptsError>> + Send;
+
+    /// Get every version of a prompt, newest first.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [GetPromptError::NotFound] if the prompt has no version.
+    fn get_prompt_versions(
+        &self,
+        name: PromptName,
+    ) -> impl Future<Output = Result<Vec<PromptTemplate>, GetPromptError>> + Send;
+
+    /// Store `req` as the next version of the prompt.
+    fn create_prompt_version(
+        &self,
+        req: &CreatePromptVersionRequest,
+    ) -> impl Future<Output = Result<PromptTemplate, CreatePromptVersionError>> + Send;
+}
+
+/// `PreviewRepository` stores the generated sites hosted under `/preview/{slug}/`.
+pub trait PreviewRepository: Clone + Send + Sync + 'static {
+    /// Persist a [Preview], replacing the expiry of an existing one with the same slug.
+    fn create_preview(
+        &self,
+        req: &CreatePreviewRequest,
+    ) -> impl Future<Output = Result<Preview, CreatePreviewError>> + Send;
+
+    /// # Errors
+    ///
+    /// - MUST return [GetPreviewError::NotFound] if no [Preview] with `slug` exists.
+    fn get_preview(
+        &self,
+        slug: &str,
+    ) -> impl Future<Output = Result<Preview, GetPreviewError>> + Send;
+
+    /// Get the [Preview]s of a website, oldest first.
+    fn list_previews(
+        &self,
+        website_id: i64,
+    ) -> impl Future<Output = Result<Vec<Preview>, ListPreviewsError>> + Send;
+}
+
+/// `AutomationFailureRepository` stores what was captured from failed browser automation runs.
+pub trait AutomationFailureRepository: Clone + Send + Sync + 'static {
+    fn record_automation_failure(
+        &self,
+        req: &RecordAutomationFailureRequest,
+    ) -> impl Future<Output = Result<AutomationFailure, RecordAutomationFailureError>> + Send;
+
+    fn list_automation_failures(
+        &self,
+        website_id: i64,
+    ) -> impl Future<Output = Result<Vec<AutomationFailure>, ListAutomationFailuresError>> + Send;
+
+    /// # Errors
+    ///
+    /// - MUST return [GetAutomationArtifactError::NotFound] if no [AutomationFailure] with `id`
+    ///   exists, or it has no such artifact.
+    fn get_automation_artifact(
+        &self,
+        id: i64,
+        artifact: AutomationArtifact,
+    ) -> impl Future<Output = Result<Vec<u8>, GetAutomationArtifactError>> + Send;
+}
+
+/// `ScreenshotRepository` stores where the screenshots of the sites are kept.
+pub trait ScreenshotRepository: Clone + Send + Sync + 'static {
+    /// Persist a [Screenshot], replacing the one of the same site and viewport.
+    fn save_screenshot(
+        &self,
+        req: &SaveScreenshotRequest,
+    ) -> impl Future<Output = Result<Screenshot, SaveScreenshotError>> + Send;
+
+    fn get_screenshots(
+        &self,
+        website_ids: &[i64],
+    ) -> impl Future<Output = Result<Vec<Screenshot>, GetScreenshotsError>> + Send;
+}
+
+/// `ObjectStorage` keeps binary objects, like images, by key.
+pub trait ObjectStorage: Clone + Send + Sync + 'static {
+    /// Store `content` under `key`, replacing any existing object.
+    fn put_object(
+        &self,
+        key: &str,
+        content: &[u8],
+    ) -> impl Future<Output = Result<(), PutObjectError>> + Send;
+
+    /// # Errors
+    ///
+    /// - MUST return [GetObjectError::NotFound] if nothing is stored under `key`.
+    fn get_object(&self, key: &str)
+    -> impl Future<Output = Result<Vec<u8>, GetObjectError>> + Send;
+
+    /// Remove the object stored under `key`, if any.
+    fn delete_object(
+        &self,
+        key: &str,
+    ) -> impl Future<Output = Result<(), DeleteObjectError>> + Send;
+}
+
+/// `EventRepository` is the log of every [WebsiteEvent], kept so clients can catch up on the
+/// events they missed.
+pub trait EventRepository: Clone + Send + Sync + 'static {
+    /// Append `event` to the log, giving it the next event id.
+    fn append_event(
+        &self,
+        event: &WebsiteEvent,
+    ) -> impl Future<Output = Result<EventEnvelope, AppendEventError>> + Send;
+
+    fn get_events_after(
+        &self,
+        last_event_id: i64,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<EventEnvelope>, GetEventsError>> + Send;
+
+    /// Put an ephemeral `event` in an envelope without logging it. It gets the id of the latest
+    /// logged event, so clients resuming from it miss nothing.
+    fn wrap_ephemeral_event(
+        &self,
+        event: &WebsiteEvent,
+    ) -> impl Future<Output = Result<EventEnvelope, AppendEventError>> + Send;
+
+    /// Get up to `limit` events about the websites of `tenant` logged after `last_event_id`,
+    /// oldest first. Only the deletion is returned of the websites that were deleted.
+    fn get_tenant_events_after(
+        &self,
+        tenant: &str,
+        last_event_id: i64,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<EventEnvelope>, GetEventsError>> + Send;
+
+    /// Get the latest event of one of the `event_types` logged about a website, if any.
+    fn get_last_event(
+        &self,
+        website_id: i64,
+        event_types: &[WebsiteEventType],
+    ) -> impl Future<Output = Result<Option<EventEnvelope>, GetEventsError>> + Send;
+}
+
+/// `OutboxRepository` holds the [WebsiteEvent]s the [WebsiteRepository] writes in the same
+/// transaction as the changes they describe, until they are published.
+pub trait OutboxRepository: Clone + Send + Sync + 'static {
+    /// Take or renew the lease of `relay_id` on the outbox for `lease`, and get up to `limit`
+    /// entries, oldest first. Returns no entries while another relay holds the lease.
+    fn claim_outbox(
+        &self,
+        relay_id: Uuid,
+        lease: Duration,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<OutboxEntry>, OutboxError>> + Send;
+
+    /// Remove a published entry.
+    fn delete_outbox_entry(
+        &self,
+        outbox_id: i64,
+    ) -> impl Future<Output = Result<(), OutboxError>> + Send;
+}
+
+/// `WebhookRepository` stores the [Webhook]s of the tenants and the queue of their deliveries.
+pub trait WebhookRepository: Clone + Send + Sync + 'static {
+    fn create_webhook(
+        &self,
+        req: &CreateWebhookRequest,
+    ) -> impl Future<Output = Result<Webhook, CreateWebhookError>> + Send;
+
+    fn list_webhooks(
+        &self,
+        tenant: &str,
+    ) -> impl Future<Output = Result<Vec<Webhook>, ListWebhooksError>> + Send;
+
+    /// # Errors
+    ///
+    /// - MUST return [GetWebhookError::NotFound] if `tenant` has no [Webhook] with `webhook_id`.
+    fn get_webhook(
+        &self,
+        tenant: &str,
+        webhook_id: i64,
+    ) -> impl Future<Output = Result<Webhook, GetWebhookError>> + Send;
+
+    /// # Errors
+    ///
+    /// - MUST return [GetWebhookError::NotFound] if `tenant` has no [Webhook] with `webhook_id`.
+    fn delete_webhook(
+        &self,
+        tenant: &str,
+        webhook_id: i64,
+    ) -> impl Future<Output = Result<(), GetWebhookError>> + Send;
+
+    /// Get up to `limit` deliveries of a [Webhook], newest first.
+    fn list_webhook_deliveries(
+        &self,
+        webhook_id: i64,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<WebhookDelivery>, WebhookDeliveryError>> + Send;
+
+    /// # Errors
+    ///
+    /// - MUST return [GetWebhookDeliveryError::NotFound] if the [Webhook] has no delivery with
+    ///   `delivery_id`.
+    fn get_webhook_delivery(
+        &self,
+        webhook_id: i64,
+        delivery_id: i64,
+    ) -> impl Future<Output = Result<WebhookDelivery, GetWebhookDeliveryError>> + Send;
+
+    /// Persist a [WebhookDelivery], left unscheduled for the caller to send.
+    fn create_webhook_delivery(
+        &self,
+        req: &CreateWebhookDeliveryRequest,
+    ) -> impl Future<Output = Result<WebhookDelivery, WebhookDeliveryError>> + Send;
+
+    /// Schedule a delivery of each of the next `limit` logged events to every [Webhook]
+    /// subscribed to it, returning the number of events dispatched. Every event is dispatched
+    /// once, even with several instances.
+    fn dispatch_webhook_events(
+        &self,
+        limit: i64,
+    ) -> impl Future<Output = Result<usize, WebhookDeliveryError>> + Send;
+
+    /// Take up to `limit` scheduled deliveries that are due, along with their [Webhook]. They are
+    /// not due again for `lease`, so other instances do not send them meanwhile.
+    fn claim_webhook_deliveries(
+        &self,
+        limit: i64,
+        lease: Duration,
+    ) -> impl Future<Output = Result<Vec<(Webhook, WebhookDelivery)>, WebhookDeliveryError>> + Send;
+
+    /// Record the outcome of sending a delivery.
+    fn record_webhook_attempt(
+        &self,
+        delivery_id: i64,
+        attempt: &WebhookAttempt,
+    ) -> impl Future<Output = Result<WebhookDelivery, WebhookDeliveryError>> + Send;
+}
+
+/// `WebhookSender` POSTs the [WebhookDelivery]s to the [Webhook]s.
+pub trait WebhookSender: Send + Sync + Clone + 'static {
+    /// Send the payload of `delivery`, signed with the secret of `webhook`, returning the
+    /// response status.
+    ///
+    /// # Errors
+    ///
+    /// - [SendWebhookError::Rejected] if the response status is not a success.
+    /// - [SendWebhookError::ForbiddenAddress] if the host of the [Webhook] resolves to an address
+    ///   that is not [public](super::models::webhook::is_public_address).
+    fn send(
+        &self,
+        webhook: &Webhook,
+        delivery: &WebhookDelivery,
+    ) -> impl Future<Output = Result<u16, SendWebhookError>> + Send;
 }
 
 /// `WebsiteNotifier` triggers notifications for status changes on websites.
 ///
-/// This will be used by the SSE endpoint to notify the browser
-///
+/// Its events are followed by the browser through the websocket and the SSE endpoint.
 pub trait WebsiteNotifier: Send + Sync + Clone + 'static {
-    fn get_receiver(&self) -> Receiver<WebsiteEvent>;
+    fn get_receiver(&self) -> Receiver<EventEnvelope>;
     fn contact_fetched(
         &self,
         contact: &ContactEvent,
@@ -86,20 +733,97 @@ pub trait WebsiteNotifier: Send + Sync + Clone + 'static {
         &self,
         generated_website: GeneratedWebsiteEvent,
     ) -> impl Future<Output = Result<usize, WebsiteEventError>> + Send;
+    fn stage_started(
+        &self,
+        stage: &StageEvent,
+    ) -> impl Future<Output = Result<usize, WebsiteEventError>> + Send;
+    /// Progress is ephemeral, it is sent to the connected clients without being logged.
+    fn stage_progress(
+        &self,
+        progress: &StageProgressEvent,
+    ) -> impl Future<Output = Result<usize, WebsiteEventError>> + Send;
+    fn stage_failed(
+        &self,
+        failure: &StageFailedEvent,
+    ) -> impl Future<Output = Result<usize, WebsiteEventError>> + Send;
+    fn contact_updated(
+        &self,
+        contact: &ContactEvent,
+    ) -> impl Future<Output = Result<usize, WebsiteEventError>> + Send;
+    fn website_deleted(
+        &self,
+        deleted: &WebsiteDeletedEvent,
+    ) -> impl Future<Output = Result<usize, WebsiteEventError>> + Send;
 }
 
-/// `WebsiteAi` does calls to various AI services like lovable and chagpt for various tasks
-pub trait WebsiteAi: Send + Sync + Clone + 'static {
-    fn get_contact(
-        &self,
-        website_source_address: &str,
-    ) -> impl Future<Output = Result<Contact, WebsiteAiError>> + Send;
+/// `WebsiteCrawler` downloads the pages of a website.
+pub trait WebsiteCrawler: Send + Sync + Clone + 'static {
+    /// Crawl the website, sending conditional requests for the urls in `previous_pages` and
+    /// reusing their stored body when the server reports them unchanged. Sites whose static HTML
+    /// has little visible text may be rendered in a browser instead. The number of pages
+    /// downloaded so far is sent to `pages_crawled`.
+    ///
+    /// # Errors
+    ///
+    /// - [CrawlWebsiteError::InvalidUrl] if `website_source_address` is not a valid url.
+    /// - [CrawlWebsiteError::FailedToFetchContent] if any page could not be downloaded.
     fn get_full_website(
         &self,
         website_source_address: &str,
-    ) -> impl Future<Output = Result<String, WebsiteAiError>> + Send;
+        previous_pages: &[PageSnapshot],
+        pages_crawled: &watch::Sender<usize>,
+    ) -> impl Future<Output = Result<CrawledWebsite, CrawlWebsiteError>> + Send;
+
+    /// Take a screenshot of the page at `url` in a browser window of the `viewport` size.
+    fn capture_screenshot(
+        &self,
+        url: &str,
+        viewport: Viewport,
+    ) -> impl Future<Output = Result<CapturedScreenshot, CaptureScreenshotError>> + Send;
+}
+
+/// `ContactExtractor` finds the owner's contact details in the pages of a crawled website.
+pub trait ContactExtractor: Send + Sync + Clone + 'static {
+    /// Extract the [Contact] of `website` from its `crawled` pages, recording in each `*_method`
+    /// field how the value was found.
+    fn get_contact(
+        &self,
+        website: &Website,
+        crawled: &CrawledWebsite,
+    ) -> impl Future<Output = Result<Contact, ExtractContactError>> + Send;
+}
+
+/// `BusinessProfileExtractor` finds the address, phone numbers, opening hours, category and
+/// services of the business behind a crawled website.
+pub trait BusinessProfileExtractor: Send + Sync + Clone + 'static {
+    fn get_business_profile(
+        &self,
+        website: &Website,
+        crawled: &CrawledWebsite,
+    ) -> impl Future<Output = Result<BusinessProfile, ExtractBusinessProfileError>> + Send;
+}
+
+/// `CopyWriter` writes the marketing copy of a generated landing page.
+pub trait CopyWriter: Send + Sync + Clone + 'static {
+    fn write_landing_page_copy(
+        &self,
+        website: &Website,
+    ) -> impl Future<Output = Result<LandingPageCopy, WriteCopyError>> + Send;
+}
+
+/// `SiteGenerator` builds a new landing page for a website.
+pub trait SiteGenerator: Send + Sync + Clone + 'static {
+    /// Generate the page from the stored contact and business profile of `website`, recording
+    /// the prompt version used in [GeneratedWebsite::prompt_version].
     fn generate_new_single_page(
         &self,
-        website_source_address: &str,
-    ) -> impl Future<Output = Result<GeneratedWebsite, WebsiteAiError>> + Send;
+        website: &Website,
+    ) -> impl Future<Output = Result<GeneratedWebsite, GenerateWebsiteError>> + Send;
+
+    /// Remove the files hosted for the [Preview] `slug`. Removing a preview that has no files is
+    /// not an error.
+    fn delete_preview(
+        &self,
+        slug: &str,
+    ) -> impl Future<Output = Result<(), DeletePreviewError>> + Send;
 }
@@ -3,102 +3,1142 @@
    website-domain logic is defined here.
 */
 
-use tokio::sync::broadcast::Receiver;
+use std::{pin::pin, sync::Arc, time::Duration};
+
+use chrono::Utc;
+use futures_util::future::join_all;
+use tokio::{
+    sync::{
+        Notify,
+        broadcast::{Receiver, error::RecvError},
+        watch,
+    },
+    time::{Instant, MissedTickBehavior, interval, interval_at, sleep},
+};
+use url::Url;
+use uuid::Uuid;
 
 use super::{
-    models::website::{
-        ContactEvent, CreateWebsiteError, CreateWebsiteRequest, GeneratedWebsiteEvent,
-        GetWebsitesError, Website, WebsiteEvent,
+    models::{
+        automation_failure::{
+            AutomationArtifact, AutomationFailure, GetAutomationArtifactError,
+            ListAutomationFailuresError,
+        },
+        business_profile::UpdateBusinessProfileError,
+        event::{EventEnvelope, GetEventsError},
+        object_storage::GetObjectError,
+        page::CrawlWebsiteError,
+        preview::{GetPreviewError, Preview},
+        prompt::{
+            CreatePromptVersionError, CreatePromptVersionRequest, GetPromptError, ListPromptsError,
+            PromptName, PromptTemplate,
+        },
+        quota::{CheckQuotaError, QuotaKind, Quotas, start_of_month},
+        screenshot::{
+            GetComparisonError, GetScreenshotImageError, GetScreenshotsError,
+            SaveScreenshotRequest, Screenshot, ScreenshotSubject, Viewport,
+        },
+        usage::{GetUsageError, GetUsageRequest, UsageReport},
+        webhook::{
+            CreateWebhookDeliveryRequest, CreateWebhookError, CreateWebhookRequest, DeliveryStatus,
+            GetWebhookDeliveryError, GetWebhookError, ListWebhooksError, SendWebhookError, Webhook,
+            WebhookAttempt, WebhookDelivery, WebhookDeliveryError, WebhookPing,
+        },
+        website::{
+            CorrectContactError, CorrectContactRequest, CreateWebsiteError, CreateWebsiteRequest,
+            DeleteWebsiteError, GetWebsiteError, GetWebsitesError, PipelineStage, StageEvent,
+            StageFailedEvent, StageProgressEvent, UpdateContactError, UpdateGeneratedWebsiteError,
+            Website, WebsiteDetail, WebsiteEvent, WebsiteEventError, WebsiteStatus,
+        },
+    },
+    ports::{
+        AutomationFailureRepository, BusinessProfileExtractor, ContactExtractor, EventRepository,
+        ObjectStorage, OutboxRepository, PreviewRepository, PromptRepository, ScreenshotRepository,
+        SiteGenerator, UsageRepository, WebhookRepository, WebhookSender, WebsiteCrawler,
+        WebsiteNotifier, WebsiteRepository, WebsiteService,
     },
-    ports::{WebsiteAi, WebsiteNotifier, WebsiteRepository, WebsiteService},
 };
 
 /// Canonical implementation of the [WebsiteService] port, through which the website domain API is
 /// consumed.
+///
+/// Crawling, extraction and site generation are independent ports, so each can be swapped for
+/// another implementation (or an in-memory fake) without touching the others.
 #[derive(Debug, Clone)]
-pub struct Service<R, N, A>
+pub struct Service<R, N, C, E, G, O, W>
 where
     R: WebsiteRepository,
     N: WebsiteNotifier,
 {
     repository: R,
     notifier: N,
-    ai: A,
+    crawler: C,
+    extractor: E,
+    site_generator: G,
+    storage: O,
+    webhook_sender: W,
+    quotas: Quotas,
+    /// Wakes the outbox relay up after a change was written.
+    outbox_written: Arc<Notify>,
+}
+
+/// Checks the `kinds` quotas of `owner_id` for the current month. Websites added before owners
+/// were tracked have no owner and are not limited.
+async fn check_quotas<R: UsageRepository>(
+    repository: &R,
+    quotas: &Quotas,
+    owner_id: Option<&str>,
+    kinds: &[QuotaKind],
+) -> Result<(), CheckQuotaError> {
+    let Some(owner_id) = owner_id else {
+        return Ok(());
+    };
+    let usage = repository
+        .get_monthly_usage(owner_id, start_of_month(Utc::now()))
+        .await
+        .map_err(|e| CheckQuotaError::Unknown(e.into()))?;
+    quotas.check(&usage, kinds)?;
+    Ok(())
+}
+
+/// Captures `url` at every [Viewport] and stores the screenshots. Failures are only logged, since
+/// the screenshots are not needed by the rest of the pipeline.
+async fn capture_screenshots<R: ScreenshotRepository, C: WebsiteCrawler, O: ObjectStorage>(
+    repository: &R,
+    crawler: &C,
+    storage: &O,
+    website_id: i64,
+    subject: ScreenshotSubject,
+    url: &str,
+) {
+    for viewport in Viewport::ALL {
+        let saved = async {
+            let captured = crawler.capture_screenshot(url, viewport).await?;
+            let (image_key, thumbnail_key) = Screenshot::keys(website_id, subject, viewport);
+            storage.put_object(&image_key, &captured.image).await?;
+            storage
+                .put_object(&thumbnail_key, &captured.thumbnail)
+                .await?;
+            repository
+                .save_screenshot(&SaveScreenshotRequest {
+                    website_id,
+                    subject,
+                    viewport,
+                    url: url.to_string(),
+                    image_key,
+                    thumbnail_key,
+                })
+                .await?;
+            anyhow::Ok(())
+        }
+        .await;
+        if let Err(e) = saved {
+            tracing::warn!(
+                "failed to capture the {viewport} screenshot of the {subject} site of website {website_id}: {e:?}"
+            );
+        }
+    }
+}
+
+/// How often a [StageProgressEvent] is sent while a stage runs.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Why a pipeline stage failed. Only `message` is shown to users, `error` is logged.
+struct StageError {
+    /// `None` to show the [PipelineStage::failure_message].
+    message: Option<String>,
+    error: anyhow::Error,
+    /// The website was deleted while the stage ran, so the pipeline stops without a failure.
+    deleted: bool,
+}
+
+impl StageError {
+    fn internal(error: impl Into<anyhow::Error>) -> Self {
+        Self {
+            message: None,
+            error: error.into(),
+            deleted: false,
+        }
+    }
+
+    fn deleted(error: impl Into<anyhow::Error>) -> Self {
+        Self {
+            message: None,
+            error: error.into(),
+            deleted: true,
+        }
+    }
+
+    fn get_website(error: GetWebsiteError) -> Self {
+        match error {
+            e @ GetWebsiteError::NotFound { .. } => Self::deleted(e),
+            e => Self::internal(e),
+        }
+    }
+
+    fn update_contact(error: UpdateContactError) -> Self {
+        match error {
+            e @ UpdateContactError::NotFound { .. } => Self::deleted(e),
+            e => Self::internal(e),
+        }
+    }
+
+    fn update_business_profile(error: UpdateBusinessProfileError) -> Self {
+        match error {
+            e @ UpdateBusinessProfileError::NotFound { .. } => Self::deleted(e),
+            e => Self::internal(e),
+        }
+    }
+
+    fn update_generated_website(error: UpdateGeneratedWebsiteError) -> Self {
+        match error {
+            e @ UpdateGeneratedWebsiteError::NotFound { .. } => Self::deleted(e),
+            e => Self::internal(e),
+        }
+    }
+
+    fn quota(error: CheckQuotaError) -> Self {
+        match error {
+            CheckQuotaError::Exceeded(e) => Self {
+                message: Some(e.to_string()),
+                error: e.into(),
+                deleted: false,
+            },
+            CheckQuotaError::Unknown(e) => Self::internal(e),
+        }
+    }
+
+    fn crawl(error: CrawlWebsiteError) -> Self {
+        match error {
+            CrawlWebsiteError::Unknown(e) => Self::internal(e),
+            e => Self {
+                message: Some(e.to_string()),
+                error: e.into(),
+                deleted: false,
+            },
+        }
+    }
 }
 
-impl<R, N, A> Service<R, N, A>
+/// Runs `work` as the `stage` of the pipeline of `website_id`, announcing it, reporting its
+/// progress every [PROGRESS_INTERVAL] and its failure. Progress includes `pages_crawled` when
+/// set. Returns `None` if the stage failed.
+async fn run_stage<N: WebsiteNotifier, T>(
+    notifier: &N,
+    website_id: i64,
+    stage: PipelineStage,
+    pages_crawled: Option<&watch::Receiver<usize>>,
+    work: impl Future<Output = Result<T, StageError>>,
+) -> Option<T> {
+    if let Err(e) = notifier
+        .stage_started(&StageEvent { website_id, stage })
+        .await
+    {
+        tracing::warn!("failed to announce {stage:?} for website {website_id}: {e:?}");
+    }
+    let started = Instant::now();
+    let mut progress = interval_at(started + PROGRESS_INTERVAL, PROGRESS_INTERVAL);
+    progress.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let mut work = pin!(work);
+    let result = loop {
+        tokio::select! {
+            result = &mut work => break result,
+            _ = progress.tick() => {
+                let event = StageProgressEvent {
+                    website_id,
+                    stage,
+                    elapsed_secs: started.elapsed().as_secs(),
+                    pages_crawled: pages_crawled.map(|pages| *pages.borrow()),
+                };
+                if let Err(e) = notifier.stage_progress(&event).await {
+                    tracing::warn!(
+                        "failed to report the progress of {stage:?} for website {website_id}: {e:?}"
+                    );
+                }
+            }
+        }
+    };
+    match result {
+        Ok(value) => Some(value),
+        Err(StageError {
+            error,
+            deleted: true,
+            ..
+        }) => {
+            tracing::debug!("stopping the pipeline of website {website_id}: {error}");
+            None
+        }
+        Err(StageError { message, error, .. }) => {
+            tracing::error!("{stage:?} failed for website {website_id}: {error:?}");
+            let failure = StageFailedEvent {
+                website_id,
+                stage,
+                message: message.unwrap_or_else(|| stage.failure_message().to_string()),
+            };
+            if let Err(e) = notifier.stage_failed(&failure).await {
+                tracing::warn!(
+                    "failed to report the failure of {stage:?} for website {website_id}: {e:?}"
+                );
+            }
+            None
+        }
+    }
+}
+
+/// Most outbox entries published at once.
+const OUTBOX_BATCH: i64 = 100;
+/// How often the outbox is checked for changes written by other instances.
+const OUTBOX_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// How long a relay keeps the outbox to itself without renewing its lease.
+const OUTBOX_LEASE: Duration = Duration::from_secs(30);
+
+/// Publishes `event` through the matching [WebsiteNotifier] method.
+async fn publish<N: WebsiteNotifier>(
+    notifier: &N,
+    event: &WebsiteEvent,
+) -> Result<usize, WebsiteEventError> {
+    match event {
+        WebsiteEvent::WebsiteAdded(website) => notifier.website_added(website).await,
+        WebsiteEvent::FetchedContact(contact) => notifier.contact_fetched(contact).await,
+        WebsiteEvent::GeneratedWebsite(generated_website) => {
+            notifier.website_generated(generated_website.clone()).await
+        }
+        WebsiteEvent::StageStarted(stage) => notifier.stage_started(stage).await,
+        WebsiteEvent::StageProgress(progress) => notifier.stage_progress(progress).await,
+        WebsiteEvent::StageFailed(failure) => notifier.stage_failed(failure).await,
+        WebsiteEvent::ContactUpdated(contact) => notifier.contact_updated(contact).await,
+        WebsiteEvent::WebsiteDeleted(deleted) => notifier.website_deleted(deleted).await,
+    }
+}
+
+/// Publishes the entries of the outbox, oldest first, deleting each once published. Stops at
+/// the first entry that fails to be published, so it is retried before the next ones.
+async fn relay_outbox<R: OutboxRepository, N: WebsiteNotifier>(
+    repository: &R,
+    notifier: &N,
+    relay_id: Uuid,
+) {
+    loop {
+        let entries = match repository
+            .claim_outbox(relay_id, OUTBOX_LEASE, OUTBOX_BATCH)
+            .await
+        {
+            Ok(entries) => entries,
+            Err(e) => {
+                tracing::error!("failed to read the outbox: {e:?}");
+                return;
+            }
+        };
+        for entry in &entries {
+            if let Err(e) = publish(notifier, &entry.event).await {
+                tracing::error!("failed to publish outbox entry {}: {e:?}", entry.id);
+                return;
+            }
+            // Published again by the next run if this fails.
+            if let Err(e) = repository.delete_outbox_entry(entry.id).await {
+                tracing::error!("failed to delete outbox entry {}: {e:?}", entry.id);
+                return;
+            }
+        }
+        if (entries.len() as i64) < OUTBOX_BATCH {
+            return;
+        }
+    }
+}
+
+/// Most events dispatched, or deliveries sent, at once.
+const WEBHOOK_BATCH: i64 = 100;
+/// How often the deliveries due for a retry are looked for.
+const WEBHOOK_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// How long a claimed delivery is left to one instance to send.
+const WEBHOOK_LEASE: Duration = Duration::from_secs(60);
+/// Most deliveries listed per webhook.
+const WEBHOOK_DELIVERY_LOG_LIMIT: i64 = 100;
+
+/// Sends `delivery` to `webhook` and records the outcome, scheduling a retry when it failed.
+async fn deliver_webhook<R: WebhookRepository, W: WebhookSender>(
+    repository: &R,
+    webhook_sender: &W,
+    webhook: &Webhook,
+    delivery: &WebhookDelivery,
+) -> Result<WebhookDelivery, WebhookDeliveryError> {
+    let attempt = match webhook_sender.send(webhook, delivery).await {
+        Ok(status) => WebhookAttempt {
+            status: DeliveryStatus::Succeeded,
+            response_status: Some(status),
+            error: None,
+            next_attempt_at: None,
+        },
+        Err(e) => {
+            tracing::warn!(
+                "failed to send delivery {} to webhook {}: {e:?}",
+                delivery.id,
+                webhook.id
+            );
+            let next_attempt_at = delivery.retry_at(Utc::now());
+            WebhookAttempt {
+                status: match next_attempt_at {
+                    Some(_) => DeliveryStatus::Pending,
+                    None => DeliveryStatus::Failed,
+                },
+                response_status: match e {
+                    SendWebhookError::Rejected { status } => Some(status),
+                    SendWebhookError::ForbiddenAddress { .. } | SendWebhookError::Unknown(_) => {
+                        None
+                    }
+                },
+                error: Some(e.to_string()),
+                next_attempt_at,
+            }
+        }
+    };
+    repository
+        .record_webhook_attempt(delivery.id, &attempt)
+        .await
+}
+
+/// Turns the events logged since the last run into deliveries, then sends the due ones.
+async fn dispatch_webhooks<R: WebhookRepository, W: WebhookSender>(
+    repository: &R,
+    webhook_sender: &W,
+) {
+    loop {
+        match repository.dispatch_webhook_events(WEBHOOK_BATCH).await {
+            Ok(dispatched) if (dispatched as i64) < WEBHOOK_BATCH => break,
+            Ok(_) => {}
+            Err(e) => {
+                tracing::error!("failed to dispatch the events to the webhooks: {e:?}");
+                break;
+            }
+        }
+    }
+    loop {
+        let claimed = match repository
+            .claim_webhook_deliveries(WEBHOOK_BATCH, WEBHOOK_LEASE)
+            .await
+        {
+            Ok(claimed) => claimed,
+            Err(e) => {
+                tracing::error!("failed to claim the due webhook deliveries: {e:?}");
+                return;
+            }
+        };
+        join_all(claimed.iter().map(async |(webhook, delivery)| {
+            if let Err(e) = deliver_webhook(repository, webhook_sender, webhook, delivery).await {
+                tracing::error!("failed to record delivery {}: {e:?}", delivery.id);
+            }
+        }))
+        .await;
+        if (claimed.len() as i64) < WEBHOOK_BATCH {
+            return;
+        }
+    }
+}
+
+impl<R, N, C, E, G, O, W> Service<R, N, C, E, G, O, W>
 where
-    R: WebsiteRepository,
+    R: WebsiteRepository
+        + UsageRepository
+        + PromptRepository
+        + PreviewRepository
+        + AutomationFailureRepository
+        + ScreenshotRepository
+        + EventRepository
+        + OutboxRepository
+        + WebhookRepository,
     N: WebsiteNotifier,
-    A: WebsiteAi,
+    C: WebsiteCrawler,
+    E: ContactExtractor + BusinessProfileExtractor,
+    G: SiteGenerator,
+    O: ObjectStorage,
+    W: WebhookSender,
 {
-    pub fn new(repository: R, notifier: N, ai: A) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        repository: R,
+        notifier: N,
+        crawler: C,
+        extractor: E,
+        site_generator: G,
+        storage: O,
+        webhook_sender: W,
+        quotas: Quotas,
+    ) -> Self {
         Self {
             repository,
             notifier,
-            ai,
+            crawler,
+            extractor,
+            site_generator,
+            storage,
+            webhook_sender,
+            quotas,
+            outbox_written: Arc::new(Notify::new()),
         }
     }
+
+    /// Publishes the events of the outbox to the [WebsiteNotifier] in a background task, in
+    /// order and at least once. The outbox is checked after every change written by this
+    /// instance, and every [OUTBOX_POLL_INTERVAL] for the changes of the others.
+    pub fn spawn_outbox_relay(&self) {
+        let repository = self.repository.clone();
+        let notifier = self.notifier.clone();
+        let outbox_written = self.outbox_written.clone();
+        let relay_id = Uuid::new_v4();
+
+        tokio::spawn(async move {
+            loop {
+                relay_outbox(&repository, &notifier, relay_id).await;
+                tokio::select! {
+                    _ = outbox_written.notified() => {}
+                    _ = sleep(OUTBOX_POLL_INTERVAL) => {}
+                }
+            }
+        });
+    }
+
+    /// Delivers the events to the webhooks subscribed to them in a background task. New events
+    /// are dispatched as they are notified, and due retries every [WEBHOOK_POLL_INTERVAL].
+    pub fn spawn_webhook_dispatcher(&self) {
+        let repository = self.repository.clone();
+        let webhook_sender = self.webhook_sender.clone();
+        let mut rx = self.notifier.get_receiver();
+
+        tokio::spawn(async move {
+            let mut poll = interval(WEBHOOK_POLL_INTERVAL);
+            poll.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                tokio::select! {
+                    event = rx.recv() => {
+                        if let Err(RecvError::Closed) = event {
+                            return;
+                        }
+                        // The events are read from the log, one run covers all the notified ones.
+                        while rx.try_recv().is_ok() {}
+                    }
+                    _ = poll.tick() => {}
+                }
+                dispatch_webhooks(&repository, &webhook_sender).await;
+            }
+        });
+    }
+
+    /// Gets a [Website], as if it did not exist when it belongs to another owner.
+    async fn get_owned_website(
+        &self,
+        owner_id: &str,
+        website_id: i64,
+    ) -> Result<Website, GetWebsiteError> {
+        let website = self.repository.get_website(website_id).await?;
+        if website.owner_id.as_deref() != Some(owner_id) {
+            return Err(GetWebsiteError::NotFound { id: website_id });
+        }
+        Ok(website)
+    }
+
+    /// Runs the crawl, contact extraction and generation pipeline for `website` in a background
+    /// task. Pages stored by a previous crawl are reused when the server reports them unchanged.
+    /// The quotas of the owner are checked before each stage, stopping the pipeline once they
+    /// are exceeded. Every [PipelineStage] is announced, and the pipeline stops at the first
+    /// failing one.
+    fn spawn_pipeline(&self, website: &Website) {
+        let crawler = self.crawler.clone();
+        let extractor = self.extractor.clone();
+        let site_generator = self.site_generator.clone();
+        let notifier = self.notifier.clone();
+        let repository = self.repository.clone();
+        let storage = self.storage.clone();
+        let outbox_written = self.outbox_written.clone();
+        let quotas = self.quotas;
+        let website = website.clone();
+        let website_source_address = website.source_address.clone();
+        let website_id = website.id;
+
+        tokio::spawn(async move {
+            let check = async |kinds: &[QuotaKind]| {
+                check_quotas(&repository, &quotas, website.owner_id.as_deref(), kinds)
+                    .await
+                    .map_err(StageError::quota)
+            };
+
+            let (pages_crawled, pages_crawled_rx) = watch::channel(0);
+            let Some((source_url, crawled)) = run_stage(
+                &notifier,
+                website_id,
+                PipelineStage::Crawl,
+                Some(&pages_crawled_rx),
+                async {
+                    check(&[QuotaKind::LlmSpend]).await?;
+                    let source_url = Url::parse(&website_source_address).map_err(|_| {
+                        StageError::crawl(CrawlWebsiteError::InvalidUrl {
+                            source_address: website_source_address.clone(),
+                        })
+                    })?;
+                    let previous_pages = repository
+                        .get_pages(website_id)
+                        .await
+                        .map_err(StageError::internal)?;
+                    let crawled = crawler
+                        .get_full_website(&website_source_address, &previous_pages, &pages_crawled)
+                        .await
+                        .map_err(StageError::crawl)?;
+                    repository
+                        .save_pages(website_id, &crawled.pages)
+                        .await
+                        .map_err(StageError::internal)?;
+                    repository
+                        .update_render_mode(website_id, crawled.render_mode)
+                        .await
+                        .map_err(StageError::internal)?;
+                    capture_screenshots(
+                        &repository,
+                        &crawler,
+                        &storage,
+                        website_id,
+                        ScreenshotSubject::Original,
+                        &website_source_address,
+                    )
+                    .await;
+                    Ok((source_url, crawled))
+                },
+            )
+            .await
+            else {
+                return;
+            };
+
+            let Some(()) = run_stage(
+                &notifier,
+                website_id,
+                PipelineStage::ContactExtraction,
+                None,
+                async {
+                    check(&[QuotaKind::LlmSpend]).await?;
+                    let stored = repository
+                        .get_website(website_id)
+                        .await
+                        .map_err(StageError::get_website)?;
+                    let contact = extractor
+                        .get_contact(&website, &crawled)
+                        .await
+                        .map_err(StageError::internal)?
+                        .with_manual_fields(&stored.contact());
+                    repository
+                        .update_contact(website_id, &contact)
+                        .await
+                        .map_err(StageError::update_contact)?;
+                    outbox_written.notify_one();
+                    Ok(())
+                },
+            )
+            .await
+            else {
+                return;
+            };
+
+            let Some(()) = run_stage(
+                &notifier,
+                website_id,
+                PipelineStage::BusinessProfile,
+                None,
+                async {
+                    check(&[QuotaKind::LlmSpend]).await?;
+                    let business_profile = extractor
+                        .get_business_profile(&website, &crawled)
+                        .await
+                        .map_err(StageError::internal)?
+                        .with_normalized_phone_numbers(&source_url);
+                    repository
+                        .update_business_profile(website_id, &business_profile)
+                        .await
+                        .map_err(StageError::update_business_profile)?;
+                    Ok(())
+                },
+            )
+            .await
+            else {
+                return;
+            };
+
+            let Some(()) = run_stage(
+                &notifier,
+                website_id,
+                PipelineStage::Generation,
+                None,
+                async {
+                    check(&[QuotaKind::Generations]).await?;
+                    repository
+                        .record_generation(&website)
+                        .await
+                        .map_err(StageError::internal)?;
+                    let updated_website = repository
+                        .get_website(website_id)
+                        .await
+                        .map_err(StageError::get_website)?;
+                    let generated_website = site_generator
+                        .generate_new_single_page(&updated_website)
+                        .await
+                        .map_err(StageError::internal)?;
+                    repository
+                        .update_generated_website(website_id, &generated_website)
+                        .await
+                        .map_err(StageError::update_generated_website)?;
+                    outbox_written.notify_one();
+                    capture_screenshots(
+                        &repository,
+                        &crawler,
+                        &storage,
+                        website_id,
+                        ScreenshotSubject::Generated,
+                        generated_website.url.as_str(),
+                    )
+                    .await;
+                    Ok(())
+                },
+            )
+            .await
+            else {
+                return;
+            };
+
+            tracing::debug!("pipeline finished for website {}", website_id);
+        });
+    }
 }
 
-impl<R, N, A> WebsiteService for Service<R, N, A>
+impl<R, N, C, E, G, O, W> WebsiteService for Service<R, N, C, E, G, O, W>
 where
-    R: WebsiteRepository,
+    R: WebsiteRepository
+        + UsageRepository
+        + PromptRepository
+        + PreviewRepository
+        + AutomationFailureRepository
+        + ScreenshotRepository
+        + EventRepository
+        + OutboxRepository
+        + WebhookRepository,
     N: WebsiteNotifier,
-    A: WebsiteAi,
+    C: WebsiteCrawler,
+    E: ContactExtractor + BusinessProfileExtractor,
+    G: SiteGenerator,
+    O: ObjectStorage,
+    W: WebhookSender,
 {
     /// Create the [Website] specified in `req`
     ///
     /// # Errors
     ///
+    /// - [CreateWebsiteError::QuotaExceeded] if the owner reached the monthly websites or LLM
+    ///   spend quota.
     /// - Propagates any [CreateWebsiteError] returned by the [WebsiteRepository].
     async fn create_website(
         &self,
         req: &CreateWebsiteRequest,
     ) -> Result<Website, CreateWebsiteError> {
+        check_quotas(
+            &self.repository,
+            &self.quotas,
+            Some(&req.owner_id),
+            &[QuotaKind::Websites, QuotaKind::LlmSpend],
+        )
+        .await
+        .map_err(|e| match e {
+            CheckQuotaError::Exceeded(e) => CreateWebsiteError::QuotaExceeded(e),
+            CheckQuotaError::Unknown(e) => CreateWebsiteError::Unknown(e),
+        })?;
         let website = self.repository.create_website(req).await?;
-        let ai = self.ai.clone();
-        let notifier = self.notifier.clone();
-        let repository = self.repository.clone();
-        let website_source_address = website.source_address.clone();
-        let website_id = website.id;
-        notifier
-            .website_added(&website)
+        self.outbox_written.notify_one();
+
+        self.spawn_pipeline(&website);
+        Ok(website)
+    }
+    async fn get_websites(&self, owner_id: &str) -> Result<Vec<Website>, GetWebsitesError> {
+        self.repository.get_websites(owner_id).await
+    }
+
+    async fn get_website(&self, website_id: i64) -> Result<Website, GetWebsiteError> {
+        self.repository.get_website(website_id).await
+    }
+
+    /// Re-run the pipeline for an existing [Website] of an owner
+    ///
+    /// # Errors
+    ///
+    /// - [GetWebsiteError::NotFound] if the [Website] belongs to another owner.
+    /// - Propagates any [GetWebsiteError] returned by the [WebsiteRepository].
+    async fn refresh_website(
+        &self,
+        owner_id: &str,
+        website_id: i64,
+    ) -> Result<Website, GetWebsiteError> {
+        let website = self.get_owned_website(owner_id, website_id).await?;
+        self.spawn_pipeline(&website);
+        Ok(website)
+    }
+
+    /// Get a [Website] of an owner, with its [WebsiteStatus] and crawl summary
+    ///
+    /// # Errors
+    ///
+    /// - [GetWebsiteError::NotFound] if the [Website] belongs to another owner.
+    /// - Propagates any [GetWebsiteError] returned by the [WebsiteRepository].
+    async fn get_website_detail(
+        &self,
+        owner_id: &str,
+        website_id: i64,
+    ) -> Result<WebsiteDetail, GetWebsiteError> {
+        let website = self.get_owned_website(owner_id, website_id).await?;
+        let last_event = self
+            .repository
+            .get_last_event(website_id, &WebsiteStatus::EVENT_TYPES)
             .await
-            .map_err(|_| CreateWebsiteError::NotificationFailed)?;
+            .map_err(|e| GetWebsiteError::Unknown(e.into()))?;
+        let crawl = self
+            .repository
+            .get_crawl_summary(website_id)
+            .await
+            .map_err(|e| GetWebsiteError::Unknown(e.into()))?;
+        Ok(WebsiteDetail {
+            status: WebsiteStatus::new(&website, last_event.as_ref().map(|e| &e.event)),
+            crawl,
+            website,
+        })
+    }
 
-        tokio::spawn(async move {
-            if let Ok(content) = ai.get_full_website(&website_source_address).await
-                && let Ok(contact) = ai.get_contact(&content).await
-                && let Ok(_) = repository.update_contact(website_id, &contact).await
-                && let Ok(_) = notifier
-                    .contact_fetched(&ContactEvent {
-                        website_id,
-                        contact: contact.clone(),
-                    })
-                    .await
-                && let Ok(generated_website) = ai.generate_new_single_page(&content).await
-                && let Ok(_) = notifier
-                    .website_generated(GeneratedWebsiteEvent {
-                        website_id,
-                        generated_website,
-                    })
-                    .await
-            {
-                tracing::debug!("Contact fetched event sent for website {}", website_id);
-            } else {
-                tracing::error!(
-                    "background task for get_contact failed for website: {website_source_address} "
+    /// Correct the [Contact](super::models::website::Contact) of a [Website] of an owner
+    ///
+    /// # Errors
+    ///
+    /// - [CorrectContactError::NotFound] if the [Website] belongs to another owner.
+    /// - Propagates any [CorrectContactError] returned by the [WebsiteRepository].
+    async fn correct_contact(
+        &self,
+        req: &CorrectContactRequest,
+    ) -> Result<Website, CorrectContactError> {
+        let not_found = |e| match e {
+            GetWebsiteError::NotFound { id } => CorrectContactError::NotFound { id },
+            GetWebsiteError::Unknown(e) => CorrectContactError::Unknown(e),
+        };
+        let website = self
+            .get_owned_website(&req.owner_id, req.website_id)
+            .await
+            .map_err(not_found)?;
+        let contact = req.apply(website.contact());
+        self.repository
+            .correct_contact(req.website_id, &contact)
+            .await?;
+        self.outbox_written.notify_one();
+        self.repository
+            .get_website(req.website_id)
+            .await
+            .map_err(not_found)
+    }
+
+    /// Delete a [Website] of an owner, then the images of its screenshots
+    ///
+    /// # Errors
+    ///
+    /// - [DeleteWebsiteError::NotFound] if the [Website] belongs to another owner.
+    /// - Propagates any [DeleteWebsiteError] returned by the [WebsiteRepository].
+    async fn delete_website(
+        &self,
+        owner_id: &str,
+        website_id: i64,
+    ) -> Result<(), DeleteWebsiteError> {
+        self.get_owned_website(owner_id, website_id)
+            .await
+            .map_err(|e| match e {
+                GetWebsiteError::NotFound { id } => DeleteWebsiteError::NotFound { id },
+                GetWebsiteError::Unknown(e) => DeleteWebsiteError::Unknown(e),
+            })?;
+        let screenshots = self
+            .repository
+            .get_screenshots(&[website_id])
+            .await
+            .map_err(|e| DeleteWebsiteError::Unknown(e.into()))?;
+        let previews = self
+            .repository
+            .list_previews(website_id)
+            .await
+            .map_err(|e| DeleteWebsiteError::Unknown(e.into()))?;
+        self.repository.delete_website(website_id).await?;
+        self.outbox_written.notify_one();
+
+        // The website is deleted already, images and previews left behind are only logged.
+        for screenshot in &screenshots {
+            for key in [&screenshot.image_key, &screenshot.thumbnail_key] {
+                if let Err(e) = self.storage.delete_object(key).await {
+                    tracing::warn!(
+                        "failed to delete screenshot {key} of website {website_id}: {e:?}"
+                    );
+                }
+            }
+        }
+        for preview in &previews {
+            if let Err(e) = self.site_generator.delete_preview(&preview.slug).await {
+                tracing::warn!(
+                    "failed to delete preview {} of website {website_id}: {e:?}",
+                    preview.slug
                 );
             }
-        });
-        Ok(website)
+        }
+        Ok(())
+    }
+
+    /// Get the LLM usage of an owner
+    ///
+    /// # Errors
+    ///
+    /// - [GetUsageError::InvalidRange] if the range is empty.
+    /// - Propagates any [GetUsageError] returned by the [UsageRepository].
+    async fn get_usage(&self, req: &GetUsageRequest) -> Result<UsageReport, GetUsageError> {
+        if req.from >= req.to {
+            return Err(GetUsageError::InvalidRange {
+                from: req.from,
+                to: req.to,
+            });
+        }
+        self.repository.get_llm_usage(req).await
+    }
+
+    async fn list_prompts(&self) -> Result<Vec<PromptTemplate>, ListPromptsError> {
+        self.repository.list_prompts().await
+    }
+
+    async fn get_prompt_versions(
+        &self,
+        name: PromptName,
+    ) -> Result<Vec<PromptTemplate>, GetPromptError> {
+        self.repository.get_prompt_versions(name).await
     }
-    async fn get_websites(&self) -> Result<Vec<Website>, GetWebsitesError> {
-        self.repository.get_websites().await
+
+    /// Store a new version of a prompt
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any [CreatePromptVersionError] returned by the [PromptRepository].
+    async fn create_prompt_version(
+        &self,
+        req: &CreatePromptVersionRequest,
+    ) -> Result<PromptTemplate, CreatePromptVersionError> {
+        self.repository.create_prompt_version(req).await
+    }
+
+    /// Get a hosted preview
+    ///
+    /// # Errors
+    ///
+    /// - [GetPreviewError::Expired] if the preview is past its expiry.
+    /// - Propagates any [GetPreviewError] returned by the [PreviewRepository].
+    async fn get_preview(&self, slug: &str) -> Result<Preview, GetPreviewError> {
+        let preview = self.repository.get_preview(slug).await?;
+        if let Some(expires_at) = preview.expires_at
+            && preview.is_expired(Utc::now())
+        {
+            return Err(GetPreviewError::Expired {
+                slug: preview.slug,
+                expires_at,
+            });
+        }
+        Ok(preview)
+    }
+
+    async fn list_automation_failures(
+        &self,
+        website_id: i64,
+    ) -> Result<Vec<AutomationFailure>, ListAutomationFailuresError> {
+        self.repository.list_automation_failures(website_id).await
     }
 
-    fn get_receiver(&self) -> Receiver<WebsiteEvent> {
+    async fn get_automation_artifact(
+        &self,
+        id: i64,
+        artifact: AutomationArtifact,
+    ) -> Result<Vec<u8>, GetAutomationArtifactError> {
+        self.repository.get_automation_artifact(id, artifact).await
+    }
+
+    async fn get_screenshots(
+        &self,
+        website_ids: &[i64],
+    ) -> Result<Vec<Screenshot>, GetScreenshotsError> {
+        self.repository.get_screenshots(website_ids).await
+    }
+
+    /// Get the screenshots of a [Website] of an owner
+    ///
+    /// # Errors
+    ///
+    /// - [GetComparisonError::NotFound] if the [Website] belongs to another owner.
+    async fn get_comparison(
+        &self,
+        owner_id: &str,
+        website_id: i64,
+    ) -> Result<Vec<Screenshot>, GetComparisonError> {
+        self.get_owned_website(owner_id, website_id)
+            .await
+            .map_err(|e| match e {
+                GetWebsiteError::NotFound { id } => GetComparisonError::NotFound { id },
+                GetWebsiteError::Unknown(e) => GetComparisonError::Unknown(e),
+            })?;
+        self.repository
+            .get_screenshots(&[website_id])
+            .await
+            .map_err(|e| GetComparisonError::Unknown(e.into()))
+    }
+
+    /// Get the image of a screenshot of a [Website] of an owner from the [ObjectStorage]
+    ///
+    /// # Errors
+    ///
+    /// - [GetScreenshotImageError::NotFound] if the [Website] belongs to another owner, the
+    ///   screenshot was never captured, or its image is missing from the [ObjectStorage].
+    async fn get_screenshot_image(
+        &self,
+        owner_id: &str,
+        website_id: i64,
+        subject: ScreenshotSubject,
+        viewport: Viewport,
+        thumbnail: bool,
+    ) -> Result<Vec<u8>, GetScreenshotImageError> {
+        let not_found = || GetScreenshotImageError::NotFound {
+            website_id,
+            subject,
+            viewport,
+        };
+        self.get_owned_website(owner_id, website_id)
+            .await
+            .map_err(|e| match e {
+                GetWebsiteError::NotFound { .. } => not_found(),
+                GetWebsiteError::Unknown(e) => GetScreenshotImageError::Unknown(e),
+            })?;
+        let screenshot = self
+            .repository
+            .get_screenshots(&[website_id])
+            .await
+            .map_err(|e| GetScreenshotImageError::Unknown(e.into()))?
+            .into_iter()
+            .find(|screenshot| screenshot.subject == subject && screenshot.viewport == viewport)
+            .ok_or_else(not_found)?;
+        let key = if thumbnail {
+            &screenshot.thumbnail_key
+        } else {
+            &screenshot.image_key
+        };
+        self.storage.get_object(key).await.map_err(|e| match e {
+            GetObjectError::NotFound { .. } => not_found(),
+            GetObjectError::Unknown(e) => GetScreenshotImageError::Unknown(e),
+        })
+    }
+
+    async fn get_events_after(
+        &self,
+        tenant: &str,
+        last_event_id: i64,
+        limit: i64,
+    ) -> Result<Vec<EventEnvelope>, GetEventsError> {
+        self.repository
+            .get_tenant_events_after(tenant, last_event_id, limit)
+            .await
+    }
+
+    fn get_receiver(&self) -> Receiver<EventEnvelope> {
         self.notifier.get_receiver()
     }
+
+    async fn create_webhook(
+        &self,
+        req: &CreateWebhookRequest,
+    ) -> Result<Webhook, CreateWebhookError> {
+        self.repository.create_webhook(req).await
+    }
+
+    async fn list_webhooks(&self, tenant: &str) -> Result<Vec<Webhook>, ListWebhooksError> {
+        self.repository.list_webhooks(tenant).await
+    }
+
+    async fn delete_webhook(&self, tenant: &str, webhook_id: i64) -> Result<(), GetWebhookError> {
+        self.repository.delete_webhook(tenant, webhook_id).await
+    }
+
+    async fn list_webhook_deliveries(
+        &self,
+        tenant: &str,
+        webhook_id: i64,
+    ) -> Result<Vec<WebhookDelivery>, GetWebhookError> {
+        self.repository.get_webhook(tenant, webhook_id).await?;
+        self.repository
+            .list_webhook_deliveries(webhook_id, WEBHOOK_DELIVERY_LOG_LIMIT)
+            .await
+            .map_err(|e| GetWebhookError::Unknown(e.into()))
+    }
+
+    /// Send a [WebhookPing] to a [Webhook], without retrying
+    ///
+    /// # Errors
+    ///
+    /// - [GetWebhookError::NotFound] if `tenant` has no [Webhook] with `webhook_id`.
+    async fn test_webhook(
+        &self,
+        tenant: &str,
+        webhook_id: i64,
+    ) -> Result<WebhookDelivery, GetWebhookError> {
+        let webhook = self.repository.get_webhook(tenant, webhook_id).await?;
+        let ping = WebhookPing::Ping {
+            webhook_id,
+            occurred_at: Utc::now(),
+        };
+        let sent = async {
+            let delivery = self
+                .repository
+                .create_webhook_delivery(&CreateWebhookDeliveryRequest {
+                    webhook_id,
+                    event_id: None,
+                    replay_of: None,
+                    payload: serde_json::to_string(&ping)?,
+                })
+                .await?;
+            Ok(
+                deliver_webhook(&self.repository, &self.webhook_sender, &webhook, &delivery)
+                    .await?,
+            )
+        };
+        sent.await.map_err(GetWebhookError::Unknown)
+    }
+
+    async fn replay_webhook_delivery(
+        &self,
+        tenant: &str,
+        webhook_id: i64,
+        delivery_id: i64,
+    ) -> Result<WebhookDelivery, GetWebhookDeliveryError> {
+        let webhook = self
+            .repository
+            .get_webhook(tenant, webhook_id)
+            .await
+            .map_err(|e| match e {
+                GetWebhookError::NotFound { .. } => {
+                    GetWebhookDeliveryError::NotFound { id: delivery_id }
+                }
+                GetWebhookError::Unknown(e) => GetWebhookDeliveryError::Unknown(e),
+            })?;
+        let replayed = self
+            .repository
+            .get_webhook_delivery(webhook_id, delivery_id)
+            .await?;
+        let sent = async {
+            let delivery = self
+                .repository
+                .create_webhook_delivery(&CreateWebhookDeliveryRequest {
+                    webhook_id,
+                    event_id: replayed.event_id,
+                    replay_of: Some(replayed.id),
+                    payload: replayed.payload,
+                })
+                .await?;
+            deliver_webhook(&self.repository, &self.webhook_sender, &webhook, &delivery).await
+        };
+        sent.await
+            .map_err(|e| GetWebhookDeliveryError::Unknown(e.into()))
+    }
 }
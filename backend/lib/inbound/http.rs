@@ -6,13 +6,33 @@
 pub mod extractors;
 pub mod handlers;
 
-use std::{sync::Arc, time::Duration};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
 use anyhow::Context;
-use axum::routing::{any, post};
+use axum::routing::{any, delete, post};
 use axum::{Router, routing::get};
+use handlers::automation_failures::{
+    get_automation_page_source, get_automation_screenshot, list_automation_failures,
+};
 use handlers::create_website::create_website;
+use handlers::delete_website::delete_website;
+use handlers::event_stream::{LAST_EVENT_ID, event_stream};
+use handlers::get_usage::get_usage;
+use handlers::get_website::get_website;
 use handlers::get_websites::get_websites;
+use handlers::preview::{preview_file, preview_index, preview_root};
+use handlers::prompts::{create_prompt_version, get_prompt_versions, list_prompts};
+use handlers::refresh_website::refresh_website;
+use handlers::screenshots::{get_comparison, get_screenshot_image};
+use handlers::update_website::update_website;
+use handlers::webhooks::{
+    create_webhook, delete_webhook, list_webhook_deliveries, list_webhooks,
+    replay_webhook_delivery, test_webhook,
+};
 use handlers::websocket::websocket;
 use http::{
     Method,
@@ -30,6 +50,11 @@ use crate::domain::website::ports::WebsiteService;
 pub struct HttpServerConfig<'a> {
     pub port: &'a str,
     pub jwks: &'a str,
+    pub admin_user_ids: &'a [String],
+    /// Directory the generated sites served under `/preview/{slug}/` are read from.
+    pub preview_dir: &'a Path,
+    /// HTML shown in the banner on top of every preview.
+    pub preview_banner_text: &'a str,
 }
 
 #[derive(Clone)]
@@ -37,6 +62,9 @@ pub struct HttpServerConfig<'a> {
 pub struct AppState<WS: WebsiteService> {
     website_service: Arc<WS>,
     jwt_verifier: Arc<RemoteJwksVerifier>,
+    admin_user_ids: Arc<[String]>,
+    preview_dir: Arc<PathBuf>,
+    preview_banner_text: Arc<str>,
 }
 
 /// The application's HTTP server. The underlying HTTP package is opaque to module consumers.
@@ -64,15 +92,19 @@ impl HttpServer {
         let state = AppState {
             website_service: Arc::new(website_service),
             jwt_verifier: Arc::new(jwt_verifier),
+            admin_user_ids: config.admin_user_ids.into(),
+            preview_dir: Arc::new(config.preview_dir.to_path_buf()),
+            preview_banner_text: config.preview_banner_text.into(),
         };
 
         let cors = CorsLayer::new()
-            .allow_methods([Method::GET, Method::POST])
-            .allow_headers([AUTHORIZATION, ACCEPT, CONTENT_TYPE])
+            .allow_methods([Method::GET, Method::POST, Method::PATCH, Method::DELETE])
+            .allow_headers([AUTHORIZATION, ACCEPT, CONTENT_TYPE, LAST_EVENT_ID])
             .allow_origin(Any);
 
         let router = axum::Router::new()
             .nest("/api", api_routes())
+            .nest("/preview", preview_routes())
             .layer(cors)
             .layer(trace_layer)
             .with_state(state)
@@ -98,6 +130,52 @@ impl HttpServer {
 fn api_routes<WS: WebsiteService>() -> Router<AppState<WS>> {
     Router::new()
         .route("/website", post(create_website))
+        .route(
+            "/website/{id}",
+            get(get_website)
+                .patch(update_website)
+                .delete(delete_website),
+        )
+        .route("/website/{id}/refresh", post(refresh_website))
+        .route("/website/{id}/comparison", get(get_comparison))
+        .route(
+            "/website/{id}/screenshots/{subject}/{viewport}",
+            get(get_screenshot_image),
+        )
         .route("/websites", get(get_websites))
+        .route("/usage", get(get_usage))
+        .route("/webhooks", get(list_webhooks).post(create_webhook))
+        .route("/webhooks/{id}", delete(delete_webhook))
+        .route("/webhooks/{id}/deliveries", get(list_webhook_deliveries))
+        .route("/webhooks/{id}/test", post(test_webhook))
+        .route(
+            "/webhooks/{id}/deliveries/{delivery_id}/replay",
+            post(replay_webhook_delivery),
+        )
+        .route("/admin/prompts", get(list_prompts))
+        .route(
+            "/admin/prompts/{name}",
+            get(get_prompt_versions).post(create_prompt_version),
+        )
+        .route(
+            "/admin/website/{id}/failures",
+            get(list_automation_failures),
+        )
+        .route(
+            "/admin/failures/{id}/screenshot",
+            get(get_automation_screenshot),
+        )
+        .route(
+            "/admin/failures/{id}/page_source",
+            get(get_automation_page_source),
+        )
         .route("/events", any(websocket::<WS>))
+        .route("/events/stream", get(event_stream::<WS>))
+}
+
+fn preview_routes<WS: WebsiteService>() -> Router<AppState<WS>> {
+    Router::new()
+        .route("/{slug}", get(preview_root))
+        .route("/{slug}/", get(preview_index::<WS>))
+        .route("/{slug}/{*path}", get(preview_file::<WS>))
 }
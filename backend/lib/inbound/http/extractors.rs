@@ -17,6 +17,12 @@ pub struct Jwt<WS> {
     pub user_id: String,
 }
 
+/// A [Jwt] whose subject is one of the configured admins.
+pub struct AdminJwt<WS> {
+    _marker: PhantomData<WS>,
+    pub user_id: String,
+}
+
 pub struct QueryJwt<WS> {
     _marker: PhantomData<WS>,
     pub user_id: String,
@@ -55,6 +61,29 @@ where
     }
 }
 
+impl<S, WS> FromRequestParts<S> for AdminJwt<WS>
+where
+    WS: WebsiteService,
+    AppState<WS>: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Jwt { user_id, .. } = Jwt::<WS>::from_request_parts(parts, state).await?;
+        let state = AppState::from_ref(state);
+        if !state.admin_user_ids.contains(&user_id) {
+            return Err(ApiError::Forbidden(format!(
+                "user {user_id} is not an admin"
+            )));
+        }
+        Ok(AdminJwt::<WS> {
+            _marker: PhantomData,
+            user_id,
+        })
+    }
+}
+
 #[derive(Deserialize, Debug)]
 struct QueryToken {
     token: String,
@@ -5,10 +5,32 @@ use axum::{
 use http::StatusCode;
 use serde::Serialize;
 
+use crate::domain::website::models::quota::{QuotaExceeded, QuotaKind};
+
+pub mod automation_failures;
 pub mod create_website;
+pub mod delete_website;
+pub mod event_stream;
+pub mod get_usage;
+pub mod get_website;
 pub mod get_websites;
+pub mod preview;
+pub mod prompts;
+pub mod refresh_website;
+pub mod screenshots;
+pub mod update_website;
+pub mod webhooks;
 pub mod websocket;
 
+impl From<QuotaExceeded> for ApiError {
+    fn from(e: QuotaExceeded) -> Self {
+        match e.kind {
+            QuotaKind::LlmSpend => Self::PaymentRequired(e.to_string()),
+            QuotaKind::Generations | QuotaKind::Websites => Self::TooManyRequests(e.to_string()),
+        }
+    }
+}
+
 /// Represents a response containing an API error and a status code
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct ApiResponseBody<T: Serialize + PartialEq> {
@@ -61,6 +83,14 @@ pub enum ApiError {
     UnprocessableEntity(String),
     InternalServerError(String),
     Unauthorized(String),
+    Forbidden(String),
+    NotFound(String),
+    /// The resource existed but is no longer available.
+    Gone(String),
+    /// A spending quota was exceeded.
+    PaymentRequired(String),
+    /// A rate or count quota was exceeded.
+    TooManyRequests(String),
 }
 
 impl IntoResponse for ApiError {
@@ -90,6 +120,17 @@ impl IntoResponse for ApiError {
                 )
                     .into_response()
             }
+            Forbidden(e) => {
+                tracing::warn!("{}", e);
+                (
+                    StatusCode::FORBIDDEN,
+                    Json(ApiResponseBody::new_error(
+                        StatusCode::FORBIDDEN,
+                        "Forbidden".to_string(),
+                    )),
+                )
+                    .into_response()
+            }
             UnprocessableEntity(e) => {
                 tracing::warn!("{}", e);
                 (
@@ -101,6 +142,38 @@ impl IntoResponse for ApiError {
                 )
                     .into_response()
             }
+            NotFound(e) => {
+                tracing::warn!("{}", e);
+                (
+                    StatusCode::NOT_FOUND,
+                    Json(ApiResponseBody::new_error(StatusCode::NOT_FOUND, e)),
+                )
+                    .into_response()
+            }
+            Gone(e) => {
+                tracing::warn!("{}", e);
+                (
+                    StatusCode::GONE,
+                    Json(ApiResponseBody::new_error(StatusCode::GONE, e)),
+                )
+                    .into_response()
+            }
+            PaymentRequired(e) => {
+                tracing::warn!("{}", e);
+                (
+                    StatusCode::PAYMENT_REQUIRED,
+                    Json(ApiResponseBody::new_error(StatusCode::PAYMENT_REQUIRED, e)),
+                )
+                    .into_response()
+            }
+            TooManyRequests(e) => {
+                tracing::warn!("{}", e);
+                (
+                    StatusCode::TOO_MANY_REQUESTS,
+                    Json(ApiResponseBody::new_error(StatusCode::TOO_MANY_REQUESTS, e)),
+                )
+                    .into_response()
+            }
         }
     }
 }
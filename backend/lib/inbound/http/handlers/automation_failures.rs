@@ -0,0 +1,156 @@
+use axum::{
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use http::{
+    HeaderValue, StatusCode,
+    header::{CONTENT_TYPE, X_CONTENT_TYPE_OPTIONS},
+};
+use serde::Serialize;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    domain::website::{
+        models::automation_failure::{
+            AutomationArtifact, AutomationFailure, GetAutomationArtifactError,
+            ListAutomationFailuresError,
+        },
+        ports::WebsiteService,
+    },
+    inbound::http::{AppState, extractors::AdminJwt},
+};
+
+use super::{ApiError, ApiSuccess};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+pub struct AutomationFailureResponseData {
+    #[ts(type = "number")]
+    id: i64,
+    #[ts(type = "number")]
+    website_id: i64,
+    run_id: Uuid,
+    error: String,
+    url: Option<String>,
+    /// Where to download the artifacts from, `None` if they could not be captured.
+    screenshot_link: Option<String>,
+    page_source_link: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+pub struct AutomationFailuresResponseData {
+    failures: Vec<AutomationFailureResponseData>,
+}
+
+impl From<&AutomationFailure> for AutomationFailureResponseData {
+    fn from(failure: &AutomationFailure) -> Self {
+        let link = |artifact: &str| format!("/api/admin/failures/{}/{artifact}", failure.id);
+        Self {
+            id: failure.id,
+            website_id: failure.website_id,
+            run_id: failure.run_id,
+            error: failure.error.clone(),
+            url: failure.url.clone(),
+            screenshot_link: failure.has_screenshot.then(|| link("screenshot")),
+            page_source_link: failure.has_page_source.then(|| link("page_source")),
+            created_at: failure.created_at,
+        }
+    }
+}
+
+impl From<&Vec<AutomationFailure>> for AutomationFailuresResponseData {
+    fn from(failures: &Vec<AutomationFailure>) -> Self {
+        Self {
+            failures: failures
+                .iter()
+                .map(AutomationFailureResponseData::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<ListAutomationFailuresError> for ApiError {
+    fn from(e: ListAutomationFailuresError) -> Self {
+        match e {
+            ListAutomationFailuresError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+impl From<GetAutomationArtifactError> for ApiError {
+    fn from(e: GetAutomationArtifactError) -> Self {
+        match e {
+            GetAutomationArtifactError::NotFound { .. } => Self::NotFound(e.to_string()),
+            GetAutomationArtifactError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// The failures captured from the site generator automation for a website, newest first.
+pub async fn list_automation_failures<WS: WebsiteService>(
+    AdminJwt { user_id, .. }: AdminJwt<WS>,
+    State(state): State<AppState<WS>>,
+    Path(website_id): Path<i64>,
+) -> Result<ApiSuccess<AutomationFailuresResponseData>, ApiError> {
+    tracing::debug!("Decoded admin {user_id}");
+    state
+        .website_service
+        .list_automation_failures(website_id)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref failures| ApiSuccess::new(StatusCode::OK, failures.into()))
+}
+
+async fn automation_artifact<WS: WebsiteService>(
+    state: AppState<WS>,
+    id: i64,
+    artifact: AutomationArtifact,
+) -> Result<Response, ApiError> {
+    let content = state
+        .website_service
+        .get_automation_artifact(id, artifact)
+        .await?;
+    let content_type = match artifact {
+        AutomationArtifact::Screenshot => "image/png",
+        // Served as text, the captured page must not run on our origin.
+        AutomationArtifact::PageSource => "text/plain; charset=utf-8",
+    };
+    Ok((
+        StatusCode::OK,
+        [
+            (CONTENT_TYPE, HeaderValue::from_static(content_type)),
+            (X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff")),
+        ],
+        content,
+    )
+        .into_response())
+}
+
+/// The screenshot of the browser when the automation failed.
+pub async fn get_automation_screenshot<WS: WebsiteService>(
+    AdminJwt { user_id, .. }: AdminJwt<WS>,
+    State(state): State<AppState<WS>>,
+    Path(id): Path<i64>,
+) -> Result<Response, ApiError> {
+    tracing::debug!("Decoded admin {user_id}");
+    automation_artifact(state, id, AutomationArtifact::Screenshot).await
+}
+
+/// The source of the page the browser was on when the automation failed.
+pub async fn get_automation_page_source<WS: WebsiteService>(
+    AdminJwt { user_id, .. }: AdminJwt<WS>,
+    State(state): State<AppState<WS>>,
+    Path(id): Path<i64>,
+) -> Result<Response, ApiError> {
+    tracing::debug!("Decoded admin {user_id}");
+    automation_artifact(state, id, AutomationArtifact::PageSource).await
+}
@@ -2,6 +2,7 @@ use axum::{Json, extract::State};
 use http::StatusCode;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use ts_rs::TS;
 use url::Url;
 
 use crate::{
@@ -14,7 +15,8 @@ use crate::{
 
 use super::{ApiError, ApiSuccess};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
 pub struct CreateWebsiteResponseData {
     id: String,
 }
@@ -38,10 +40,7 @@ impl From<CreateWebsiteError> for ApiError {
                 tracing::error!("{:?}", cause);
                 Self::InternalServerError("Internal server error".to_string())
             }
-            CreateWebsiteError::NotificationFailed => {
-                tracing::error!("Failed notification");
-                Self::InternalServerError("Internal server error".to_string())
-            }
+            CreateWebsiteError::QuotaExceeded(e) => e.into(),
         }
     }
 }
@@ -80,9 +79,12 @@ impl From<ParseCreateWebsiteHttpRequestError> for ApiError {
 impl CreateWebsiteHttpRequestBody {
     /// converts the HTTP request body into a domain request. We could use serde as well to avoid
     /// some boilerplate, in case this is infallible
-    fn try_into_domain(&self) -> Result<CreateWebsiteRequest, ParseCreateWebsiteHttpRequestError> {
+    fn try_into_domain(
+        &self,
+        owner_id: &str,
+    ) -> Result<CreateWebsiteRequest, ParseCreateWebsiteHttpRequestError> {
         let url = Url::parse(&self.source_address)?;
-        Ok(CreateWebsiteRequest::new(url))
+        Ok(CreateWebsiteRequest::new(url, owner_id))
     }
 }
 
@@ -92,7 +94,7 @@ pub async fn create_website<WS: WebsiteService>(
     Json(body): Json<CreateWebsiteHttpRequestBody>,
 ) -> Result<ApiSuccess<CreateWebsiteResponseData>, ApiError> {
     tracing::debug!("Decoded user {user_id}");
-    let create_website_request = body.try_into_domain()?;
+    let create_website_request = body.try_into_domain(&user_id)?;
     state
         .website_service
         .create_website(&create_website_request)
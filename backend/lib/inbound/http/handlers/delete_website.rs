@@ -0,0 +1,38 @@
+use axum::extract::{Path, State};
+use http::StatusCode;
+
+use crate::{
+    domain::website::{models::website::DeleteWebsiteError, ports::WebsiteService},
+    inbound::http::{AppState, extractors::Jwt},
+};
+
+use super::ApiError;
+
+impl From<DeleteWebsiteError> for ApiError {
+    fn from(e: DeleteWebsiteError) -> Self {
+        match e {
+            DeleteWebsiteError::NotFound { id } => {
+                tracing::warn!("Website {} not found", id);
+                Self::NotFound(format!("Website {id} not found"))
+            }
+            DeleteWebsiteError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// Deletes a website of the user, along with everything stored about it.
+pub async fn delete_website<WS: WebsiteService>(
+    Jwt { user_id, .. }: Jwt<WS>,
+    State(state): State<AppState<WS>>,
+    Path(website_id): Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    tracing::debug!("Decoded user {user_id}");
+    state
+        .website_service
+        .delete_website(&user_id, website_id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
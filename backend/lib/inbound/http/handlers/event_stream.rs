@@ -0,0 +1,124 @@
+use std::collections::VecDeque;
+
+use axum::{
+    extract::State,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures_util::{Stream, stream};
+use http::{HeaderMap, HeaderName};
+use tokio::sync::broadcast::{Receiver, error::RecvError};
+
+use crate::{
+    domain::website::{models::event::EventEnvelope, ports::WebsiteService},
+    inbound::http::{AppState, extractors::Jwt},
+};
+
+use super::{
+    ApiError,
+    websocket::{ControlMessage, missed_events},
+};
+
+/// Sent by `EventSource` when reconnecting, with the id of the last event it received.
+pub const LAST_EVENT_ID: HeaderName = HeaderName::from_static("last-event-id");
+
+/// Ephemeral events have no id, so `EventSource` resumes after the last logged event.
+fn sse_event(event: &EventEnvelope) -> Result<Event, axum::Error> {
+    if event.event.is_ephemeral() {
+        return Event::default().json_data(event);
+    }
+    Event::default()
+        .id(event.event_id.to_string())
+        .json_data(event)
+}
+
+/// Follows the website events for one client, replaying the missed ones first.
+struct Follower<WS: WebsiteService> {
+    state: AppState<WS>,
+    /// The user following, only the events of their websites are sent.
+    tenant: String,
+    rx: Receiver<EventEnvelope>,
+    last_sent: Option<i64>,
+    pending: VecDeque<Result<Event, axum::Error>>,
+}
+
+impl<WS: WebsiteService> Follower<WS> {
+    /// Queues the events logged after `last_sent`, or a [ControlMessage::Resync] when they cannot
+    /// be replayed.
+    async fn catch_up(&mut self) {
+        match missed_events(&self.state, &self.tenant, self.last_sent).await {
+            Some(missed) => {
+                for event in missed {
+                    self.pending.push_back(sse_event(&event));
+                    self.last_sent = Some(event.event_id);
+                }
+            }
+            None => self
+                .pending
+                .push_back(Event::default().json_data(ControlMessage::Resync {
+                    last_event_id: self.last_sent,
+                })),
+        }
+    }
+
+    async fn next(mut self) -> Option<(Result<Event, axum::Error>, Self)> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some((event, self));
+            }
+            match self.rx.recv().await {
+                Ok(event)
+                    if !event.event.is_ephemeral()
+                        && self.last_sent.is_some_and(|id| event.event_id <= id) => {}
+                Ok(event) => {
+                    if !event.event.is_ephemeral() {
+                        self.last_sent = Some(event.event_id);
+                    }
+                    if event.tenant.as_deref() == Some(self.tenant.as_str()) {
+                        return Some((sse_event(&event), self));
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("event stream lagged behind by {} events", skipped);
+                    self.catch_up().await;
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+/// Streams the website events as Server-Sent Events, resuming after the `Last-Event-ID` header
+/// when it is set.
+pub async fn event_stream<WS: WebsiteService>(
+    Jwt { user_id, .. }: Jwt<WS>,
+    State(state): State<AppState<WS>>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, ApiError> {
+    tracing::debug!("Decoded user {user_id}");
+    let last_event_id = headers
+        .get(LAST_EVENT_ID)
+        .map(|value| {
+            value
+                .to_str()
+                .ok()
+                .and_then(|value| value.trim().parse::<i64>().ok())
+                .ok_or_else(|| {
+                    ApiError::UnprocessableEntity("invalid Last-Event-ID header".to_string())
+                })
+        })
+        .transpose()?;
+
+    // Subscribe before replaying, so no event falls in between.
+    let mut follower = Follower {
+        rx: state.website_service.get_receiver(),
+        state,
+        tenant: user_id,
+        last_sent: last_event_id,
+        pending: VecDeque::new(),
+    };
+    if last_event_id.is_some() {
+        follower.catch_up().await;
+    }
+
+    Ok(Sse::new(stream::unfold(follower, Follower::next)).keep_alive(KeepAlive::default()))
+}
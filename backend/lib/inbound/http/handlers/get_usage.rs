@@ -0,0 +1,75 @@
+use axum::extract::{Query, State};
+use chrono::{Days, NaiveDate, Utc};
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::{
+    domain::website::{
+        models::usage::{GetUsageError, GetUsageRequest, UsageReport},
+        ports::WebsiteService,
+    },
+    inbound::http::{AppState, extractors::Jwt},
+};
+
+use super::{ApiError, ApiSuccess};
+
+/// Number of days covered when the request has no `from` date.
+const DEFAULT_RANGE_DAYS: u64 = 30;
+
+/// The date range of a usage request, both ends inclusive. Defaults to the last 30 days.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct GetUsageQuery {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+pub struct GetUsageResponseData {
+    from: NaiveDate,
+    to: NaiveDate,
+    #[serde(flatten)]
+    usage: UsageReport,
+}
+
+impl From<GetUsageError> for ApiError {
+    fn from(e: GetUsageError) -> Self {
+        match e {
+            GetUsageError::InvalidRange { .. } => {
+                Self::UnprocessableEntity("from must not be after to".to_string())
+            }
+            GetUsageError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+pub async fn get_usage<WS: WebsiteService>(
+    Jwt { user_id, .. }: Jwt<WS>,
+    State(state): State<AppState<WS>>,
+    Query(query): Query<GetUsageQuery>,
+) -> Result<ApiSuccess<GetUsageResponseData>, ApiError> {
+    tracing::debug!("Decoded user {user_id}");
+    let to = query.to.unwrap_or_else(|| Utc::now().date_naive());
+    let from = query
+        .from
+        .or_else(|| to.checked_sub_days(Days::new(DEFAULT_RANGE_DAYS - 1)))
+        .unwrap_or(to);
+    let end = to
+        .checked_add_days(Days::new(1))
+        .ok_or_else(|| ApiError::UnprocessableEntity(format!("invalid date {to}")))?;
+    let req = GetUsageRequest {
+        owner_id: user_id,
+        from: from.and_time(Default::default()).and_utc(),
+        to: end.and_time(Default::default()).and_utc(),
+    };
+    state
+        .website_service
+        .get_usage(&req)
+        .await
+        .map_err(ApiError::from)
+        .map(|usage| ApiSuccess::new(StatusCode::OK, GetUsageResponseData { from, to, usage }))
+}
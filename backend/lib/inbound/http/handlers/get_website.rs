@@ -0,0 +1,54 @@
+use axum::extract::{Path, State};
+use http::StatusCode;
+use serde::Serialize;
+use ts_rs::TS;
+
+use crate::{
+    domain::website::{
+        models::{
+            screenshot::Screenshot,
+            website::{CrawlSummary, WebsiteDetail, WebsiteStatus},
+        },
+        ports::WebsiteService,
+    },
+    inbound::http::{AppState, extractors::Jwt},
+};
+
+use super::{ApiError, ApiSuccess, get_websites::WebsiteResponseData};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+pub struct WebsiteDetailResponseData {
+    #[serde(flatten)]
+    website: WebsiteResponseData,
+    status: WebsiteStatus,
+    crawl: CrawlSummary,
+}
+
+impl WebsiteDetailResponseData {
+    fn new(detail: &WebsiteDetail, screenshots: &[Screenshot]) -> Self {
+        Self {
+            website: WebsiteResponseData::new(&detail.website, screenshots),
+            status: detail.status.clone(),
+            crawl: detail.crawl.clone(),
+        }
+    }
+}
+
+/// A website of the user, with the state of its pipeline and what its last crawl stored.
+pub async fn get_website<WS: WebsiteService>(
+    Jwt { user_id, .. }: Jwt<WS>,
+    State(state): State<AppState<WS>>,
+    Path(website_id): Path<i64>,
+) -> Result<ApiSuccess<WebsiteDetailResponseData>, ApiError> {
+    tracing::debug!("Decoded user {user_id}");
+    let detail = state
+        .website_service
+        .get_website_detail(&user_id, website_id)
+        .await?;
+    let screenshots = state.website_service.get_screenshots(&[website_id]).await?;
+    Ok(ApiSuccess::new(
+        StatusCode::OK,
+        WebsiteDetailResponseData::new(&detail, &screenshots),
+    ))
+}
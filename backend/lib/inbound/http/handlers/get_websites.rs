@@ -1,28 +1,49 @@
 use axum::extract::State;
 use http::StatusCode;
 use serde::Serialize;
+use ts_rs::TS;
 
 use crate::{
     domain::website::{
-        models::website::{GetWebsitesError, Website},
+        models::{
+            business_profile::BusinessProfile,
+            screenshot::{Screenshot, ScreenshotSubject, Viewport},
+            website::{ExtractionMethod, GetWebsitesError, RenderMode, Social, Website},
+        },
         ports::WebsiteService,
     },
     inbound::http::{AppState, extractors::Jwt},
 };
 
-use super::{ApiError, ApiSuccess};
+use super::{ApiError, ApiSuccess, screenshots::screenshot_link};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
 pub struct GetWebsiteResponseData {
     websites: Vec<WebsiteResponseData>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
 pub struct WebsiteResponseData {
+    #[ts(type = "number")]
     id: i64,
     source_address: String,
     contact_email: Option<String>,
     contact_name: Option<String>,
+    contact_phone: Option<String>,
+    contact_email_method: Option<ExtractionMethod>,
+    contact_name_method: Option<ExtractionMethod>,
+    contact_phone_method: Option<ExtractionMethod>,
+    contact_prompt_version: Option<i32>,
+    social_links: Option<Social>,
+    generated_website_link: Option<String>,
+    generated_website_name: Option<String>,
+    generated_website_prompt_version: Option<i32>,
+    render_mode: Option<RenderMode>,
+    business_profile: Option<BusinessProfile>,
+    /// Desktop screenshot thumbnails of the original and generated sites.
+    original_thumbnail_link: Option<String>,
+    generated_thumbnail_link: Option<String>,
 }
 
 impl From<GetWebsitesError> for ApiError {
@@ -36,31 +57,63 @@ impl From<GetWebsitesError> for ApiError {
     }
 }
 
-impl From<&Vec<Website>> for GetWebsiteResponseData {
-    fn from(websites: &Vec<Website>) -> Self {
+impl WebsiteResponseData {
+    /// Builds the response for `website`, linking the desktop thumbnails found in `screenshots`.
+    pub(super) fn new(website: &Website, screenshots: &[Screenshot]) -> Self {
+        let thumbnail_link = |subject: ScreenshotSubject| {
+            screenshots
+                .iter()
+                .find(|screenshot| {
+                    screenshot.website_id == website.id
+                        && screenshot.subject == subject
+                        && screenshot.viewport == Viewport::Desktop
+                })
+                .map(|screenshot| screenshot_link(screenshot, true))
+        };
+        Self {
+            id: website.id,
+            source_address: website.source_address.clone(),
+            contact_email: website.contact_email.clone(),
+            contact_name: website.contact_name.clone(),
+            contact_phone: website.contact_phone.clone(),
+            contact_email_method: website.contact_email_method,
+            contact_name_method: website.contact_name_method,
+            contact_phone_method: website.contact_phone_method,
+            contact_prompt_version: website.contact_prompt_version,
+            social_links: website.social_links.clone(),
+            generated_website_link: website.generated_website_link.clone(),
+            generated_website_name: website.generated_website_name.clone(),
+            generated_website_prompt_version: website.generated_website_prompt_version,
+            render_mode: website.render_mode,
+            business_profile: website.business_profile.clone(),
+            original_thumbnail_link: thumbnail_link(ScreenshotSubject::Original),
+            generated_thumbnail_link: thumbnail_link(ScreenshotSubject::Generated),
+        }
+    }
+}
+
+impl GetWebsiteResponseData {
+    fn new(websites: &[Website], screenshots: &[Screenshot]) -> Self {
         Self {
             websites: websites
                 .iter()
-                .map(|website| WebsiteResponseData {
-                    id: website.id,
-                    source_address: website.source_address.clone(),
-                    contact_email: website.contact_email.clone(),
-                    contact_name: website.contact_name.clone(),
-                })
+                .map(|website| WebsiteResponseData::new(website, screenshots))
                 .collect(),
         }
     }
 }
 
+/// The websites of the user, oldest first.
 pub async fn get_websites<WS: WebsiteService>(
     Jwt { user_id, .. }: Jwt<WS>,
     State(state): State<AppState<WS>>,
 ) -> Result<ApiSuccess<GetWebsiteResponseData>, ApiError> {
     tracing::debug!("Decoded user {user_id}");
-    state
-        .website_service
-        .get_websites()
-        .await
-        .map_err(ApiError::from)
-        .map(|ref websites| ApiSuccess::new(StatusCode::OK, websites.into()))
+    let websites = state.website_service.get_websites(&user_id).await?;
+    let website_ids: Vec<i64> = websites.iter().map(|website| website.id).collect();
+    let screenshots = state.website_service.get_screenshots(&website_ids).await?;
+    Ok(ApiSuccess::new(
+        StatusCode::OK,
+        GetWebsiteResponseData::new(&websites, &screenshots),
+    ))
 }
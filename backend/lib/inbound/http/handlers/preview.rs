@@ -0,0 +1,130 @@
+use std::path::{Component, Path as FsPath};
+
+use axum::{
+    extract::{Path, State},
+    response::{IntoResponse, Redirect, Response},
+};
+use http::{HeaderValue, StatusCode, header::CONTENT_TYPE};
+
+use crate::{
+    domain::website::{
+        models::preview::{GetPreviewError, Preview},
+        ports::WebsiteService,
+    },
+    inbound::http::AppState,
+};
+
+use super::ApiError;
+
+impl From<GetPreviewError> for ApiError {
+    fn from(e: GetPreviewError) -> Self {
+        match e {
+            GetPreviewError::NotFound { slug } => {
+                Self::NotFound(format!("Preview {slug} not found"))
+            }
+            GetPreviewError::Expired { slug, expires_at } => Self::Gone(format!(
+                "Preview {slug} expired on {}",
+                expires_at.date_naive()
+            )),
+            GetPreviewError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+fn content_type(path: &FsPath) -> &'static str {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("js") => "text/javascript; charset=utf-8",
+        Some("json") => "application/json",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("webp") => "image/webp",
+        Some("ico") => "image/x-icon",
+        Some("woff2") => "font/woff2",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Inserts the preview banner right after the opening `<body>` tag.
+fn with_banner(html: &str, banner_text: &str, preview: &Preview) -> String {
+    let expiry = preview
+        .expires_at
+        .map(|expires_at| format!(" · available until {}", expires_at.date_naive()))
+        .unwrap_or_default();
+    let banner = format!(
+        r#"<div id="mithrilforge-preview-banner" style="position:sticky;top:0;z-index:2147483647;padding:.5rem 1rem;background:#0f172a;color:#f8fafc;font:600 14px/1.4 system-ui,sans-serif;text-align:center">{banner_text}{expiry}</div>"#
+    );
+    let body_start = html
+        .find("<body")
+        .and_then(|start| html[start..].find('>').map(|end| start + end + 1));
+    match body_start {
+        Some(index) => format!("{}{banner}{}", &html[..index], &html[index..]),
+        None => format!("{banner}{html}"),
+    }
+}
+
+/// Redirects to the trailing slash, so the relative links of the site resolve under the slug.
+pub async fn preview_root(Path(slug): Path<String>) -> Redirect {
+    Redirect::permanent(&format!("/preview/{slug}/"))
+}
+
+pub async fn preview_index<WS: WebsiteService>(
+    State(state): State<AppState<WS>>,
+    Path(slug): Path<String>,
+) -> Result<Response, ApiError> {
+    serve_preview(state, slug, "index.html".to_string()).await
+}
+
+pub async fn preview_file<WS: WebsiteService>(
+    State(state): State<AppState<WS>>,
+    Path((slug, path)): Path<(String, String)>,
+) -> Result<Response, ApiError> {
+    serve_preview(state, slug, path).await
+}
+
+/// Serves `path` of the site hosted under `slug`, with the preview banner on HTML pages.
+async fn serve_preview<WS: WebsiteService>(
+    state: AppState<WS>,
+    slug: String,
+    path: String,
+) -> Result<Response, ApiError> {
+    let not_found = || ApiError::NotFound(format!("{path} not found in preview {slug}"));
+    let relative = FsPath::new(&path);
+    if !relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+    {
+        return Err(not_found());
+    }
+    let preview = state.website_service.get_preview(&slug).await?;
+    let file = state.preview_dir.join(&preview.slug).join(relative);
+    let file = if path.is_empty() || path.ends_with('/') {
+        file.join("index.html")
+    } else {
+        file
+    };
+    let body = tokio::fs::read(&file).await.map_err(|_| not_found())?;
+
+    let content_type = content_type(&file);
+    let body = if content_type.starts_with("text/html") {
+        with_banner(
+            &String::from_utf8_lossy(&body),
+            &state.preview_banner_text,
+            &preview,
+        )
+        .into_bytes()
+    } else {
+        body
+    };
+    Ok((
+        StatusCode::OK,
+        [(CONTENT_TYPE, HeaderValue::from_static(content_type))],
+        body,
+    )
+        .into_response())
+}
@@ -0,0 +1,156 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use chrono::{DateTime, Utc};
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::{
+    domain::website::{
+        models::prompt::{
+            CreatePromptVersionError, CreatePromptVersionRequest, GetPromptError, ListPromptsError,
+            PromptName, PromptTemplate,
+        },
+        ports::WebsiteService,
+    },
+    inbound::http::{AppState, extractors::AdminJwt},
+};
+
+use super::{ApiError, ApiSuccess};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+pub struct PromptResponseData {
+    name: PromptName,
+    version: i32,
+    template: String,
+    /// The variables the template may reference, as `{name}`.
+    variables: &'static [&'static str],
+    created_by: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+pub struct PromptsResponseData {
+    prompts: Vec<PromptResponseData>,
+}
+
+/// The body of a request storing a new version of a prompt.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CreatePromptVersionHttpRequestBody {
+    template: String,
+}
+
+impl From<&PromptTemplate> for PromptResponseData {
+    fn from(prompt: &PromptTemplate) -> Self {
+        Self {
+            name: prompt.name,
+            version: prompt.version,
+            template: prompt.template.clone(),
+            variables: prompt.name.variables(),
+            created_by: prompt.created_by.clone(),
+            created_at: prompt.created_at,
+        }
+    }
+}
+
+impl From<&Vec<PromptTemplate>> for PromptsResponseData {
+    fn from(prompts: &Vec<PromptTemplate>) -> Self {
+        Self {
+            prompts: prompts.iter().map(PromptResponseData::from).collect(),
+        }
+    }
+}
+
+impl From<ListPromptsError> for ApiError {
+    fn from(e: ListPromptsError) -> Self {
+        match e {
+            ListPromptsError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+impl From<GetPromptError> for ApiError {
+    fn from(e: GetPromptError) -> Self {
+        match e {
+            GetPromptError::NotFound { name } => Self::NotFound(format!("Prompt {name} not found")),
+            GetPromptError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+impl From<CreatePromptVersionError> for ApiError {
+    fn from(e: CreatePromptVersionError) -> Self {
+        match e {
+            CreatePromptVersionError::Empty | CreatePromptVersionError::UnknownVariable { .. } => {
+                Self::UnprocessableEntity(e.to_string())
+            }
+            CreatePromptVersionError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+fn parse_prompt_name(name: &str) -> Result<PromptName, ApiError> {
+    name.parse()
+        .map_err(|_| ApiError::NotFound(format!("Prompt {name} not found")))
+}
+
+/// The version in use of every prompt.
+pub async fn list_prompts<WS: WebsiteService>(
+    AdminJwt { user_id, .. }: AdminJwt<WS>,
+    State(state): State<AppState<WS>>,
+) -> Result<ApiSuccess<PromptsResponseData>, ApiError> {
+    tracing::debug!("Decoded admin {user_id}");
+    state
+        .website_service
+        .list_prompts()
+        .await
+        .map_err(ApiError::from)
+        .map(|ref prompts| ApiSuccess::new(StatusCode::OK, prompts.into()))
+}
+
+/// Every version of a prompt, newest first.
+pub async fn get_prompt_versions<WS: WebsiteService>(
+    AdminJwt { user_id, .. }: AdminJwt<WS>,
+    State(state): State<AppState<WS>>,
+    Path(name): Path<String>,
+) -> Result<ApiSuccess<PromptsResponseData>, ApiError> {
+    tracing::debug!("Decoded admin {user_id}");
+    let name = parse_prompt_name(&name)?;
+    state
+        .website_service
+        .get_prompt_versions(name)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref prompts| ApiSuccess::new(StatusCode::OK, prompts.into()))
+}
+
+/// Stores a new version of a prompt, used by every extraction and generation from then on.
+pub async fn create_prompt_version<WS: WebsiteService>(
+    AdminJwt { user_id, .. }: AdminJwt<WS>,
+    State(state): State<AppState<WS>>,
+    Path(name): Path<String>,
+    Json(body): Json<CreatePromptVersionHttpRequestBody>,
+) -> Result<ApiSuccess<PromptResponseData>, ApiError> {
+    tracing::debug!("Decoded admin {user_id}");
+    let name = parse_prompt_name(&name)?;
+    let req = CreatePromptVersionRequest::new(name, &body.template, &user_id)?;
+    state
+        .website_service
+        .create_prompt_version(&req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref prompt| ApiSuccess::new(StatusCode::CREATED, prompt.into()))
+}
@@ -0,0 +1,57 @@
+use axum::extract::{Path, State};
+use http::StatusCode;
+use serde::Serialize;
+use ts_rs::TS;
+
+use crate::{
+    domain::website::{
+        models::website::{GetWebsiteError, Website},
+        ports::WebsiteService,
+    },
+    inbound::http::{AppState, extractors::Jwt},
+};
+
+use super::{ApiError, ApiSuccess};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+pub struct RefreshWebsiteResponseData {
+    id: String,
+}
+
+impl From<GetWebsiteError> for ApiError {
+    fn from(e: GetWebsiteError) -> Self {
+        match e {
+            GetWebsiteError::NotFound { id } => {
+                tracing::warn!("Website {} not found", id);
+                Self::NotFound(format!("Website {id} not found"))
+            }
+            GetWebsiteError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+impl From<&Website> for RefreshWebsiteResponseData {
+    fn from(website: &Website) -> Self {
+        Self {
+            id: website.id.to_string(),
+        }
+    }
+}
+
+pub async fn refresh_website<WS: WebsiteService>(
+    Jwt { user_id, .. }: Jwt<WS>,
+    State(state): State<AppState<WS>>,
+    Path(website_id): Path<i64>,
+) -> Result<ApiSuccess<RefreshWebsiteResponseData>, ApiError> {
+    tracing::debug!("Decoded user {user_id}");
+    state
+        .website_service
+        .refresh_website(&user_id, website_id)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref website| ApiSuccess::new(StatusCode::ACCEPTED, website.into()))
+}
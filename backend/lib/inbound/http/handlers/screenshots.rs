@@ -0,0 +1,170 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use http::{HeaderValue, StatusCode, header::CONTENT_TYPE};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::{
+    domain::website::{
+        models::screenshot::{
+            GetComparisonError, GetScreenshotImageError, GetScreenshotsError, Screenshot,
+            ScreenshotSubject, Viewport,
+        },
+        ports::WebsiteService,
+    },
+    inbound::http::{
+        AppState,
+        extractors::{Jwt, QueryJwt},
+    },
+};
+
+use super::{ApiError, ApiSuccess};
+
+/// Where the image of a screenshot is served. The images are requested by `<img>` tags, so they
+/// authenticate with the `token` query parameter.
+pub fn screenshot_link(screenshot: &Screenshot, thumbnail: bool) -> String {
+    let link = format!(
+        "/api/website/{}/screenshots/{}/{}",
+        screenshot.website_id, screenshot.subject, screenshot.viewport
+    );
+    if thumbnail {
+        format!("{link}?thumbnail=true")
+    } else {
+        link
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+pub struct ScreenshotResponseData {
+    viewport: Viewport,
+    url: String,
+    image_link: String,
+    thumbnail_link: String,
+    captured_at: DateTime<Utc>,
+}
+
+/// The screenshots of the original site next to the ones of the generated site.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+pub struct ComparisonResponseData {
+    #[ts(type = "number")]
+    website_id: i64,
+    original: Vec<ScreenshotResponseData>,
+    generated: Vec<ScreenshotResponseData>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ScreenshotImageQuery {
+    #[serde(default)]
+    thumbnail: bool,
+}
+
+impl From<&Screenshot> for ScreenshotResponseData {
+    fn from(screenshot: &Screenshot) -> Self {
+        Self {
+            viewport: screenshot.viewport,
+            url: screenshot.url.clone(),
+            image_link: screenshot_link(screenshot, false),
+            thumbnail_link: screenshot_link(screenshot, true),
+            captured_at: screenshot.captured_at,
+        }
+    }
+}
+
+impl ComparisonResponseData {
+    fn new(website_id: i64, screenshots: &[Screenshot]) -> Self {
+        let of = |subject: ScreenshotSubject| {
+            screenshots
+                .iter()
+                .filter(|screenshot| screenshot.subject == subject)
+                .map(ScreenshotResponseData::from)
+                .collect()
+        };
+        Self {
+            website_id,
+            original: of(ScreenshotSubject::Original),
+            generated: of(ScreenshotSubject::Generated),
+        }
+    }
+}
+
+impl From<GetScreenshotsError> for ApiError {
+    fn from(e: GetScreenshotsError) -> Self {
+        match e {
+            GetScreenshotsError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+impl From<GetComparisonError> for ApiError {
+    fn from(e: GetComparisonError) -> Self {
+        match e {
+            GetComparisonError::NotFound { id } => {
+                tracing::warn!("Website {} not found", id);
+                Self::NotFound(format!("Website {id} not found"))
+            }
+            GetComparisonError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+impl From<GetScreenshotImageError> for ApiError {
+    fn from(e: GetScreenshotImageError) -> Self {
+        match e {
+            GetScreenshotImageError::NotFound { .. } => Self::NotFound(e.to_string()),
+            GetScreenshotImageError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// The before and after screenshots of a website, at every viewport.
+pub async fn get_comparison<WS: WebsiteService>(
+    Jwt { user_id, .. }: Jwt<WS>,
+    State(state): State<AppState<WS>>,
+    Path(website_id): Path<i64>,
+) -> Result<ApiSuccess<ComparisonResponseData>, ApiError> {
+    tracing::debug!("Decoded user {user_id}");
+    state
+        .website_service
+        .get_comparison(&user_id, website_id)
+        .await
+        .map_err(ApiError::from)
+        .map(|screenshots| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                ComparisonResponseData::new(website_id, &screenshots),
+            )
+        })
+}
+
+/// The PNG image of a screenshot, or its thumbnail with `?thumbnail=true`.
+pub async fn get_screenshot_image<WS: WebsiteService>(
+    QueryJwt { user_id, .. }: QueryJwt<WS>,
+    State(state): State<AppState<WS>>,
+    Path((website_id, subject, viewport)): Path<(i64, ScreenshotSubject, Viewport)>,
+    Query(query): Query<ScreenshotImageQuery>,
+) -> Result<Response, ApiError> {
+    tracing::debug!("Decoded user {user_id}");
+    let image = state
+        .website_service
+        .get_screenshot_image(&user_id, website_id, subject, viewport, query.thumbnail)
+        .await?;
+    Ok((
+        StatusCode::OK,
+        [(CONTENT_TYPE, HeaderValue::from_static("image/png"))],
+        image,
+    )
+        .into_response())
+}
@@ -0,0 +1,130 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use http::StatusCode;
+use serde::{Deserialize, Deserializer};
+use thiserror::Error;
+
+use crate::{
+    domain::website::{
+        models::website::{CorrectContactError, CorrectContactRequest},
+        ports::WebsiteService,
+    },
+    inbound::http::{AppState, extractors::Jwt},
+};
+
+use super::{ApiError, ApiSuccess, get_websites::WebsiteResponseData};
+
+impl From<CorrectContactError> for ApiError {
+    fn from(e: CorrectContactError) -> Self {
+        match e {
+            CorrectContactError::NotFound { id } => {
+                tracing::warn!("Website {} not found", id);
+                Self::NotFound(format!("Website {id} not found"))
+            }
+            CorrectContactError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// Tells a field set to `null` apart from a missing one, which is `None`.
+fn present<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Option<String>>, D::Error> {
+    Option::<String>::deserialize(deserializer).map(Some)
+}
+
+/// The body of a contact correction request. Fields left out are unchanged, `null` clears them.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct UpdateWebsiteHttpRequestBody {
+    #[serde(default, deserialize_with = "present")]
+    contact_email: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    contact_name: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    contact_phone: Option<Option<String>>,
+}
+
+#[derive(Debug, Clone, Error)]
+enum ParseUpdateWebsiteHttpRequestError {
+    #[error("at least one contact field is required")]
+    NoCorrections,
+    #[error("{field} must not be blank, use null to clear it")]
+    Blank { field: &'static str },
+    #[error("invalid email address {email}")]
+    InvalidEmail { email: String },
+}
+
+impl From<ParseUpdateWebsiteHttpRequestError> for ApiError {
+    fn from(e: ParseUpdateWebsiteHttpRequestError) -> Self {
+        Self::UnprocessableEntity(e.to_string())
+    }
+}
+
+/// Trims the value of a correction, rejecting blank ones.
+fn trimmed(
+    field: &'static str,
+    correction: Option<Option<String>>,
+) -> Result<Option<Option<String>>, ParseUpdateWebsiteHttpRequestError> {
+    match correction {
+        Some(Some(value)) if value.trim().is_empty() => {
+            Err(ParseUpdateWebsiteHttpRequestError::Blank { field })
+        }
+        Some(Some(value)) => Ok(Some(Some(value.trim().to_string()))),
+        correction => Ok(correction),
+    }
+}
+
+impl UpdateWebsiteHttpRequestBody {
+    fn try_into_domain(
+        self,
+        owner_id: &str,
+        website_id: i64,
+    ) -> Result<CorrectContactRequest, ParseUpdateWebsiteHttpRequestError> {
+        if self.contact_email.is_none()
+            && self.contact_name.is_none()
+            && self.contact_phone.is_none()
+        {
+            return Err(ParseUpdateWebsiteHttpRequestError::NoCorrections);
+        }
+        let contact_email = trimmed("contact_email", self.contact_email)?;
+        if let Some(Some(email)) = &contact_email {
+            let valid = email
+                .split_once('@')
+                .is_some_and(|(local, domain)| !local.is_empty() && domain.contains('.'))
+                && !email.contains(char::is_whitespace);
+            if !valid {
+                return Err(ParseUpdateWebsiteHttpRequestError::InvalidEmail {
+                    email: email.clone(),
+                });
+            }
+        }
+        Ok(CorrectContactRequest {
+            owner_id: owner_id.to_string(),
+            website_id,
+            contact_email,
+            contact_name: trimmed("contact_name", self.contact_name)?,
+            contact_phone: trimmed("contact_phone", self.contact_phone)?,
+        })
+    }
+}
+
+/// Corrects the contact of a website of the user. Corrected fields are kept when the website is
+/// refreshed.
+pub async fn update_website<WS: WebsiteService>(
+    Jwt { user_id, .. }: Jwt<WS>,
+    State(state): State<AppState<WS>>,
+    Path(website_id): Path<i64>,
+    Json(body): Json<UpdateWebsiteHttpRequestBody>,
+) -> Result<ApiSuccess<WebsiteResponseData>, ApiError> {
+    tracing::debug!("Decoded user {user_id}");
+    let req = body.try_into_domain(&user_id, website_id)?;
+    let website = state.website_service.correct_contact(&req).await?;
+    let screenshots = state.website_service.get_screenshots(&[website_id]).await?;
+    Ok(ApiSuccess::new(
+        StatusCode::OK,
+        WebsiteResponseData::new(&website, &screenshots),
+    ))
+}
@@ -0,0 +1,345 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use chrono::{DateTime, Utc};
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use ts_rs::TS;
+use url::{Host, Url};
+
+use crate::{
+    domain::website::{
+        models::{
+            webhook::{
+                CreateWebhookError, CreateWebhookRequest, DeliveryStatus, GetWebhookDeliveryError,
+                GetWebhookError, ListWebhooksError, Webhook, WebhookDelivery, is_public_address,
+            },
+            website::WebsiteEventType,
+        },
+        ports::WebsiteService,
+    },
+    inbound::http::{AppState, extractors::Jwt},
+};
+
+use super::{ApiError, ApiSuccess};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+pub struct WebhookResponseData {
+    #[ts(type = "number")]
+    id: i64,
+    url: String,
+    event_types: Vec<WebsiteEventType>,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+pub struct WebhooksResponseData {
+    webhooks: Vec<WebhookResponseData>,
+}
+
+/// The created webhook, along with its secret. The secret is not returned again.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+pub struct CreateWebhookResponseData {
+    #[serde(flatten)]
+    webhook: WebhookResponseData,
+    secret: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+pub struct WebhookDeliveryResponseData {
+    #[ts(type = "number")]
+    id: i64,
+    #[ts(type = "number")]
+    webhook_id: i64,
+    /// `null` for pings.
+    #[ts(type = "number | null")]
+    event_id: Option<i64>,
+    #[ts(type = "number | null")]
+    replay_of: Option<i64>,
+    payload: String,
+    status: DeliveryStatus,
+    attempts: i32,
+    next_attempt_at: Option<DateTime<Utc>>,
+    last_response_status: Option<i32>,
+    last_error: Option<String>,
+    created_at: DateTime<Utc>,
+    delivered_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+pub struct WebhookDeliveriesResponseData {
+    deliveries: Vec<WebhookDeliveryResponseData>,
+}
+
+impl From<&Webhook> for WebhookResponseData {
+    fn from(webhook: &Webhook) -> Self {
+        Self {
+            id: webhook.id,
+            url: webhook.url.to_string(),
+            event_types: webhook.event_types.clone(),
+            created_at: webhook.created_at,
+        }
+    }
+}
+
+impl From<&Vec<Webhook>> for WebhooksResponseData {
+    fn from(webhooks: &Vec<Webhook>) -> Self {
+        Self {
+            webhooks: webhooks.iter().map(WebhookResponseData::from).collect(),
+        }
+    }
+}
+
+impl From<&Webhook> for CreateWebhookResponseData {
+    fn from(webhook: &Webhook) -> Self {
+        Self {
+            webhook: webhook.into(),
+            secret: webhook.secret.clone(),
+        }
+    }
+}
+
+impl From<&WebhookDelivery> for WebhookDeliveryResponseData {
+    fn from(delivery: &WebhookDelivery) -> Self {
+        Self {
+            id: delivery.id,
+            webhook_id: delivery.webhook_id,
+            event_id: delivery.event_id,
+            replay_of: delivery.replay_of,
+            payload: delivery.payload.clone(),
+            status: delivery.status,
+            attempts: delivery.attempts,
+            next_attempt_at: delivery.next_attempt_at,
+            last_response_status: delivery.last_response_status,
+            last_error: delivery.last_error.clone(),
+            created_at: delivery.created_at,
+            delivered_at: delivery.delivered_at,
+        }
+    }
+}
+
+impl From<&Vec<WebhookDelivery>> for WebhookDeliveriesResponseData {
+    fn from(deliveries: &Vec<WebhookDelivery>) -> Self {
+        Self {
+            deliveries: deliveries
+                .iter()
+                .map(WebhookDeliveryResponseData::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<CreateWebhookError> for ApiError {
+    fn from(e: CreateWebhookError) -> Self {
+        match e {
+            CreateWebhookError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+impl From<ListWebhooksError> for ApiError {
+    fn from(e: ListWebhooksError) -> Self {
+        match e {
+            ListWebhooksError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+impl From<GetWebhookError> for ApiError {
+    fn from(e: GetWebhookError) -> Self {
+        match e {
+            GetWebhookError::NotFound { .. } => Self::NotFound(e.to_string()),
+            GetWebhookError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+impl From<GetWebhookDeliveryError> for ApiError {
+    fn from(e: GetWebhookDeliveryError) -> Self {
+        match e {
+            GetWebhookDeliveryError::NotFound { .. } => Self::NotFound(e.to_string()),
+            GetWebhookDeliveryError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// The body of a [Webhook] creation request. A secret is generated when none is given.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CreateWebhookHttpRequestBody {
+    url: String,
+    event_types: Vec<WebsiteEventType>,
+    secret: Option<String>,
+}
+
+#[derive(Debug, Clone, Error)]
+enum ParseCreateWebhookHttpRequestError {
+    #[error("cannot parse url: {0}")]
+    Url(#[from] url::ParseError),
+    #[error("webhook urls must be http or https, not {scheme}")]
+    UnsupportedScheme { scheme: String },
+    #[error("webhooks cannot be sent to {host}")]
+    ForbiddenHost { host: String },
+    #[error("at least one event type is required")]
+    NoEventTypes,
+    #[error("{event_type:?} events are only sent to connected clients, not to webhooks")]
+    EphemeralEventType { event_type: WebsiteEventType },
+    #[error("the secret must not be empty")]
+    EmptySecret,
+}
+
+impl From<ParseCreateWebhookHttpRequestError> for ApiError {
+    fn from(e: ParseCreateWebhookHttpRequestError) -> Self {
+        Self::UnprocessableEntity(e.to_string())
+    }
+}
+
+impl CreateWebhookHttpRequestBody {
+    fn try_into_domain(
+        self,
+        tenant: &str,
+    ) -> Result<CreateWebhookRequest, ParseCreateWebhookHttpRequestError> {
+        let url = Url::parse(&self.url)?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(ParseCreateWebhookHttpRequestError::UnsupportedScheme {
+                scheme: url.scheme().to_string(),
+            });
+        }
+        // Hosts resolving to a private address are refused when sending, these never work.
+        let forbidden = match url.host() {
+            Some(Host::Ipv4(address)) => !is_public_address(address.into()),
+            Some(Host::Ipv6(address)) => !is_public_address(address.into()),
+            Some(Host::Domain(domain)) => {
+                domain.eq_ignore_ascii_case("localhost")
+                    || domain.to_ascii_lowercase().ends_with(".localhost")
+            }
+            None => true,
+        };
+        if forbidden {
+            return Err(ParseCreateWebhookHttpRequestError::ForbiddenHost {
+                host: url.host_str().unwrap_or_default().to_string(),
+            });
+        }
+        if self.event_types.is_empty() {
+            return Err(ParseCreateWebhookHttpRequestError::NoEventTypes);
+        }
+        if self.event_types.contains(&WebsiteEventType::StageProgress) {
+            return Err(ParseCreateWebhookHttpRequestError::EphemeralEventType {
+                event_type: WebsiteEventType::StageProgress,
+            });
+        }
+        if self.secret.as_ref().is_some_and(String::is_empty) {
+            return Err(ParseCreateWebhookHttpRequestError::EmptySecret);
+        }
+        Ok(CreateWebhookRequest::new(
+            tenant,
+            url,
+            self.event_types,
+            self.secret,
+        ))
+    }
+}
+
+/// Subscribes a webhook to the events of the websites of the user.
+pub async fn create_webhook<WS: WebsiteService>(
+    Jwt { user_id, .. }: Jwt<WS>,
+    State(state): State<AppState<WS>>,
+    Json(body): Json<CreateWebhookHttpRequestBody>,
+) -> Result<ApiSuccess<CreateWebhookResponseData>, ApiError> {
+    tracing::debug!("Decoded user {user_id}");
+    let req = body.try_into_domain(&user_id)?;
+    state
+        .website_service
+        .create_webhook(&req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref webhook| ApiSuccess::new(StatusCode::CREATED, webhook.into()))
+}
+
+pub async fn list_webhooks<WS: WebsiteService>(
+    Jwt { user_id, .. }: Jwt<WS>,
+    State(state): State<AppState<WS>>,
+) -> Result<ApiSuccess<WebhooksResponseData>, ApiError> {
+    tracing::debug!("Decoded user {user_id}");
+    state
+        .website_service
+        .list_webhooks(&user_id)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref webhooks| ApiSuccess::new(StatusCode::OK, webhooks.into()))
+}
+
+pub async fn delete_webhook<WS: WebsiteService>(
+    Jwt { user_id, .. }: Jwt<WS>,
+    State(state): State<AppState<WS>>,
+    Path(webhook_id): Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    tracing::debug!("Decoded user {user_id}");
+    state
+        .website_service
+        .delete_webhook(&user_id, webhook_id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// The latest deliveries of a webhook, newest first.
+pub async fn list_webhook_deliveries<WS: WebsiteService>(
+    Jwt { user_id, .. }: Jwt<WS>,
+    State(state): State<AppState<WS>>,
+    Path(webhook_id): Path<i64>,
+) -> Result<ApiSuccess<WebhookDeliveriesResponseData>, ApiError> {
+    tracing::debug!("Decoded user {user_id}");
+    state
+        .website_service
+        .list_webhook_deliveries(&user_id, webhook_id)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref deliveries| ApiSuccess::new(StatusCode::OK, deliveries.into()))
+}
+
+/// Sends a ping to a webhook, responding with the outcome of the delivery.
+pub async fn test_webhook<WS: WebsiteService>(
+    Jwt { user_id, .. }: Jwt<WS>,
+    State(state): State<AppState<WS>>,
+    Path(webhook_id): Path<i64>,
+) -> Result<ApiSuccess<WebhookDeliveryResponseData>, ApiError> {
+    tracing::debug!("Decoded user {user_id}");
+    state
+        .website_service
+        .test_webhook(&user_id, webhook_id)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref delivery| ApiSuccess::new(StatusCode::CREATED, delivery.into()))
+}
+
+/// Sends a delivery again, responding with the outcome of the new delivery.
+pub async fn replay_webhook_delivery<WS: WebsiteService>(
+    Jwt { user_id, .. }: Jwt<WS>,
+    State(state): State<AppState<WS>>,
+    Path((webhook_id, delivery_id)): Path<(i64, i64)>,
+) -> Result<ApiSuccess<WebhookDeliveryResponseData>, ApiError> {
+    tracing::debug!("Decoded user {user_id}");
+    state
+        .website_service
+        .replay_webhook_delivery(&user_id, webhook_id, delivery_id)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref delivery| ApiSuccess::new(StatusCode::CREATED, delivery.into()))
+}
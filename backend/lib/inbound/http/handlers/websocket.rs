@@ -1,17 +1,32 @@
 use axum::{
     extract::{
-        State, WebSocketUpgrade,
+        Query, State, WebSocketUpgrade,
         ws::{Message, WebSocket},
     },
     response::Response,
 };
-use serde::Serialize;
+use std::{collections::BTreeSet, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use tokio::{
+    sync::broadcast::error::RecvError,
+    time::{Instant, MissedTickBehavior, interval},
+};
+use ts_rs::TS;
 
 use crate::{
-    domain::website::ports::WebsiteService,
+    domain::website::{
+        models::{
+            event::EventEnvelope,
+            website::{GetWebsiteError, WebsiteEventType},
+        },
+        ports::WebsiteService,
+    },
     inbound::http::{AppState, extractors::QueryJwt},
 };
 
+use super::get_websites;
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct GetWebsiteResponseData {
     websites: Vec<WebsiteResponseData>,
@@ -22,22 +37,341 @@ pub struct WebsiteResponseData {
     id: i64,
 }
 
+/// Most events replayed at once. Clients missing more are told to resync instead.
+pub(super) const REPLAY_LIMIT: i64 = 500;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct EventsQuery {
+    /// The id of the last event the client received, to replay the ones emitted after it.
+    last_event_id: Option<i64>,
+}
+
+/// How often the server pings the client.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+/// Connections the client sent nothing on, not even a pong, for this long are closed.
+const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(75);
+
+/// Messages about the stream itself, sent next to the events.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[serde(tag = "type")]
+#[ts(export)]
+pub(super) enum ControlMessage {
+    /// Some events could not be delivered, the client should reload the websites.
+    Resync {
+        #[ts(type = "number | null")]
+        last_event_id: Option<i64>,
+    },
+    /// Answers a [ClientCommand::Ping].
+    Pong { nonce: Option<String> },
+    /// Answers a [ClientCommand::Snapshot] with the current state of the website.
+    Snapshot {
+        website: Box<get_websites::WebsiteResponseData>,
+    },
+    /// A command could not be handled.
+    Error { message: String },
+}
+
+/// Messages clients send over the websocket. Until the first [ClientCommand::Subscribe], every
+/// event is sent.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, TS)]
+#[serde(tag = "type")]
+#[ts(export)]
+pub(super) enum ClientCommand {
+    /// Receive the events of these websites, or of these types. Subscribing to websites or types
+    /// stops the events of the other ones.
+    Subscribe {
+        #[serde(default)]
+        #[ts(type = "Array<number>")]
+        website_ids: Vec<i64>,
+        #[serde(default)]
+        event_types: Vec<WebsiteEventType>,
+    },
+    /// Stop receiving the events of these websites, or of these types.
+    Unsubscribe {
+        #[serde(default)]
+        #[ts(type = "Array<number>")]
+        website_ids: Vec<i64>,
+        #[serde(default)]
+        event_types: Vec<WebsiteEventType>,
+    },
+    Ping {
+        #[serde(default)]
+        nonce: Option<String>,
+    },
+    /// Get the current state of a website, e.g. before following its events.
+    Snapshot {
+        #[ts(type = "number")]
+        website_id: i64,
+    },
+}
+
+/// A set of values that starts out matching everything.
+#[derive(Debug, Clone)]
+struct Filter<T> {
+    /// `None` until values are included explicitly.
+    included: Option<BTreeSet<T>>,
+    excluded: BTreeSet<T>,
+}
+
+impl<T: Ord + Copy> Filter<T> {
+    fn matches(&self, value: &T) -> bool {
+        self.included
+            .as_ref()
+            .is_none_or(|included| included.contains(value))
+            && !self.excluded.contains(value)
+    }
+
+    fn include(&mut self, values: &[T]) {
+        if values.is_empty() {
+            return;
+        }
+        for value in values {
+            self.excluded.remove(value);
+        }
+        self.included
+            .get_or_insert_default()
+            .extend(values.iter().copied());
+    }
+
+    fn exclude(&mut self, values: &[T]) {
+        match &mut self.included {
+            Some(included) => values.iter().for_each(|value| {
+                included.remove(value);
+            }),
+            None => self.excluded.extend(values.iter().copied()),
+        }
+    }
+}
+
+impl<T> Default for Filter<T> {
+    fn default() -> Self {
+        Self {
+            included: None,
+            excluded: BTreeSet::new(),
+        }
+    }
+}
+
+/// The events a client asked for.
+#[derive(Debug, Clone, Default)]
+struct Subscription {
+    website_ids: Filter<i64>,
+    event_types: Filter<WebsiteEventType>,
+}
+
+impl Subscription {
+    fn matches(&self, event: &EventEnvelope) -> bool {
+        self.website_ids.matches(&event.website_id)
+            && self.event_types.matches(&event.event.event_type())
+    }
+}
+
 pub async fn websocket<WS: WebsiteService>(
     ws: WebSocketUpgrade,
     // and still 15 years later header authentication is not a thing in websockets.
     // Jwt { user_id, .. }: Jwt<WS>,
     QueryJwt { user_id, .. }: QueryJwt<WS>,
     State(state): State<AppState<WS>>,
+    Query(query): Query<EventsQuery>,
 ) -> Response {
     tracing::debug!("Decoded user {user_id}");
-    ws.on_upgrade(|socket| handle_socket(socket, state))
+    ws.on_upgrade(move |socket| handle_socket(socket, state, user_id, query.last_event_id))
+}
+
+async fn send(socket: &mut WebSocket, message: &impl Serialize) -> Result<(), axum::Error> {
+    let data = serde_json::to_string(message).unwrap_or("".to_string());
+    socket.send(Message::Text(data.into())).await
+}
+
+/// Returns the events about the websites of `tenant` logged after `last_event_id`, or `None` when
+/// they cannot be replayed and the client has to resync.
+pub(super) async fn missed_events<WS: WebsiteService>(
+    state: &AppState<WS>,
+    tenant: &str,
+    last_event_id: Option<i64>,
+) -> Option<Vec<EventEnvelope>> {
+    state
+        .website_service
+        .get_events_after(tenant, last_event_id?, REPLAY_LIMIT + 1)
+        .await
+        .inspect_err(|e| tracing::error!("failed to replay events: {:?}", e))
+        .ok()
+        .filter(|events| events.len() as i64 <= REPLAY_LIMIT)
+}
+
+/// One client connected to the websocket.
+struct Connection<WS: WebsiteService> {
+    socket: WebSocket,
+    state: AppState<WS>,
+    /// The user connected, only the events of their websites are sent.
+    tenant: String,
+    subscription: Subscription,
+    last_sent: Option<i64>,
+}
+
+impl<WS: WebsiteService> Connection<WS> {
+    async fn send_event(&mut self, event: &EventEnvelope) -> Result<(), axum::Error> {
+        // Ephemeral events carry the id of the last logged event, they are never received twice.
+        if !event.event.is_ephemeral() {
+            if self.last_sent.is_some_and(|id| event.event_id <= id) {
+                return Ok(());
+            }
+            self.last_sent = Some(event.event_id);
+        }
+        if event.tenant.as_deref() != Some(self.tenant.as_str())
+            || !self.subscription.matches(event)
+        {
+            return Ok(());
+        }
+        send(&mut self.socket, event).await
+    }
+
+    /// Sends the events logged after `last_sent`, or a [ControlMessage::Resync] when they cannot
+    /// be replayed.
+    async fn catch_up(&mut self) -> Result<(), axum::Error> {
+        let Some(missed) = missed_events(&self.state, &self.tenant, self.last_sent).await else {
+            return send(
+                &mut self.socket,
+                &ControlMessage::Resync {
+                    last_event_id: self.last_sent,
+                },
+            )
+            .await;
+        };
+        for event in missed {
+            self.send_event(&event).await?;
+        }
+        Ok(())
+    }
+
+    async fn snapshot(state: &AppState<WS>, tenant: &str, website_id: i64) -> ControlMessage {
+        let snapshot = async {
+            let detail = state
+                .website_service
+                .get_website_detail(tenant, website_id)
+                .await?;
+            let screenshots = state
+                .website_service
+                .get_screenshots(&[website_id])
+                .await
+                .map_err(|e| GetWebsiteError::Unknown(e.into()))?;
+            Ok(get_websites::WebsiteResponseData::new(
+                &detail.website,
+                &screenshots,
+            ))
+        };
+        match snapshot.await {
+            Ok(website) => ControlMessage::Snapshot {
+                website: Box::new(website),
+            },
+            Err(GetWebsiteError::NotFound { id }) => ControlMessage::Error {
+                message: format!("website {id} not found"),
+            },
+            Err(GetWebsiteError::Unknown(e)) => {
+                tracing::error!("failed to get the snapshot of website {website_id}: {e:?}");
+                ControlMessage::Error {
+                    message: format!("failed to get the snapshot of website {website_id}"),
+                }
+            }
+        }
+    }
+
+    async fn handle_command(&mut self, command: ClientCommand) -> Result<(), axum::Error> {
+        match command {
+            ClientCommand::Subscribe {
+                website_ids,
+                event_types,
+            } => {
+                self.subscription.website_ids.include(&website_ids);
+                self.subscription.event_types.include(&event_types);
+                Ok(())
+            }
+            ClientCommand::Unsubscribe {
+                website_ids,
+                event_types,
+            } => {
+                self.subscription.website_ids.exclude(&website_ids);
+                self.subscription.event_types.exclude(&event_types);
+                Ok(())
+            }
+            ClientCommand::Ping { nonce } => {
+                send(&mut self.socket, &ControlMessage::Pong { nonce }).await
+            }
+            ClientCommand::Snapshot { website_id } => {
+                let snapshot = Self::snapshot(&self.state, &self.tenant, website_id).await;
+                send(&mut self.socket, &snapshot).await
+            }
+        }
+    }
+
+    async fn handle_message(&mut self, message: Message) -> Result<(), axum::Error> {
+        let Message::Text(text) = message else {
+            return Ok(());
+        };
+        match serde_json::from_str::<ClientCommand>(&text) {
+            Ok(command) => self.handle_command(command).await,
+            Err(e) => {
+                send(
+                    &mut self.socket,
+                    &ControlMessage::Error {
+                        message: format!("invalid command: {e}"),
+                    },
+                )
+                .await
+            }
+        }
+    }
 }
-async fn handle_socket<WS: WebsiteService>(mut socket: WebSocket, state: AppState<WS>) {
+
+async fn handle_socket<WS: WebsiteService>(
+    socket: WebSocket,
+    state: AppState<WS>,
+    tenant: String,
+    last_event_id: Option<i64>,
+) {
+    // Subscribe before replaying, so no event falls in between. Events received both ways are
+    // only sent once.
     let mut rx = state.website_service.get_receiver();
+    let mut connection = Connection {
+        socket,
+        state,
+        tenant,
+        subscription: Subscription::default(),
+        last_sent: last_event_id,
+    };
+    if last_event_id.is_some() && connection.catch_up().await.is_err() {
+        return;
+    }
 
-    while let Ok(message) = rx.recv().await {
-        let event_data = serde_json::to_string(&message).unwrap_or("".to_string());
-        if socket.send(Message::Text(event_data.into())).await.is_err() {
+    let mut heartbeat = interval(HEARTBEAT_INTERVAL);
+    heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut last_heard = Instant::now();
+    loop {
+        let sent = tokio::select! {
+            event = rx.recv() => match event {
+                Ok(event) => connection.send_event(&event).await,
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("websocket lagged behind by {} events", skipped);
+                    connection.catch_up().await
+                }
+                Err(RecvError::Closed) => return,
+            },
+            message = connection.socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(message)) => {
+                    last_heard = Instant::now();
+                    connection.handle_message(message).await
+                }
+            },
+            _ = heartbeat.tick() => {
+                if last_heard.elapsed() > HEARTBEAT_TIMEOUT {
+                    tracing::debug!("closing websocket silent for {:?}", last_heard.elapsed());
+                    return;
+                }
+                connection.socket.send(Message::Ping(Default::default())).await
+            }
+        };
+        if sent.is_err() {
             return;
         }
     }
@@ -1,3 +1,15 @@
 pub mod ai;
+pub mod builder_script;
+pub mod crawler;
 pub mod event_publisher;
+pub mod llm;
+pub mod local_storage;
+pub mod lovable;
+pub mod notifier;
+pub mod pg_notifier;
 pub mod postgres;
+pub mod rule_based;
+pub mod site_generator;
+pub mod static_site;
+pub mod webdriver_pool;
+pub mod webhook_sender;
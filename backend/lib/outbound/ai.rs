@@ -1,333 +1,624 @@
-use std::{collections::HashMap, env, time::Duration};
-
-use fantoccini::{Client, ClientBuilder, Locator, key::Key};
-use openai_api_rs::v1::{
-    api::OpenAIClient,
-    chat_completion::{self, ChatCompletionRequest},
-    common::O3,
-    types,
-};
-use scraper::{Html, Selector};
-use tokio::time::{Instant, sleep};
-use url::Url;
+use std::{collections::BTreeMap, time::Instant};
+
+use serde_json::{Value, json};
 
 use crate::domain::website::{
-    models::website::{Contact, GeneratedWebsite, WebsiteAiError},
-    ports::WebsiteAi,
+    models::{
+        business_profile::{BusinessProfile, ExtractBusinessProfileError},
+        landing_page::{LandingPageCopy, WriteCopyError},
+        page::CrawledWebsite,
+        prompt::{PromptName, PromptTemplate, landing_page_variables},
+        usage::{LlmCallOutcome, LlmUsage},
+        website::{Contact, ExtractContactError, ExtractionMethod, Social, Website},
+    },
+    ports::{
+        BusinessProfileExtractor, ContactExtractor, CopyWriter, PromptRepository, UsageRepository,
+    },
 };
 
+use super::llm::{Llm, LlmError, TokenUsage, ToolDefinition, chunk, estimate_tokens};
+
+/// Extracts contact details from crawled websites with the configured [Llm], using the prompts
+/// of the [PromptRepository] and recording every call in the [UsageRepository].
 #[derive(Clone)]
-pub struct Ai {
-    webdriver_address: String,
-    lovable_user: String,
-    lovable_password: String,
+pub struct Ai<R> {
+    llm: Llm,
+    repository: R,
 }
 
-impl Ai {
-    pub fn new(webdriver_addres: &str, lovable_user: &str, lovable_password: &str) -> Self {
-        Self {
-            webdriver_address: webdriver_addres.to_string(),
-            lovable_user: lovable_user.to_string(),
-            lovable_password: lovable_password.to_string(),
+impl<R: UsageRepository + PromptRepository> Ai<R> {
+    pub fn new(llm: Llm, repository: R) -> Self {
+        Self { llm, repository }
+    }
+
+    /// Calls `tool` and records the tokens, latency, cost and outcome of the call against
+    /// `website`. Failing to record the usage does not fail the call.
+    async fn call_tool(
+        &self,
+        website: &Website,
+        prompt: &str,
+        tool: &ToolDefinition,
+    ) -> Result<Value, LlmError> {
+        let started_at = Instant::now();
+        let result = self.llm.call_tool(prompt, tool).await;
+        let latency = started_at.elapsed();
+
+        // Providers only report usage on success, otherwise the prompt is estimated.
+        let estimated = || TokenUsage {
+            prompt_tokens: u32::try_from(estimate_tokens(prompt)).unwrap_or(u32::MAX),
+            completion_tokens: 0,
+        };
+        let (usage, outcome) = match &result {
+            Ok(call) => (
+                call.usage.unwrap_or_else(|| TokenUsage {
+                    completion_tokens: u32::try_from(estimate_tokens(&call.arguments.to_string()))
+                        .unwrap_or(u32::MAX),
+                    ..estimated()
+                }),
+                LlmCallOutcome::Success,
+            ),
+            Err(LlmError::NoToolCall { .. }) => (estimated(), LlmCallOutcome::NoToolCall),
+            Err(_) => (estimated(), LlmCallOutcome::Failed),
+        };
+        let record = LlmUsage {
+            website_id: website.id,
+            owner_id: website.owner_id.clone(),
+            operation: tool.name.clone(),
+            provider: self.llm.provider().as_str().to_string(),
+            model: self.llm.model().to_string(),
+            prompt_tokens: usage.prompt_tokens.into(),
+            completion_tokens: usage.completion_tokens.into(),
+            latency_ms: i64::try_from(latency.as_millis()).unwrap_or(i64::MAX),
+            estimated_cost_usd: self.llm.estimate_cost(&usage),
+            outcome,
+        };
+        if let Err(e) = self.repository.record_llm_usage(&record).await {
+            tracing::error!("failed to record llm usage for website {}: {e}", website.id);
         }
+        result.map(|call| call.arguments)
+    }
+}
+
+fn save_site_contacts_tool() -> ToolDefinition {
+    ToolDefinition {
+        name: String::from("save_site_contacts"),
+        description: String::from("Stores contact information found on a website"),
+        parameters: json!({
+            "type": "object",
+            "properties": {
+                "contact_name": {
+                    "type": "string",
+                    "description": "First name of the website owner, if absent, your best guess on who the company owner might be, if abset the company name ",
+                },
+                "contact_email": {
+                    "type": "string",
+                    "description": "Email address of the owner or main contact",
+                },
+                "contact_phone": {
+                    "type": "string",
+                    "description": "Phone number of the owner or main contact",
+                },
+                "social_links": {
+                    "type": "object",
+                    "description": "Social and review links on the site, only if present in the sent html",
+                    "properties": {
+                        "instagram": { "type": "string" },
+                        "facebook": { "type": "string" },
+                        "google_review": { "type": "string" },
+                        "google_maps": { "type": "string" },
+                    },
+                },
+            },
+        }),
     }
+}
 
-    pub async fn wait_until_lovable_preview_disappears(
+fn save_business_profile_tool() -> ToolDefinition {
+    ToolDefinition {
+        name: String::from("save_business_profile"),
+        description: String::from("Stores the profile of the business behind a website"),
+        parameters: json!({
+            "type": "object",
+            "properties": {
+                "address": {
+                    "type": "object",
+                    "description": "Postal address of the business",
+                    "properties": {
+                        "street_address": { "type": "string" },
+                        "postal_code": { "type": "string" },
+                        "locality": { "type": "string", "description": "City or town" },
+                        "region": { "type": "string" },
+                        "country": {
+                            "type": "string",
+                            "description": "ISO 3166-1 alpha-2 country code",
+                        },
+                    },
+                },
+                "phone_numbers": {
+                    "type": "array",
+                    "description": "Phone numbers of the business, in international format if possible",
+                    "items": { "type": "string" },
+                },
+                "opening_hours": {
+                    "type": "array",
+                    "description": "One entry per day the business is open",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "day_of_week": {
+                                "type": "string",
+                                "enum": ["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"],
+                            },
+                            "opens": { "type": "string", "description": "HH:MM" },
+                            "closes": { "type": "string", "description": "HH:MM" },
+                        },
+                        "required": ["day_of_week", "opens", "closes"],
+                    },
+                },
+                "category": {
+                    "type": "string",
+                    "description": "Industry category of the business in English, e.g. Bakery, Dentist, Law firm",
+                },
+                "services": {
+                    "type": "array",
+                    "description": "Products or services offered, as short names",
+                    "items": { "type": "string" },
+                },
+            },
+        }),
+    }
+}
+
+fn save_landing_page_copy_tool() -> ToolDefinition {
+    ToolDefinition {
+        name: String::from("save_landing_page_copy"),
+        description: String::from("Stores the copy of a landing page"),
+        parameters: json!({
+            "type": "object",
+            "properties": {
+                "headline": { "type": "string" },
+                "subheadline": { "type": "string" },
+                "about": { "type": "string", "description": "Two or three sentences about the business" },
+                "call_to_action": { "type": "string", "description": "Label of the main button" },
+                "services": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string" },
+                            "description": { "type": "string" },
+                        },
+                        "required": ["name", "description"],
+                    },
+                },
+            },
+            "required": ["headline", "subheadline", "about", "call_to_action"],
+        }),
+    }
+}
+
+/// Picks the value found in the most chunks, ties go to the value found first. Values for which
+/// `preferred` holds win over the others regardless of how often they were found.
+fn most_common<T: PartialEq + Clone>(
+    values: impl IntoIterator<Item = T>,
+    preferred: impl Fn(&T) -> bool,
+) -> Option<T> {
+    let mut counts: Vec<(T, usize)> = Vec::new();
+    for value in values {
+        match counts.iter_mut().find(|(candidate, _)| *candidate == value) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((value, 1)),
+        }
+    }
+    // `max_by_key` returns the last maximum, iterate in reverse so the first value found wins.
+    counts
+        .into_iter()
+        .rev()
+        .max_by_key(|(value, count)| (preferred(value), *count))
+        .map(|(value, _)| value)
+}
+
+/// Merges the values of the same list field found in every chunk, keeping the first occurrence.
+fn union<T: PartialEq>(values: impl IntoIterator<Item = Vec<T>>) -> Vec<T> {
+    let mut merged = Vec::new();
+    for value in values.into_iter().flatten() {
+        if !merged.contains(&value) {
+            merged.push(value);
+        }
+    }
+    merged
+}
+
+fn merge_contacts(contacts: Vec<Contact>, domain: Option<&str>) -> Contact {
+    let domain = domain.map(|domain| domain.trim_start_matches("www."));
+    let on_domain = |email: &String| {
+        domain.is_some_and(|domain| {
+            email
+                .rsplit_once('@')
+                .is_some_and(|(_, host)| host == domain || domain.ends_with(&format!(".{host}")))
+        })
+    };
+    let contact_email = most_common(
+        contacts
+            .iter()
+            .filter_map(|contact| contact.contact_email.as_ref())
+            .map(|email| email.trim().to_lowercase()),
+        on_domain,
+    );
+    let contact_name = most_common(
+        contacts
+            .iter()
+            .filter_map(|contact| contact.contact_name.as_ref())
+            .map(|name| name.trim().to_string()),
+        |_| false,
+    );
+    let contact_phone = most_common(
+        contacts
+            .iter()
+            .filter_map(|contact| contact.contact_phone.as_ref())
+            .map(|phone| phone.trim().to_string()),
+        |_| false,
+    );
+    let social_link = |link: fn(&Social) -> &Option<String>| {
+        most_common(
+            contacts
+                .iter()
+                .filter_map(|contact| contact.social_links.as_ref())
+                .filter_map(|social_links| link(social_links).clone()),
+            |_| false,
+        )
+    };
+    let social_links = Social {
+        instagram: social_link(|social_links| &social_links.instagram),
+        facebook: social_link(|social_links| &social_links.facebook),
+        google_maps: social_link(|social_links| &social_links.google_maps),
+        google_reviews: social_link(|social_links| &social_links.google_reviews),
+    };
+    let method = |field: &Option<String>| field.as_ref().map(|_| ExtractionMethod::Llm);
+    Contact {
+        contact_email_method: method(&contact_email),
+        contact_name_method: method(&contact_name),
+        contact_phone_method: method(&contact_phone),
+        contact_email,
+        contact_name,
+        contact_phone,
+        prompt_version: None,
+        social_links: (social_links != Social::default()).then_some(social_links),
+    }
+}
+
+fn merge_business_profiles(profiles: Vec<BusinessProfile>) -> BusinessProfile {
+    BusinessProfile {
+        address: most_common(
+            profiles
+                .iter()
+                .filter_map(|profile| profile.address.clone()),
+            |_| false,
+        ),
+        phone_numbers: union(profiles.iter().map(|profile| profile.phone_numbers.clone())),
+        opening_hours: most_common(
+            profiles
+                .iter()
+                .map(|profile| profile.opening_hours.clone())
+                .filter(|opening_hours| !opening_hours.is_empty()),
+            |_| false,
+        )
+        .unwrap_or_default(),
+        category: most_common(
+            profiles
+                .iter()
+                .filter_map(|profile| profile.category.clone()),
+            |_| false,
+        ),
+        services: union(profiles.into_iter().map(|profile| profile.services)),
+    }
+}
+
+impl<R: UsageRepository + PromptRepository> Ai<R> {
+    /// Runs `tool` on every chunk of `website` that fits the token budget, rendering `prompt`
+    /// with the chunk as `{website_content}`, and returns the arguments of the calls that
+    /// succeeded. Fails only if no chunk could be extracted.
+    async fn call_tool_per_chunk(
         &self,
-        webdriver: &Client,
-        timeout: Duration,
-    ) -> Result<(), fantoccini::error::CmdError> {
-        let xpath = "//span[normalize-space(.)='Spinning up preview...']";
-        let start = Instant::now();
-
-        while start.elapsed() < timeout {
-            match webdriver.find(Locator::XPath(xpath)).await {
-                Ok(_) => {
-                    sleep(Duration::from_millis(250)).await;
-                }
-                Err(fantoccini::error::CmdError::WaitTimeout) => {
-                    return Ok(());
+        website: &Website,
+        crawled: &CrawledWebsite,
+        prompt: &PromptTemplate,
+        tool: &ToolDefinition,
+    ) -> Result<Vec<Value>, LlmError> {
+        let chunks = chunk(&crawled.sections(), self.llm.token_budget());
+        tracing::debug!(
+            "running {} on {} chunks, about {} tokens",
+            tool.name,
+            chunks.len(),
+            chunks.iter().map(|c| estimate_tokens(c)).sum::<usize>()
+        );
+        let mut results = Vec::with_capacity(chunks.len());
+        let mut last_error = None;
+        for (index, content) in chunks.iter().enumerate() {
+            let variables = BTreeMap::from([
+                ("website_source_address", website.source_address.clone()),
+                ("website_content", content.clone()),
+            ]);
+            let prompt = prompt.render(&variables);
+            match self.call_tool(website, &prompt, tool).await {
+                Ok(arguments) => results.push(arguments),
+                Err(e) => {
+                    tracing::warn!("{} failed on chunk {index}: {e}", tool.name);
+                    last_error = Some(e);
                 }
-                Err(e) => return Err(e),
             }
         }
+        match last_error {
+            Some(e) if results.is_empty() => Err(e),
+            _ => Ok(results),
+        }
+    }
+}
 
-        Err(fantoccini::error::CmdError::WaitTimeout)
+impl<R: UsageRepository + PromptRepository> ContactExtractor for Ai<R> {
+    async fn get_contact(
+        &self,
+        website: &Website,
+        crawled: &CrawledWebsite,
+    ) -> Result<Contact, ExtractContactError> {
+        let prompt = self
+            .repository
+            .get_prompt(PromptName::ContactExtraction)
+            .await
+            .map_err(|e| ExtractContactError::Unknown(e.into()))?;
+
+        let results = self
+            .call_tool_per_chunk(website, crawled, &prompt, &save_site_contacts_tool())
+            .await
+            .map_err(|e| match e {
+                LlmError::NoToolCall { .. } => ExtractContactError::FailedToFetchContact,
+                e => ExtractContactError::Unknown(e.into()),
+            })?;
+        tracing::debug!("contacts extracted: {:?}", results);
+        let contacts = results
+            .into_iter()
+            .map(serde_json::from_value)
+            .collect::<Result<Vec<Contact>, _>>()
+            .map_err(|e| ExtractContactError::Unknown(e.into()))?;
+        let domain = crawled.pages.first().and_then(|page| page.url.host_str());
+        Ok(Contact {
+            prompt_version: Some(prompt.version),
+            ..merge_contacts(contacts, domain)
+        })
     }
 }
 
-impl From<fantoccini::error::CmdError> for WebsiteAiError {
-    fn from(value: fantoccini::error::CmdError) -> Self {
-        WebsiteAiError::WebdriverError(value)
+impl<R: UsageRepository + PromptRepository> BusinessProfileExtractor for Ai<R> {
+    async fn get_business_profile(
+        &self,
+        website: &Website,
+        crawled: &CrawledWebsite,
+    ) -> Result<BusinessProfile, ExtractBusinessProfileError> {
+        let prompt = self
+            .repository
+            .get_prompt(PromptName::BusinessProfileExtraction)
+            .await
+            .map_err(|e| ExtractBusinessProfileError::Unknown(e.into()))?;
+
+        let results = self
+            .call_tool_per_chunk(website, crawled, &prompt, &save_business_profile_tool())
+            .await
+            .map_err(|e| match e {
+                LlmError::NoToolCall { .. } => ExtractBusinessProfileError::FailedToFetchProfile,
+                e => ExtractBusinessProfileError::Unknown(e.into()),
+            })?;
+        tracing::debug!("business profiles extracted: {:?}", results);
+        let profiles = results
+            .into_iter()
+            .map(serde_json::from_value)
+            .collect::<Result<Vec<BusinessProfile>, _>>()
+            .map_err(|e| ExtractBusinessProfileError::Unknown(e.into()))?;
+        Ok(merge_business_profiles(profiles))
     }
 }
 
-impl WebsiteAi for Ai {
-    async fn get_full_website(
+impl<R: UsageRepository + PromptRepository> CopyWriter for Ai<R> {
+    async fn write_landing_page_copy(
         &self,
-        website_source_address: &str,
-    ) -> Result<String, WebsiteAiError> {
-        tracing::debug!("getting full website for {}", website_source_address);
-        let mut visited: HashMap<Url, String> = HashMap::new();
-        let url =
-            Url::parse(website_source_address).map_err(|_| WebsiteAiError::FailedToFetchContent)?;
-        let website_source_code = reqwest::get(url.to_owned())
+        website: &Website,
+    ) -> Result<LandingPageCopy, WriteCopyError> {
+        let prompt = self
+            .repository
+            .get_prompt(PromptName::LandingPageCopy)
             .await
-            .map_err(|_| WebsiteAiError::FailedToFetchContent)?
-            .text()
+            .map_err(|e| WriteCopyError::Unknown(e.into()))?;
+        let variables = landing_page_variables(website);
+
+        let arguments = self
+            .call_tool(
+                website,
+                &prompt.render(&variables),
+                &save_landing_page_copy_tool(),
+            )
             .await
-            .map_err(|_| WebsiteAiError::FailedToFetchContent)?;
-        visited.insert(url.clone(), website_source_code.clone());
-
-        let (to_visit, external_links) = {
-            let selector = Selector::parse("a[href]").unwrap();
-            let fragment = Html::parse_fragment(&website_source_code);
-
-            let mut internals = Vec::new();
-            let mut externals = Vec::new();
-
-            for el in fragment.select(&selector) {
-                if let Some(href) = el.value().attr("href") {
-                    if href.starts_with("http") {
-                        if let Ok(u) = Url::parse(href) {
-                            externals.push(u);
-                        }
-                        continue;
-                    }
-
-                    if href.starts_with('/') && href != "/" {
-                        if let Ok(u) = Url::parse(&format!("{website_source_address}{href}")) {
-                            internals.push(u);
-                        }
-                    }
-                }
-            }
+            .map_err(|e| match e {
+                LlmError::NoToolCall { .. } => WriteCopyError::FailedToWriteCopy,
+                e => WriteCopyError::Unknown(e.into()),
+            })?;
+        tracing::debug!("landing page copy written: {}", arguments);
+        let copy: LandingPageCopy =
+            serde_json::from_value(arguments).map_err(|e| WriteCopyError::Unknown(e.into()))?;
+        Ok(LandingPageCopy {
+            prompt_version: Some(prompt.version),
+            ..copy
+        })
+    }
+}
 
-            (internals, externals)
-        };
+#[cfg(test)]
+mod tests {
+    use crate::domain::website::models::{
+        business_profile::{BusinessProfile, DayOfWeek, OpeningHours, PostalAddress},
+        website::{Contact, ExtractionMethod, Social},
+    };
 
-        for url in to_visit.iter() {
-            let website_source_code = reqwest::get(url.to_owned())
-                .await
-                .map_err(|_| WebsiteAiError::FailedToFetchContent)?
-                .text()
-                .await
-                .map_err(|_| WebsiteAiError::FailedToFetchContent)?;
-            visited.insert(url.clone(), website_source_code.clone());
-        }
+    use super::{merge_business_profiles, merge_contacts, most_common, union};
 
-        let mut full_content = String::new();
-        for page in visited.iter() {
-            full_content.push_str("==== ");
-            full_content.push_str(page.0.as_ref());
-            full_content.push_str(" ==== \n");
-            full_content.push_str(page.1);
-            full_content.push_str("\n\n");
+    fn contact(email: Option<&str>, name: Option<&str>, instagram: Option<&str>) -> Contact {
+        Contact {
+            contact_email: email.map(str::to_string),
+            contact_name: name.map(str::to_string),
+            contact_phone: None,
+            contact_email_method: None,
+            contact_name_method: None,
+            contact_phone_method: None,
+            prompt_version: None,
+            social_links: instagram.map(|instagram| Social {
+                instagram: Some(instagram.to_string()),
+                ..Social::default()
+            }),
         }
-        full_content.push_str("\n\n==== Related links ==== \n");
-        for external_link in external_links.iter() {
-            full_content.push_str(external_link.as_ref());
-            full_content.push('\n');
+    }
+
+    fn opening_hours(day_of_week: DayOfWeek) -> OpeningHours {
+        OpeningHours {
+            day_of_week,
+            opens: "08:00".to_string(),
+            closes: "18:00".to_string(),
         }
-        Ok(full_content)
     }
-    async fn get_contact(&self, full_website: &str) -> Result<Contact, WebsiteAiError> {
-        tracing::debug!("getting contact for {}", full_website);
-        let api_key = env::var("OPENAI_API_KEY")
-            .expect("OpenAI key not found. Should be saved in an env var called OPENAI_API_KEY");
-        let mut client = OpenAIClient::builder()
-            .with_api_key(api_key)
-            .build()
-            .map_err(|_| WebsiteAiError::FailedToInitOpeanAi)?;
-
-        let mut properties = HashMap::new();
-        properties.insert(
-            "contact_name".to_owned(),
-            Box::new(types::JSONSchemaDefine {
-                schema_type: Some(types::JSONSchemaType::String),
-                description: Some(
-                    "First name of the website owner, if absent, your best guess on who the company owner might be, if abset the company name ".to_string(),
-                ),
-                ..Default::default()
-            }),
+
+    #[test]
+    fn most_common_picks_the_most_frequent_value() {
+        assert_eq!(most_common(["a", "b", "b"], |_| false), Some("b"));
+    }
+
+    #[test]
+    fn most_common_breaks_ties_with_the_first_value() {
+        assert_eq!(most_common(["a", "b", "b", "a"], |_| false), Some("a"));
+    }
+
+    #[test]
+    fn most_common_prefers_preferred_values() {
+        assert_eq!(
+            most_common(["a", "a", "b"], |value| *value == "b"),
+            Some("b")
         );
-        properties.insert(
-            "contact_email".to_owned(),
-            Box::new(types::JSONSchemaDefine {
-                schema_type: Some(types::JSONSchemaType::String),
-                description: Some("Email address of the owner or main contact".to_string()),
-                ..Default::default()
-            }),
+    }
+
+    #[test]
+    fn most_common_of_nothing_is_none() {
+        assert_eq!(most_common(Vec::<&str>::new(), |_| false), None);
+    }
+
+    #[test]
+    fn union_keeps_the_first_occurrence_in_order() {
+        let merged = union([vec![1, 2], vec![3, 1], vec![], vec![2, 4]]);
+        assert_eq!(merged, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn merge_contacts_normalizes_and_votes() {
+        let merged = merge_contacts(
+            vec![
+                contact(Some(" Info@Example.com "), Some("Anna Muster"), None),
+                contact(Some("info@example.com"), Some(" Anna Muster "), None),
+                contact(Some("jobs@example.com"), None, None),
+            ],
+            None,
         );
-        properties.insert(
-            "social_links".to_owned(),
-            Box::new(types::JSONSchemaDefine {
-                schema_type: Some(types::JSONSchemaType::Object),
-                description: Some(
-                    "Social and review links on the site, only if present in the sent html"
-                        .to_string(),
-                ),
-                properties: Some(HashMap::from([
-                    (
-                        "instagram".to_owned(),
-                        Box::new(types::JSONSchemaDefine {
-                            schema_type: Some(types::JSONSchemaType::String),
-                            ..Default::default()
-                        }),
-                    ),
-                    (
-                        "facebook".to_owned(),
-                        Box::new(types::JSONSchemaDefine {
-                            schema_type: Some(types::JSONSchemaType::String),
-                            ..Default::default()
-                        }),
-                    ),
-                    (
-                        "google_review".to_owned(),
-                        Box::new(types::JSONSchemaDefine {
-                            schema_type: Some(types::JSONSchemaType::String),
-                            ..Default::default()
-                        }),
-                    ),
-                    (
-                        "google_maps".to_owned(),
-                        Box::new(types::JSONSchemaDefine {
-                            schema_type: Some(types::JSONSchemaType::String),
-                            ..Default::default()
-                        }),
-                    ),
-                ])),
-                ..Default::default()
-            }),
+        assert_eq!(merged.contact_email.as_deref(), Some("info@example.com"));
+        assert_eq!(merged.contact_email_method, Some(ExtractionMethod::Llm));
+        assert_eq!(merged.contact_name.as_deref(), Some("Anna Muster"));
+        assert_eq!(merged.contact_name_method, Some(ExtractionMethod::Llm));
+        assert_eq!(merged.contact_phone, None);
+        assert_eq!(merged.contact_phone_method, None);
+    }
+
+    #[test]
+    fn merge_contacts_prefers_emails_on_the_domain_of_the_site() {
+        let merged = merge_contacts(
+            vec![
+                contact(Some("agency@webdesign.com"), None, None),
+                contact(Some("agency@webdesign.com"), None, None),
+                contact(Some("info@example.com"), None, None),
+            ],
+            Some("www.example.com"),
         );
+        assert_eq!(merged.contact_email.as_deref(), Some("info@example.com"));
+    }
 
-        let req = ChatCompletionRequest::new(
-            O3.to_string(),
-            vec![chat_completion::ChatCompletionMessage {
-                role: chat_completion::MessageRole::user,
-                content: chat_completion::Content::Text(format!(
-                    "Extract the owner’s contact details from the following HTML. \
-         If you find a personal name use it, otherwise use the company \
-         name. Return the data **only** via the function.\n\n{full_website}\n"
-                )),
-                name: None,
-                tool_calls: None,
-                tool_call_id: None,
-            }],
-        )
-        .tools(vec![chat_completion::Tool {
-            r#type: chat_completion::ToolType::Function,
-            function: types::Function {
-                name: String::from("save_site_contacts"),
-                description: Some(String::from(
-                    "Stores contact information found on a website",
-                )),
-                parameters: types::FunctionParameters {
-                    schema_type: types::JSONSchemaType::Object,
-                    properties: Some(properties),
-                    required: Some(vec![String::from("website_source_code")]),
-                },
-            },
-        }])
-        .tool_choice(chat_completion::ToolChoiceType::Auto);
+    #[test]
+    fn merge_contacts_does_not_take_a_shorter_domain_for_the_domain_of_the_site() {
+        let merged = merge_contacts(
+            vec![
+                contact(Some("agency@webdesign.com"), None, None),
+                contact(Some("agency@webdesign.com"), None, None),
+                contact(Some("x@ample.com"), None, None),
+                contact(Some("x@e.com"), None, None),
+                contact(Some("x@m"), None, None),
+            ],
+            Some("www.example.com"),
+        );
+        assert_eq!(
+            merged.contact_email.as_deref(),
+            Some("agency@webdesign.com")
+        );
+    }
 
-        let result = client
-            .chat_completion(req)
-            .await
-            .map_err(|e| WebsiteAiError::Unknown(e.into()))?;
-        match result.choices[0].finish_reason {
-            Some(chat_completion::FinishReason::tool_calls) => {
-                if let Some(tool_call) = result.choices[0].message.tool_calls.iter().next() {
-                    let arguments = tool_call[0].function.arguments.clone().unwrap();
-                    dbg!(&arguments);
-                    let contact: Contact = serde_json::from_str(&arguments)
-                        .map_err(|e| WebsiteAiError::Unknown(e.into()))?;
-                    dbg!(&contact);
-                    return Ok(contact);
-                }
-            }
-            _ => {
-                tracing::debug!("error 1");
-                return Err(WebsiteAiError::FailedToFetchContact);
-            }
-        }
-        tracing::debug!("not matching any");
-        Err(WebsiteAiError::FailedToFetchContact)
+    #[test]
+    fn merge_contacts_merges_social_links() {
+        let merged = merge_contacts(
+            vec![
+                contact(None, None, Some("https://instagram.com/muster")),
+                contact(None, None, None),
+            ],
+            None,
+        );
+        assert_eq!(
+            merged.social_links,
+            Some(Social {
+                instagram: Some("https://instagram.com/muster".to_string()),
+                ..Social::default()
+            })
+        );
+        let merged = merge_contacts(vec![contact(None, None, None)], None);
+        assert_eq!(merged.social_links, None);
     }
 
-    async fn generate_new_single_page(
-        &self,
-        website_source_address: &str,
-    ) -> Result<GeneratedWebsite, WebsiteAiError> {
-        tracing::debug!("generating new single page");
-        let webdriver = ClientBuilder::native()
-            .connect(&self.webdriver_address)
-            .await
-            .expect("failed to connect to WebDriver");
-
-        webdriver.goto("https://lovable.dev/login").await?;
-
-        webdriver
-            .find(Locator::Id("email"))
-            .await?
-            .send_keys(&self.lovable_user)
-            .await?;
-
-        webdriver
-            .find(Locator::Id("password"))
-            .await?
-            .send_keys(&self.lovable_password)
-            .await?;
-        webdriver
-            .find(Locator::XPath("//button[normalize-space()='Log in']"))
-            .await?
-            .click()
-            .await?;
-
-        webdriver
-            .wait()
-            .at_most(Duration::from_secs(30))
-            .for_element(Locator::Id("chatinput"))
-            .await?;
-
-        let prompt = format!(
-            r#"
-You are a senior conversion‑focused web designer + copywriter. Starting from the website {website_source_address}, produce one modern, responsive, accessible landing page.
-Research: audience, core offer, pains, differentiators, social proof—invent plausible placeholders if missing.
-Brand: derive clean style; fix weak colors for accessible palette; modern typography, white space, subtle animation.
-Structure (omit if irrelevant): Hero (benefit headline + primary CTA) → Trust logos → Problem → Solution/Benefits (bullets) → Social Proof → Pricing/Offer → FAQ (4–6) → Secondary CTA + contact form → Footer.
-Copy: concise, persuasive, second‑person, outcome‑headed; ≥3 CTA placements.
-CTAs: high‑contrast (≥7:1) solid primary + outlined secondary; clear hover.
-Tech: mobile‑first; optimized images/placeholders; meta title/description; form (name/email/message) with validation. 
-"#
-        )
-        .replace("\n", " ");
-
-        let chat_form = webdriver
-            .wait()
-            .at_most(Duration::from_secs(30))
-            .for_element(Locator::XPath("(//textarea)[1]"))
-            .await?;
-        for ch in prompt.chars() {
-            chat_form.send_keys(&ch.to_string()).await?;
-            sleep(Duration::from_millis(1)).await;
-        }
-        chat_form.send_keys(&prompt).await?;
-        chat_form.send_keys(&format!("{}", Key::Enter)).await?;
-
-        sleep(Duration::from_secs(10)).await;
-
-        let timeout = Duration::from_secs(600);
-        self.wait_until_lovable_preview_disappears(&webdriver, timeout)
-            .await?;
-        let name = webdriver
-            .find(fantoccini::Locator::XPath("//*[@id='main-menu']//p[1]"))
-            .await?
-            .text()
-            .await?;
-        Ok(GeneratedWebsite {
-            name,
-            url: webdriver.current_url().await?,
-        })
+    #[test]
+    fn merge_business_profiles_votes_on_values_and_unions_lists() {
+        let address = PostalAddress {
+            locality: Some("Berlin".to_string()),
+            ..PostalAddress::default()
+        };
+        let merged = merge_business_profiles(vec![
+            BusinessProfile {
+                address: Some(address.clone()),
+                phone_numbers: vec!["+493012345678".to_string()],
+                opening_hours: vec![opening_hours(DayOfWeek::Monday)],
+                category: Some("Bakery".to_string()),
+                services: vec!["Bread".to_string()],
+            },
+            BusinessProfile {
+                address: Some(address.clone()),
+                phone_numbers: vec!["+493087654321".to_string(), "+493012345678".to_string()],
+                opening_hours: vec![],
+                category: Some("Cafe".to_string()),
+                services: vec!["Cake".to_string(), "Bread".to_string()],
+            },
+            BusinessProfile::default(),
+        ]);
+        assert_eq!(merged.address, Some(address));
+        assert_eq!(
+            merged.phone_numbers,
+            vec!["+493012345678".to_string(), "+493087654321".to_string()]
+        );
+        assert_eq!(merged.opening_hours, vec![opening_hours(DayOfWeek::Monday)]);
+        assert_eq!(merged.category.as_deref(), Some("Bakery"));
+        assert_eq!(
+            merged.services,
+            vec!["Bread".to_string(), "Cake".to_string()]
+        );
+    }
+
+    #[test]
+    fn merge_business_profiles_of_nothing_is_empty() {
+        assert_eq!(merge_business_profiles(vec![]), BusinessProfile::default());
     }
 }
@@ -0,0 +1,226 @@
+/*!
+   Module `builder_script` runs declarative browser automation scripts, so the flows of site
+   builders can be fixed or added from the configuration, without recompiling.
+*/
+
+use std::{collections::BTreeMap, sync::LazyLock, time::Duration};
+
+use fantoccini::{Client, Locator, error::CmdError, key::Key};
+use regex::Regex;
+use serde::Deserialize;
+use thiserror::Error;
+use tokio::time::{Instant, sleep};
+
+/// The script driving Lovable, used unless another one is configured.
+pub const LOVABLE_SCRIPT: &str =
+    include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/scripts/lovable.json"));
+
+/// The output of the `generate` steps holding the name of the generated site.
+pub const NAME_OUTPUT: &str = "name";
+/// The output of the `generate` steps holding the url of the generated site. The url the browser
+/// ends on is used when it is missing.
+pub const URL_OUTPUT: &str = "url";
+
+static VARIABLE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{([a-z_]+)\}").unwrap());
+
+/// How to drive a site builder. `{user}` and `{password}` can be used in the `log_in` steps and
+/// `{prompt}` in the `generate` steps.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct BuilderScript {
+    /// A page of the builder, opened to restore the cookies of an earlier login.
+    pub base_url: String,
+    /// Fails unless the session is logged in.
+    pub check_logged_in: Vec<Step>,
+    pub log_in: Vec<Step>,
+    /// Generates a site, reading the [NAME_OUTPUT] and optionally the [URL_OUTPUT].
+    pub generate: Vec<Step>,
+}
+
+impl BuilderScript {
+    /// Checks that the `generate` steps read the name of the generated site.
+    pub fn validate(&self) -> Result<(), String> {
+        let reads_name = self.generate.iter().any(|step| match step {
+            Step::ReadText { output, .. } | Step::ReadUrl { output } => output == NAME_OUTPUT,
+            _ => false,
+        });
+        if reads_name {
+            Ok(())
+        } else {
+            Err(format!(
+                "the generate steps must read the {NAME_OUTPUT} output"
+            ))
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Selector {
+    Css(String),
+    Xpath(String),
+    Id(String),
+}
+
+impl Selector {
+    fn locator(&self) -> Locator<'_> {
+        match self {
+            Selector::Css(css) => Locator::Css(css),
+            Selector::Xpath(xpath) => Locator::XPath(xpath),
+            Selector::Id(id) => Locator::Id(id),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Step {
+    Goto {
+        url: String,
+    },
+    /// Types `text` into the element, one key every `key_delay_ms` if set, then presses enter
+    /// if `submit` is set.
+    Type {
+        selector: Selector,
+        text: String,
+        #[serde(default)]
+        key_delay_ms: Option<u64>,
+        #[serde(default)]
+        submit: bool,
+    },
+    Click {
+        selector: Selector,
+    },
+    WaitFor {
+        selector: Selector,
+        timeout_secs: u64,
+    },
+    WaitUntilGone {
+        selector: Selector,
+        timeout_secs: u64,
+    },
+    /// Stores the text of the element as `output`.
+    ReadText {
+        selector: Selector,
+        output: String,
+    },
+    /// Stores the current url as `output`.
+    ReadUrl {
+        output: String,
+    },
+    Sleep {
+        secs: u64,
+    },
+}
+
+#[derive(Debug, Error)]
+#[error("step {index} of {steps} failed: {step:?}")]
+pub struct StepError {
+    /// Which steps of the [BuilderScript] were running, e.g. `log_in`.
+    pub steps: &'static str,
+    pub index: usize,
+    pub step: Step,
+    #[source]
+    pub source: CmdError,
+}
+
+/// Replaces the `{name}` placeholders found in `variables`, leaving the others untouched.
+fn substitute(text: &str, variables: &BTreeMap<&str, &str>) -> String {
+    VARIABLE
+        .replace_all(text, |captures: &regex::Captures| {
+            variables
+                .get(&captures[1])
+                .map(|value| value.to_string())
+                .unwrap_or_else(|| captures[0].to_string())
+        })
+        .into_owned()
+}
+
+async fn run_step(
+    webdriver: &Client,
+    step: &Step,
+    variables: &BTreeMap<&str, &str>,
+    outputs: &mut BTreeMap<String, String>,
+) -> Result<(), CmdError> {
+    match step {
+        Step::Goto { url } => webdriver.goto(&substitute(url, variables)).await?,
+        Step::Type {
+            selector,
+            text,
+            key_delay_ms,
+            submit,
+        } => {
+            let element = webdriver.find(selector.locator()).await?;
+            let text = substitute(text, variables);
+            match key_delay_ms {
+                Some(delay) => {
+                    for ch in text.chars() {
+                        element.send_keys(&ch.to_string()).await?;
+                        sleep(Duration::from_millis(*delay)).await;
+                    }
+                }
+                None => element.send_keys(&text).await?,
+            }
+            if *submit {
+                element.send_keys(&Key::Enter.to_string()).await?;
+            }
+        }
+        Step::Click { selector } => webdriver.find(selector.locator()).await?.click().await?,
+        Step::WaitFor {
+            selector,
+            timeout_secs,
+        } => {
+            webdriver
+                .wait()
+                .at_most(Duration::from_secs(*timeout_secs))
+                .for_element(selector.locator())
+                .await?;
+        }
+        Step::WaitUntilGone {
+            selector,
+            timeout_secs,
+        } => {
+            let timeout = Duration::from_secs(*timeout_secs);
+            let start = Instant::now();
+            loop {
+                match webdriver.find(selector.locator()).await {
+                    Ok(_) if start.elapsed() < timeout => sleep(Duration::from_millis(250)).await,
+                    Ok(_) => return Err(CmdError::WaitTimeout),
+                    Err(e) if e.is_no_such_element() => break,
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+        Step::ReadText { selector, output } => {
+            let text = webdriver.find(selector.locator()).await?.text().await?;
+            outputs.insert(output.clone(), text);
+        }
+        Step::ReadUrl { output } => {
+            let url = webdriver.current_url().await?;
+            outputs.insert(output.clone(), url.to_string());
+        }
+        Step::Sleep { secs } => sleep(Duration::from_secs(*secs)).await,
+    }
+    Ok(())
+}
+
+/// Runs `steps` in order, stopping at the first failing one. Returns what the steps read.
+pub async fn run(
+    webdriver: &Client,
+    name: &'static str,
+    steps: &[Step],
+    variables: &BTreeMap<&str, &str>,
+) -> Result<BTreeMap<String, String>, StepError> {
+    let mut outputs = BTreeMap::new();
+    for (index, step) in steps.iter().enumerate() {
+        tracing::debug!("running step {} of {}: {:?}", index, name, step);
+        run_step(webdriver, step, variables, &mut outputs)
+            .await
+            .map_err(|source| StepError {
+                steps: name,
+                index,
+                step: step.clone(),
+                source,
+            })?;
+    }
+    Ok(outputs)
+}
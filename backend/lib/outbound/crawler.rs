@@ -0,0 +1,291 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::Cursor,
+    time::Duration,
+};
+
+use chrono::Utc;
+use fantoccini::{Client, ClientBuilder};
+use image::ImageFormat;
+use reqwest::{
+    StatusCode,
+    header::{IF_MODIFIED_SINCE, IF_NONE_MATCH},
+};
+use scraper::{Html, Selector};
+use tokio::{sync::watch, time::sleep};
+use url::Url;
+
+use crate::domain::website::{
+    models::{
+        page::{CrawlWebsiteError, CrawledWebsite, PageSnapshot},
+        screenshot::{CaptureScreenshotError, CapturedScreenshot, Viewport},
+        website::RenderMode,
+    },
+    ports::WebsiteCrawler,
+};
+
+/// Landing pages with less visible text than this are assumed to be built client side.
+const MIN_VISIBLE_TEXT_LEN: usize = 200;
+/// Time given to client side scripts to build the page once it has loaded.
+const RENDER_SETTLE_TIME: Duration = Duration::from_secs(2);
+/// Width of the thumbnails of screenshots, in pixels.
+const THUMBNAIL_WIDTH: u32 = 320;
+
+/// Crawls websites over plain HTTP, falling back to a WebDriver browser for pages that are
+/// built client side.
+#[derive(Clone)]
+pub struct Crawler {
+    http: reqwest::Client,
+    webdriver_address: String,
+    render_javascript: bool,
+}
+
+/// Counts the characters a visitor would see in `html`, ignoring scripts and styles.
+fn visible_text_len(html: &str) -> usize {
+    let selector = Selector::parse("body").unwrap();
+    let document = Html::parse_document(html);
+    let Some(body) = document.select(&selector).next() else {
+        return 0;
+    };
+    body.descendants()
+        .filter(|node| {
+            !node.ancestors().any(|ancestor| {
+                ancestor.value().as_element().is_some_and(|element| {
+                    matches!(element.name(), "script" | "style" | "noscript" | "template")
+                })
+            })
+        })
+        .filter_map(|node| node.value().as_text())
+        .map(|text| text.trim().chars().count())
+        .sum()
+}
+
+/// Scales a PNG screenshot down to [THUMBNAIL_WIDTH], keeping its aspect ratio.
+fn thumbnail(png: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let image = image::load_from_memory_with_format(png, ImageFormat::Png)?;
+    let mut thumbnail = Cursor::new(Vec::new());
+    image
+        .thumbnail(THUMBNAIL_WIDTH, u32::MAX)
+        .write_to(&mut thumbnail, ImageFormat::Png)?;
+    Ok(thumbnail.into_inner())
+}
+
+impl Crawler {
+    pub fn new(webdriver_address: &str, render_javascript: bool) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            webdriver_address: webdriver_address.to_string(),
+            render_javascript,
+        }
+    }
+
+    /// Replaces the body of `page` with the DOM rendered by `webdriver`. Pages that were already
+    /// rendered are returned as they are, and rendering failures keep the static HTML.
+    async fn render_page(&self, webdriver: Option<&Client>, page: PageSnapshot) -> PageSnapshot {
+        let Some(webdriver) = webdriver else {
+            return page;
+        };
+        if page.rendered {
+            return page;
+        }
+        let rendered = async {
+            webdriver.goto(page.url.as_str()).await?;
+            sleep(RENDER_SETTLE_TIME).await;
+            webdriver.source().await
+        }
+        .await;
+        match rendered {
+            Ok(body) => page.with_rendered_body(body),
+            Err(e) => {
+                tracing::warn!("failed to render {}, keeping static html: {}", page.url, e);
+                page
+            }
+        }
+    }
+
+    /// Fetch a single page. When a `previous` snapshot is available its validators are sent
+    /// along, and a `304 Not Modified` answer returns the stored snapshot instead of a new body.
+    async fn fetch_page(
+        &self,
+        url: &Url,
+        previous: Option<&PageSnapshot>,
+    ) -> Result<PageSnapshot, CrawlWebsiteError> {
+        let mut request = self.http.get(url.to_owned());
+        if let Some(previous) = previous {
+            if let Some(etag) = previous.etag() {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = previous.last_modified() {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+        let response =
+            request
+                .send()
+                .await
+                .map_err(|_| CrawlWebsiteError::FailedToFetchContent {
+                    url: url.to_owned(),
+                })?;
+
+        if response.status() == StatusCode::NOT_MODIFIED
+            && let Some(previous) = previous
+        {
+            tracing::debug!("{} not modified, reusing stored page", url);
+            return Ok(PageSnapshot {
+                fetched_at: Utc::now(),
+                ..previous.clone()
+            });
+        }
+
+        let status = response.status().as_u16();
+        let headers: BTreeMap<String, String> = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| {
+                value
+                    .to_str()
+                    .ok()
+                    .map(|value| (name.to_string(), value.to_string()))
+            })
+            .collect();
+        let body = response
+            .text()
+            .await
+            .map_err(|_| CrawlWebsiteError::FailedToFetchContent {
+                url: url.to_owned(),
+            })?;
+        Ok(PageSnapshot::new(url.to_owned(), status, headers, body))
+    }
+}
+
+impl WebsiteCrawler for Crawler {
+    async fn get_full_website(
+        &self,
+        website_source_address: &str,
+        previous_pages: &[PageSnapshot],
+        pages_crawled: &watch::Sender<usize>,
+    ) -> Result<CrawledWebsite, CrawlWebsiteError> {
+        tracing::debug!("getting full website for {}", website_source_address);
+        let previous: HashMap<&Url, &PageSnapshot> = previous_pages
+            .iter()
+            .map(|page| (&page.url, page))
+            .collect();
+        let url =
+            Url::parse(website_source_address).map_err(|_| CrawlWebsiteError::InvalidUrl {
+                source_address: website_source_address.to_string(),
+            })?;
+        let landing_page = self.fetch_page(&url, previous.get(&url).copied()).await?;
+
+        let needs_rendering = self.render_javascript
+            && (landing_page.rendered
+                || visible_text_len(&landing_page.body) < MIN_VISIBLE_TEXT_LEN);
+        let webdriver = if needs_rendering {
+            tracing::debug!(
+                "{} has little visible text, rendering it",
+                website_source_address
+            );
+            ClientBuilder::native()
+                .connect(&self.webdriver_address)
+                .await
+                .inspect_err(|e| tracing::warn!("failed to connect to WebDriver: {}", e))
+                .ok()
+        } else {
+            None
+        };
+        let landing_page = self.render_page(webdriver.as_ref(), landing_page).await;
+
+        let (to_visit, external_links) = {
+            let selector = Selector::parse("a[href]").unwrap();
+            let fragment = Html::parse_fragment(&landing_page.body);
+
+            let mut internals = Vec::new();
+            let mut externals = Vec::new();
+
+            for el in fragment.select(&selector) {
+                if let Some(href) = el.value().attr("href") {
+                    if href.starts_with("http") {
+                        if let Ok(u) = Url::parse(href) {
+                            externals.push(u);
+                        }
+                        continue;
+                    }
+
+                    if href.starts_with('/')
+                        && href != "/"
+                        && let Ok(u) = Url::parse(&format!("{website_source_address}{href}"))
+                        && !internals.contains(&u)
+                    {
+                        internals.push(u);
+                    }
+                }
+            }
+
+            (internals, externals)
+        };
+
+        let mut pages = vec![landing_page];
+        pages_crawled.send_replace(pages.len());
+        let mut crawl_error = None;
+        for url in to_visit.iter() {
+            match self.fetch_page(url, previous.get(url).copied()).await {
+                Ok(page) => {
+                    pages.push(self.render_page(webdriver.as_ref(), page).await);
+                    pages_crawled.send_replace(pages.len());
+                }
+                Err(e) => {
+                    crawl_error = Some(e);
+                    break;
+                }
+            }
+        }
+        if let Some(webdriver) = webdriver
+            && let Err(e) = webdriver.close().await
+        {
+            tracing::warn!("failed to close WebDriver session: {}", e);
+        }
+        if let Some(e) = crawl_error {
+            return Err(e);
+        }
+
+        let render_mode = if pages.iter().any(|page| page.rendered) {
+            RenderMode::Browser
+        } else {
+            RenderMode::Static
+        };
+        Ok(CrawledWebsite {
+            pages,
+            external_links,
+            render_mode,
+        })
+    }
+
+    async fn capture_screenshot(
+        &self,
+        url: &str,
+        viewport: Viewport,
+    ) -> Result<CapturedScreenshot, CaptureScreenshotError> {
+        tracing::debug!("capturing {} screenshot of {}", viewport, url);
+        let failed = |source: anyhow::Error| CaptureScreenshotError::FailedToCapture {
+            url: url.to_string(),
+            source,
+        };
+        let webdriver = ClientBuilder::native()
+            .connect(&self.webdriver_address)
+            .await
+            .map_err(|e| failed(e.into()))?;
+        let (width, height) = viewport.size();
+        let image = async {
+            webdriver.set_window_size(width, height).await?;
+            webdriver.goto(url).await?;
+            sleep(RENDER_SETTLE_TIME).await;
+            webdriver.screenshot().await
+        }
+        .await;
+        if let Err(e) = webdriver.close().await {
+            tracing::warn!("failed to close WebDriver session: {}", e);
+        }
+        let image = image.map_err(|e| failed(e.into()))?;
+        let thumbnail = thumbnail(&image).map_err(failed)?;
+        Ok(CapturedScreenshot { image, thumbnail })
+    }
+}
@@ -1,53 +1,77 @@
 use std::sync::Arc;
 
 use crate::domain::website::{
-    models::website::{
-        ContactEvent, GeneratedWebsiteEvent, Website, WebsiteEvent, WebsiteEventError,
+    models::{
+        event::EventEnvelope,
+        website::{
+            ContactEvent, GeneratedWebsiteEvent, StageEvent, StageFailedEvent, StageProgressEvent,
+            Website, WebsiteDeletedEvent, WebsiteEvent, WebsiteEventError,
+        },
     },
-    ports::WebsiteNotifier,
+    ports::{EventRepository, WebsiteNotifier},
 };
 use tokio::sync::{
     Mutex,
     broadcast::{Receiver, Sender, channel},
 };
 
+/// Events kept for receivers that fall behind. Receivers lagging further catch up from the
+/// event log.
+const BUFFER_SIZE: usize = 256;
+
+/// Appends every event to the [EventRepository] log, then broadcasts it to the subscribed
+/// receivers. Ephemeral events are broadcast without being logged.
 #[derive(Clone)]
-pub struct EventPublisher {
-    tx: Sender<WebsiteEvent>,
-    _guard: Arc<Mutex<Receiver<WebsiteEvent>>>,
+pub struct EventPublisher<R> {
+    tx: Sender<EventEnvelope>,
+    _guard: Arc<Mutex<Receiver<EventEnvelope>>>,
+    /// Held from appending an event until it is broadcast, so receivers get events in id order.
+    publishing: Arc<Mutex<()>>,
+    repository: R,
 }
 
-impl Default for EventPublisher {
-    fn default() -> Self {
-        let buffer_size = 10;
-        let (tx, rx) = channel(buffer_size);
+impl<R: EventRepository> EventPublisher<R> {
+    pub fn new(repository: R) -> Self {
+        let (tx, rx) = channel(BUFFER_SIZE);
         let _guard = Arc::new(Mutex::new(rx));
-        Self { tx, _guard }
+        Self {
+            tx,
+            _guard,
+            publishing: Arc::new(Mutex::new(())),
+            repository,
+        }
+    }
+
+    async fn publish(&self, event: WebsiteEvent) -> Result<usize, WebsiteEventError> {
+        let _publishing = self.publishing.lock().await;
+        let stored = if event.is_ephemeral() {
+            self.repository.wrap_ephemeral_event(&event).await
+        } else {
+            self.repository.append_event(&event).await
+        }
+        .map_err(|e| WebsiteEventError::Unknown(e.into()))?;
+        self.tx.send(stored).map_err(|e| {
+            tracing::debug!("{}", e);
+            WebsiteEventError::Unknown(e.into())
+        })
     }
 }
-impl WebsiteNotifier for EventPublisher {
+
+impl<R: EventRepository> WebsiteNotifier for EventPublisher<R> {
     async fn contact_fetched(&self, contact: &ContactEvent) -> Result<usize, WebsiteEventError> {
         tracing::debug!("Sending event for contact_fetched");
-        self.tx
-            .send(WebsiteEvent::FetchedContact(contact.to_owned()))
-            .map_err(|e| {
-                tracing::debug!("{}", e);
-                WebsiteEventError::Unknown(e.into())
-            })
+        self.publish(WebsiteEvent::FetchedContact(contact.to_owned()))
+            .await
     }
 
-    fn get_receiver(&self) -> Receiver<WebsiteEvent> {
+    fn get_receiver(&self) -> Receiver<EventEnvelope> {
         self.tx.subscribe()
     }
 
     async fn website_added(&self, website: &Website) -> Result<usize, WebsiteEventError> {
         tracing::debug!("Sending event for website_added");
-        self.tx
-            .send(WebsiteEvent::WebsiteAdded(website.to_owned()))
-            .map_err(|e| {
-                tracing::debug!("{}", e);
-                WebsiteEventError::Unknown(e.into())
-            })
+        self.publish(WebsiteEvent::WebsiteAdded(Box::new(website.to_owned())))
+            .await
     }
 
     async fn website_generated(
@@ -55,11 +79,43 @@ impl WebsiteNotifier for EventPublisher {
         generated_website: GeneratedWebsiteEvent,
     ) -> Result<usize, WebsiteEventError> {
         tracing::debug!("Sending event for website_generated");
-        self.tx
-            .send(WebsiteEvent::GeneratedWebsite(generated_website.to_owned()))
-            .map_err(|e| {
-                tracing::debug!("{}", e);
-                WebsiteEventError::Unknown(e.into())
-            })
+        self.publish(WebsiteEvent::GeneratedWebsite(generated_website))
+            .await
+    }
+
+    async fn stage_started(&self, stage: &StageEvent) -> Result<usize, WebsiteEventError> {
+        tracing::debug!("Sending event for stage_started");
+        self.publish(WebsiteEvent::StageStarted(stage.to_owned()))
+            .await
+    }
+
+    async fn stage_progress(
+        &self,
+        progress: &StageProgressEvent,
+    ) -> Result<usize, WebsiteEventError> {
+        tracing::debug!("Sending event for stage_progress");
+        self.publish(WebsiteEvent::StageProgress(progress.to_owned()))
+            .await
+    }
+
+    async fn stage_failed(&self, failure: &StageFailedEvent) -> Result<usize, WebsiteEventError> {
+        tracing::debug!("Sending event for stage_failed");
+        self.publish(WebsiteEvent::StageFailed(failure.to_owned()))
+            .await
+    }
+
+    async fn contact_updated(&self, contact: &ContactEvent) -> Result<usize, WebsiteEventError> {
+        tracing::debug!("Sending event for contact_updated");
+        self.publish(WebsiteEvent::ContactUpdated(contact.to_owned()))
+            .await
+    }
+
+    async fn website_deleted(
+        &self,
+        deleted: &WebsiteDeletedEvent,
+    ) -> Result<usize, WebsiteEventError> {
+        tracing::debug!("Sending event for website_deleted");
+        self.publish(WebsiteEvent::WebsiteDeleted(deleted.to_owned()))
+            .await
     }
 }
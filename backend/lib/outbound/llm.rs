@@ -0,0 +1,464 @@
+/*!
+   Module `llm` talks to large language models through a provider agnostic interface. OpenAI, any
+   OpenAI-compatible server (Ollama, llama.cpp, vLLM, ...) and Anthropic are supported, selected
+   through [LlmConfig].
+*/
+
+use std::{str::FromStr, time::Duration};
+
+use anyhow::{Context, anyhow};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use thiserror::Error;
+
+const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+const ANTHROPIC_BASE_URL: &str = "https://api.anthropic.com/v1";
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+const ANTHROPIC_MAX_TOKENS: u32 = 4096;
+/// Conservative average of bytes per token. HTML and non-English text tokenize worse than the
+/// usual four bytes per token of English prose, so this errs on the side of smaller chunks.
+const BYTES_PER_TOKEN: usize = 3;
+
+/// The API flavour spoken by the configured LLM backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LlmProvider {
+    OpenAi,
+    /// Any server implementing the OpenAI chat completions API, e.g. a local Ollama.
+    OpenAiCompatible,
+    Anthropic,
+}
+
+impl FromStr for LlmProvider {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "openai" => Ok(Self::OpenAi),
+            "openai_compatible" => Ok(Self::OpenAiCompatible),
+            "anthropic" => Ok(Self::Anthropic),
+            _ => Err(anyhow!(
+                "unknown llm provider {s}, expected openai, openai_compatible or anthropic"
+            )),
+        }
+    }
+}
+
+impl LlmProvider {
+    fn default_base_url(&self) -> Option<&'static str> {
+        match self {
+            Self::OpenAi => Some(OPENAI_BASE_URL),
+            Self::OpenAiCompatible => None,
+            Self::Anthropic => Some(ANTHROPIC_BASE_URL),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::OpenAi => "openai",
+            Self::OpenAiCompatible => "openai_compatible",
+            Self::Anthropic => "anthropic",
+        }
+    }
+
+    fn requires_api_key(&self) -> bool {
+        !matches!(self, Self::OpenAiCompatible)
+    }
+}
+
+/// Configuration of the LLM backend.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LlmConfig {
+    pub provider: LlmProvider,
+    pub model: String,
+    pub temperature: Option<f64>,
+    pub base_url: Option<String>,
+    pub api_key: Option<String>,
+    pub timeout: Duration,
+    /// Maximum number of tokens of website content sent in a single request.
+    pub token_budget: usize,
+    /// Price of the configured model, used to estimate the cost of each call.
+    pub pricing: Option<LlmPricing>,
+}
+
+/// Price of a model in USD per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LlmPricing {
+    pub prompt_per_mtok: f64,
+    pub completion_per_mtok: f64,
+}
+
+/// Tokens consumed by a single call, as reported by the provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TokenUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+}
+
+/// The arguments the model called a tool with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolCall {
+    pub arguments: Value,
+    /// `None` when the server does not report usage, as some OpenAI-compatible ones do.
+    pub usage: Option<TokenUsage>,
+}
+
+/// Estimates the number of tokens `text` uses, independently of the provider's tokenizer.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.len().div_ceil(BYTES_PER_TOKEN)
+}
+
+/// Packs `sections` in order into chunks of at most `token_budget` estimated tokens. Sections
+/// are kept whole when they fit, larger ones are split on character boundaries. A character
+/// larger than the budget gets a chunk of its own.
+pub fn chunk(sections: &[String], token_budget: usize) -> Vec<String> {
+    let max_bytes = token_budget.max(1) * BYTES_PER_TOKEN;
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for section in sections {
+        if current.len() + section.len() > max_bytes && !current.is_empty() {
+            chunks.push(std::mem::take(&mut current));
+        }
+        let mut rest = section.as_str();
+        while rest.len() > max_bytes {
+            let mut split = max_bytes;
+            while !rest.is_char_boundary(split) {
+                split -= 1;
+            }
+            if split == 0 {
+                split = rest.chars().next().map_or(rest.len(), char::len_utf8);
+            }
+            let (head, tail) = rest.split_at(split);
+            chunks.push(head.to_string());
+            rest = tail;
+        }
+        current.push_str(rest);
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// A function the model is forced to call, described by a JSON schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolDefinition {
+    pub name: String,
+    pub description: String,
+    pub parameters: Value,
+}
+
+#[derive(Debug, Error)]
+pub enum LlmError {
+    #[error("llm request failed")]
+    Request(#[from] reqwest::Error),
+    #[error("llm responded with {status}: {body}")]
+    Status {
+        status: reqwest::StatusCode,
+        body: String,
+    },
+    #[error("llm did not call the {tool} tool")]
+    NoToolCall { tool: String },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+/// Client for the configured LLM provider.
+#[derive(Debug, Clone)]
+pub struct Llm {
+    http: reqwest::Client,
+    provider: LlmProvider,
+    model: String,
+    temperature: Option<f64>,
+    base_url: String,
+    api_key: Option<String>,
+    token_budget: usize,
+    pricing: Option<LlmPricing>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiResponse {
+    choices: Vec<OpenAiChoice>,
+    usage: Option<OpenAiUsage>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiUsage {
+    prompt_tokens: u32,
+    completion_tokens: u32,
+}
+
+#[derive(Deserialize)]
+struct OpenAiChoice {
+    message: OpenAiMessage,
+}
+
+#[derive(Deserialize)]
+struct OpenAiMessage {
+    content: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<OpenAiToolCall>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiToolCall {
+    function: OpenAiFunctionCall,
+}
+
+#[derive(Deserialize)]
+struct OpenAiFunctionCall {
+    name: String,
+    arguments: String,
+}
+
+#[derive(Deserialize)]
+struct AnthropicResponse {
+    content: Vec<AnthropicContent>,
+    usage: Option<AnthropicUsage>,
+}
+
+#[derive(Deserialize)]
+struct AnthropicUsage {
+    input_tokens: u32,
+    output_tokens: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum AnthropicContent {
+    ToolUse {
+        name: String,
+        input: Value,
+    },
+    #[serde(other)]
+    Other,
+}
+
+impl Llm {
+    /// Builds a client for `config`.
+    ///
+    /// # Errors
+    ///
+    /// Fails when the provider needs an api key or base url that is not configured.
+    pub fn new(config: LlmConfig) -> anyhow::Result<Self> {
+        let base_url = config
+            .base_url
+            .or_else(|| config.provider.default_base_url().map(str::to_string))
+            .with_context(|| format!("a base url is required for {:?}", config.provider))?;
+        if config.provider.requires_api_key() && config.api_key.is_none() {
+            return Err(anyhow!("an api key is required for {:?}", config.provider));
+        }
+        let http = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .build()
+            .context("failed to build llm http client")?;
+        Ok(Self {
+            http,
+            provider: config.provider,
+            model: config.model,
+            temperature: config.temperature,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: config.api_key,
+            token_budget: config.token_budget,
+            pricing: config.pricing,
+        })
+    }
+
+    pub fn provider(&self) -> LlmProvider {
+        self.provider
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Cost in USD of a call that used `usage` tokens, `None` when no pricing is configured.
+    pub fn estimate_cost(&self, usage: &TokenUsage) -> Option<f64> {
+        self.pricing.map(|pricing| {
+            (f64::from(usage.prompt_tokens) * pricing.prompt_per_mtok
+                + f64::from(usage.completion_tokens) * pricing.completion_per_mtok)
+                / 1_000_000.0
+        })
+    }
+
+    /// Maximum number of tokens of website content to send in a single request.
+    pub fn token_budget(&self) -> usize {
+        self.token_budget
+    }
+
+    /// Sends `prompt` and forces the model to answer by calling `tool`, returning the arguments
+    /// of the call and the tokens it used.
+    pub async fn call_tool(
+        &self,
+        prompt: &str,
+        tool: &ToolDefinition,
+    ) -> Result<ToolCall, LlmError> {
+        match self.provider {
+            LlmProvider::OpenAi | LlmProvider::OpenAiCompatible => {
+                self.call_openai_tool(prompt, tool).await
+            }
+            LlmProvider::Anthropic => self.call_anthropic_tool(prompt, tool).await,
+        }
+    }
+
+    async fn call_openai_tool(
+        &self,
+        prompt: &str,
+        tool: &ToolDefinition,
+    ) -> Result<ToolCall, LlmError> {
+        let mut body = json!({
+            "model": self.model,
+            "messages": [{ "role": "user", "content": prompt }],
+            "tools": [{
+                "type": "function",
+                "function": {
+                    "name": tool.name,
+                    "description": tool.description,
+                    "parameters": tool.parameters,
+                },
+            }],
+            "tool_choice": { "type": "function", "function": { "name": tool.name } },
+        });
+        if let Some(temperature) = self.temperature {
+            body["temperature"] = json!(temperature);
+        }
+        let mut request = self
+            .http
+            .post(format!("{}/chat/completions", self.base_url))
+            .json(&body);
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+        let response: OpenAiResponse = Self::send(request).await?;
+        let usage = response.usage.map(|usage| TokenUsage {
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+        });
+        let message = response
+            .choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.message)
+            .ok_or_else(|| LlmError::NoToolCall {
+                tool: tool.name.clone(),
+            })?;
+
+        if let Some(call) = message
+            .tool_calls
+            .into_iter()
+            .find(|call| call.function.name == tool.name)
+        {
+            let arguments = serde_json::from_str(&call.function.arguments)
+                .context("tool arguments are not valid json")?;
+            return Ok(ToolCall { arguments, usage });
+        }
+        // Small local models often ignore `tool_choice` and answer with the json as plain text.
+        message
+            .content
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .map(|arguments| ToolCall { arguments, usage })
+            .ok_or_else(|| LlmError::NoToolCall {
+                tool: tool.name.clone(),
+            })
+    }
+
+    async fn call_anthropic_tool(
+        &self,
+        prompt: &str,
+        tool: &ToolDefinition,
+    ) -> Result<ToolCall, LlmError> {
+        let mut body = json!({
+            "model": self.model,
+            "max_tokens": ANTHROPIC_MAX_TOKENS,
+            "messages": [{ "role": "user", "content": prompt }],
+            "tools": [{
+                "name": tool.name,
+                "description": tool.description,
+                "input_schema": tool.parameters,
+            }],
+            "tool_choice": { "type": "tool", "name": tool.name },
+        });
+        if let Some(temperature) = self.temperature {
+            body["temperature"] = json!(temperature);
+        }
+        let request = self
+            .http
+            .post(format!("{}/messages", self.base_url))
+            .header("x-api-key", self.api_key.as_deref().unwrap_or_default())
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .json(&body);
+        let response: AnthropicResponse = Self::send(request).await?;
+        let usage = response.usage.map(|usage| TokenUsage {
+            prompt_tokens: usage.input_tokens,
+            completion_tokens: usage.output_tokens,
+        });
+        response
+            .content
+            .into_iter()
+            .find_map(|content| match content {
+                AnthropicContent::ToolUse { name, input } if name == tool.name => Some(input),
+                _ => None,
+            })
+            .map(|arguments| ToolCall { arguments, usage })
+            .ok_or_else(|| LlmError::NoToolCall {
+                tool: tool.name.clone(),
+            })
+    }
+
+    async fn send<T: for<'de> Deserialize<'de>>(
+        request: reqwest::RequestBuilder,
+    ) -> Result<T, LlmError> {
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(LlmError::Status { status, body });
+        }
+        Ok(response.json().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::chunk;
+
+    fn sections(sections: &[&str]) -> Vec<String> {
+        sections.iter().map(|section| section.to_string()).collect()
+    }
+
+    #[test]
+    fn chunk_packs_sections_that_fit_together() {
+        let chunks = chunk(&sections(&["abc", "def", "ghi"]), 2);
+        assert_eq!(chunks, sections(&["abcdef", "ghi"]));
+    }
+
+    #[test]
+    fn chunk_splits_sections_larger_than_the_budget() {
+        let chunks = chunk(&sections(&["abcdefgh"]), 1);
+        assert_eq!(chunks, sections(&["abc", "def", "gh"]));
+    }
+
+    #[test]
+    fn chunk_splits_on_character_boundaries() {
+        let chunks = chunk(&sections(&["aéé"]), 1);
+        assert_eq!(chunks, sections(&["aé", "é"]));
+        assert_eq!(chunks.concat(), "aéé");
+    }
+
+    #[test]
+    fn chunk_keeps_characters_larger_than_the_budget_whole() {
+        let chunks = chunk(&sections(&["🦀🦀a"]), 1);
+        assert_eq!(chunks, sections(&["🦀", "🦀", "a"]));
+    }
+
+    #[test]
+    fn chunk_treats_a_zero_budget_as_one_token() {
+        let chunks = chunk(&sections(&["abcd"]), 0);
+        assert_eq!(chunks, sections(&["abc", "d"]));
+    }
+
+    #[test]
+    fn chunk_of_nothing_is_empty() {
+        assert!(chunk(&[], 10).is_empty());
+        assert!(chunk(&sections(&[""]), 10).is_empty());
+    }
+}
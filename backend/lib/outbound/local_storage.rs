@@ -0,0 +1,75 @@
+use std::{
+    io::ErrorKind,
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::{Context, anyhow};
+
+use crate::domain::website::{
+    models::object_storage::{DeleteObjectError, GetObjectError, PutObjectError},
+    ports::ObjectStorage,
+};
+
+/// Keeps objects as files under `root`, with the key as their relative path.
+#[derive(Debug, Clone)]
+pub struct LocalObjectStorage {
+    root: PathBuf,
+}
+
+impl LocalObjectStorage {
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+        }
+    }
+
+    /// Rejects keys that would resolve outside of `root`.
+    fn path(&self, key: &str) -> anyhow::Result<PathBuf> {
+        let relative = Path::new(key);
+        if key.is_empty()
+            || !relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(anyhow!("invalid object key {key}"));
+        }
+        Ok(self.root.join(relative))
+    }
+}
+
+impl ObjectStorage for LocalObjectStorage {
+    async fn put_object(&self, key: &str, content: &[u8]) -> Result<(), PutObjectError> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        tokio::fs::write(&path, content)
+            .await
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        Ok(())
+    }
+
+    async fn get_object(&self, key: &str) -> Result<Vec<u8>, GetObjectError> {
+        let path = self.path(key)?;
+        tokio::fs::read(&path).await.map_err(|e| match e.kind() {
+            ErrorKind::NotFound => GetObjectError::NotFound {
+                key: key.to_string(),
+            },
+            _ => GetObjectError::Unknown(
+                anyhow::Error::new(e).context(format!("failed to read {}", path.display())),
+            ),
+        })
+    }
+
+    async fn delete_object(&self, key: &str) -> Result<(), DeleteObjectError> {
+        let path = self.path(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(anyhow::Error::new(e)
+                .context(format!("failed to delete {}", path.display()))
+                .into()),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use anyhow::{Context, anyhow};
+use fantoccini::{Client, cookies::Cookie};
+use tokio::sync::Mutex;
+use url::Url;
+use uuid::Uuid;
+
+use crate::domain::website::{
+    models::{
+        automation_failure::RecordAutomationFailureRequest,
+        preview::DeletePreviewError,
+        prompt::{PromptName, landing_page_variables},
+        website::{GenerateWebsiteError, GeneratedWebsite, Website},
+    },
+    ports::{AutomationFailureRepository, PromptRepository, SiteGenerator},
+};
+
+use super::{
+    builder_script::{self, BuilderScript, NAME_OUTPUT, StepError, URL_OUTPUT},
+    webdriver_pool::{AcquireSessionError, PooledSession, WebdriverPool},
+};
+
+/// Generates landing pages by driving the Lovable web UI through WebDriver, with the
+/// [PromptName::LandingPage] prompt of the [PromptRepository]. The flow follows a [BuilderScript],
+/// so it can also drive other site builders. Logged in sessions are kept in a
+/// [WebdriverPool], and the login cookies are copied into new sessions to skip the login form.
+/// When a run fails, the state of the browser is stored in the [AutomationFailureRepository].
+#[derive(Clone)]
+pub struct Lovable<P> {
+    sessions: WebdriverPool,
+    script: Arc<BuilderScript>,
+    cookies: Arc<Mutex<Vec<Cookie<'static>>>>,
+    lovable_user: String,
+    lovable_password: String,
+    repository: P,
+}
+
+impl<P: PromptRepository + AutomationFailureRepository> Lovable<P> {
+    pub fn new(
+        sessions: WebdriverPool,
+        script: BuilderScript,
+        lovable_user: &str,
+        lovable_password: &str,
+        repository: P,
+    ) -> Self {
+        Self {
+            sessions,
+            script: Arc::new(script),
+            cookies: Arc::new(Mutex::new(Vec::new())),
+            lovable_user: lovable_user.to_string(),
+            lovable_password: lovable_password.to_string(),
+            repository,
+        }
+    }
+
+    /// Makes sure the session is logged in to Lovable, first with the cookies of an earlier login
+    /// and only then through the login steps.
+    async fn ensure_logged_in(&self, session: &PooledSession) -> Result<(), GenerateWebsiteError> {
+        let webdriver = session.client();
+        if session.is_fresh() {
+            let cookies = self.cookies.lock().await.clone();
+            if !cookies.is_empty() {
+                // Cookies can only be set for the domain of the current page.
+                webdriver.goto(&self.script.base_url).await?;
+                for cookie in cookies {
+                    webdriver.add_cookie(cookie).await?;
+                }
+            }
+        }
+        let no_variables = BTreeMap::new();
+        if let Err(e) = builder_script::run(
+            webdriver,
+            "check_logged_in",
+            &self.script.check_logged_in,
+            &no_variables,
+        )
+        .await
+        {
+            tracing::debug!("logging in to Lovable, not logged in: {}", e);
+            let credentials = BTreeMap::from([
+                ("user", self.lovable_user.as_str()),
+                ("password", self.lovable_password.as_str()),
+            ]);
+            builder_script::run(webdriver, "log_in", &self.script.log_in, &credentials).await?;
+            *self.cookies.lock().await = webdriver.get_all_cookies().await?;
+        }
+        Ok(())
+    }
+
+    /// Stores the url, page source and screenshot of the browser after `error`. Whatever the
+    /// browser can no longer provide is left out.
+    async fn record_failure(
+        &self,
+        website: &Website,
+        run_id: Uuid,
+        webdriver: &Client,
+        error: &GenerateWebsiteError,
+    ) {
+        let url = webdriver.current_url().await.map(String::from);
+        let page_source = webdriver.source().await;
+        let screenshot = webdriver.screenshot().await;
+        for e in [
+            url.as_ref().err(),
+            page_source.as_ref().err(),
+            screenshot.as_ref().err(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            tracing::warn!(
+                "failed to capture the browser state of run {}: {}",
+                run_id,
+                e
+            );
+        }
+        let req = RecordAutomationFailureRequest {
+            website_id: website.id,
+            run_id,
+            error: format!("{error:?}"),
+            url: url.ok(),
+            page_source: page_source.ok(),
+            screenshot: screenshot.ok(),
+        };
+        if let Err(e) = self.repository.record_automation_failure(&req).await {
+            tracing::error!("failed to record the failure of run {}: {:?}", run_id, e);
+        }
+    }
+
+    async fn generate(
+        &self,
+        webdriver: &Client,
+        prompt: &str,
+    ) -> Result<GeneratedWebsite, GenerateWebsiteError> {
+        let variables = BTreeMap::from([("prompt", prompt)]);
+        let mut outputs =
+            builder_script::run(webdriver, "generate", &self.script.generate, &variables).await?;
+        let name = outputs.remove(NAME_OUTPUT).ok_or_else(|| {
+            GenerateWebsiteError::Unknown(anyhow!("the generate steps did not read the name"))
+        })?;
+        let url = match outputs.remove(URL_OUTPUT) {
+            Some(url) => Url::parse(&url)
+                .with_context(|| format!("the generate steps read an invalid url {url}"))?,
+            None => webdriver.current_url().await?,
+        };
+        Ok(GeneratedWebsite {
+            name,
+            url,
+            prompt_version: None,
+        })
+    }
+}
+
+impl From<StepError> for GenerateWebsiteError {
+    fn from(value: StepError) -> Self {
+        GenerateWebsiteError::AutomationStepFailed {
+            step: format!("step {} of {}: {:?}", value.index, value.steps, value.step),
+            source: value.source,
+        }
+    }
+}
+
+impl From<AcquireSessionError> for GenerateWebsiteError {
+    fn from(value: AcquireSessionError) -> Self {
+        match value {
+            AcquireSessionError::Connect(e) => GenerateWebsiteError::WebdriverUnavailable(e),
+            e @ AcquireSessionError::Closed => GenerateWebsiteError::Unknown(e.into()),
+        }
+    }
+}
+
+impl From<fantoccini::error::CmdError> for GenerateWebsiteError {
+    fn from(value: fantoccini::error::CmdError) -> Self {
+        GenerateWebsiteError::WebdriverError(value)
+    }
+}
+
+impl<P: PromptRepository + AutomationFailureRepository> SiteGenerator for Lovable<P> {
+    async fn generate_new_single_page(
+        &self,
+        website: &Website,
+    ) -> Result<GeneratedWebsite, GenerateWebsiteError> {
+        tracing::debug!("generating new single page");
+        let template = self
+            .repository
+            .get_prompt(PromptName::LandingPage)
+            .await
+            .map_err(|e| GenerateWebsiteError::Unknown(e.into()))?;
+        let variables = landing_page_variables(website);
+        let prompt = template.render(&variables).replace("\n", " ");
+
+        let run_id = Uuid::new_v4();
+        tracing::debug!("starting Lovable run {}", run_id);
+        let session = self.sessions.acquire().await?;
+        let result = match self.ensure_logged_in(&session).await {
+            Ok(()) => self.generate(session.client(), &prompt).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(generated) => {
+                session.release().await;
+                Ok(GeneratedWebsite {
+                    prompt_version: Some(template.version),
+                    ..generated
+                })
+            }
+            Err(e) => {
+                self.record_failure(website, run_id, session.client(), &e)
+                    .await;
+                // The browser is left in an unknown state, start over with a new session.
+                session.discard().await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Sites built on Lovable are hosted there, no preview is stored locally.
+    async fn delete_preview(&self, _slug: &str) -> Result<(), DeletePreviewError> {
+        Ok(())
+    }
+}
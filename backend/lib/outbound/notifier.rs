@@ -0,0 +1,100 @@
+/*!
+   Module `notifier` selects the [WebsiteNotifier] implementation at runtime, from the
+   configuration.
+*/
+
+use tokio::sync::broadcast::Receiver;
+
+use crate::domain::website::{
+    models::{
+        event::EventEnvelope,
+        website::{
+            ContactEvent, GeneratedWebsiteEvent, StageEvent, StageFailedEvent, StageProgressEvent,
+            Website, WebsiteDeletedEvent, WebsiteEventError,
+        },
+    },
+    ports::{EventRepository, WebsiteNotifier},
+};
+
+use super::{event_publisher::EventPublisher, pg_notifier::PgNotifier};
+
+/// One of the available [WebsiteNotifier] implementations.
+#[derive(Clone)]
+pub enum ConfiguredNotifier<R> {
+    Local(EventPublisher<R>),
+    Postgres(PgNotifier),
+}
+
+impl<R: EventRepository> WebsiteNotifier for ConfiguredNotifier<R> {
+    fn get_receiver(&self) -> Receiver<EventEnvelope> {
+        match self {
+            Self::Local(publisher) => publisher.get_receiver(),
+            Self::Postgres(notifier) => notifier.get_receiver(),
+        }
+    }
+
+    async fn contact_fetched(&self, contact: &ContactEvent) -> Result<usize, WebsiteEventError> {
+        match self {
+            Self::Local(publisher) => publisher.contact_fetched(contact).await,
+            Self::Postgres(notifier) => notifier.contact_fetched(contact).await,
+        }
+    }
+
+    async fn website_added(&self, website: &Website) -> Result<usize, WebsiteEventError> {
+        match self {
+            Self::Local(publisher) => publisher.website_added(website).await,
+            Self::Postgres(notifier) => notifier.website_added(website).await,
+        }
+    }
+
+    async fn website_generated(
+        &self,
+        generated_website: GeneratedWebsiteEvent,
+    ) -> Result<usize, WebsiteEventError> {
+        match self {
+            Self::Local(publisher) => publisher.website_generated(generated_website).await,
+            Self::Postgres(notifier) => notifier.website_generated(generated_website).await,
+        }
+    }
+
+    async fn stage_started(&self, stage: &StageEvent) -> Result<usize, WebsiteEventError> {
+        match self {
+            Self::Local(publisher) => publisher.stage_started(stage).await,
+            Self::Postgres(notifier) => notifier.stage_started(stage).await,
+        }
+    }
+
+    async fn stage_progress(
+        &self,
+        progress: &StageProgressEvent,
+    ) -> Result<usize, WebsiteEventError> {
+        match self {
+            Self::Local(publisher) => publisher.stage_progress(progress).await,
+            Self::Postgres(notifier) => notifier.stage_progress(progress).await,
+        }
+    }
+
+    async fn stage_failed(&self, failure: &StageFailedEvent) -> Result<usize, WebsiteEventError> {
+        match self {
+            Self::Local(publisher) => publisher.stage_failed(failure).await,
+            Self::Postgres(notifier) => notifier.stage_failed(failure).await,
+        }
+    }
+
+    async fn contact_updated(&self, contact: &ContactEvent) -> Result<usize, WebsiteEventError> {
+        match self {
+            Self::Local(publisher) => publisher.contact_updated(contact).await,
+            Self::Postgres(notifier) => notifier.contact_updated(contact).await,
+        }
+    }
+
+    async fn website_deleted(
+        &self,
+        deleted: &WebsiteDeletedEvent,
+    ) -> Result<usize, WebsiteEventError> {
+        match self {
+            Self::Local(publisher) => publisher.website_deleted(deleted).await,
+            Self::Postgres(notifier) => notifier.website_deleted(deleted).await,
+        }
+    }
+}
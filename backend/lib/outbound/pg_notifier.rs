@@ -0,0 +1,195 @@
+/*!
+   Module `pg_notifier` fans website events out to every server instance through Postgres
+   `NOTIFY`, so clients receive the events of pipelines running on other replicas.
+*/
+
+use std::time::Duration;
+
+use tokio::{
+    sync::broadcast::{Receiver, Sender, channel},
+    time::sleep,
+};
+
+use crate::domain::website::{
+    models::{
+        event::EventEnvelope,
+        website::{
+            ContactEvent, GeneratedWebsiteEvent, StageEvent, StageFailedEvent, StageProgressEvent,
+            Website, WebsiteDeletedEvent, WebsiteEvent, WebsiteEventError,
+        },
+    },
+    ports::{EventRepository, WebsiteNotifier},
+};
+
+use super::postgres::Postgres;
+
+/// The channel notified with the id of every logged event.
+const CHANNEL: &str = "website_events";
+/// The channel notified with every ephemeral event, in its envelope.
+const EPHEMERAL_CHANNEL: &str = "website_ephemeral_events";
+/// Events kept for receivers that fall behind. Receivers lagging further catch up from the
+/// event log.
+const BUFFER_SIZE: usize = 256;
+/// Most events read from the log at once when relaying.
+const RELAY_BATCH: i64 = 100;
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Appends every event to the log and notifies all instances, which relay the logged events to
+/// their subscribed receivers. Events published locally reach the receivers the same way.
+/// Ephemeral events are sent along with the notification instead of being logged.
+#[derive(Clone)]
+pub struct PgNotifier {
+    tx: Sender<EventEnvelope>,
+    postgres: Postgres,
+}
+
+impl PgNotifier {
+    /// Starts relaying the events logged from now on.
+    pub async fn new(postgres: Postgres) -> anyhow::Result<Self> {
+        let last_event_id = postgres.last_event_id().await?;
+        let (tx, _) = channel(BUFFER_SIZE);
+        tokio::spawn(relay(postgres.clone(), tx.clone(), last_event_id));
+        Ok(Self { tx, postgres })
+    }
+
+    async fn publish(&self, event: WebsiteEvent) -> Result<usize, WebsiteEventError> {
+        if event.is_ephemeral() {
+            self.postgres
+                .notify_ephemeral_event(&event, EPHEMERAL_CHANNEL)
+                .await
+        } else {
+            self.postgres.append_and_notify_event(&event, CHANNEL).await
+        }
+        .map_err(|e| WebsiteEventError::Unknown(e.into()))?;
+        Ok(self.tx.receiver_count())
+    }
+}
+
+/// Broadcasts the events logged after `last_relayed`, returning the id of the last one sent.
+async fn relay_logged(
+    postgres: &Postgres,
+    tx: &Sender<EventEnvelope>,
+    mut last_relayed: i64,
+) -> i64 {
+    loop {
+        let events = match postgres.get_events_after(last_relayed, RELAY_BATCH).await {
+            Ok(events) => events,
+            Err(e) => {
+                tracing::error!("failed to read the event log: {:?}", e);
+                return last_relayed;
+            }
+        };
+        let done = (events.len() as i64) < RELAY_BATCH;
+        for event in events {
+            last_relayed = event.event_id;
+            // Fails only when nobody is subscribed.
+            let _ = tx.send(event);
+        }
+        if done {
+            return last_relayed;
+        }
+    }
+}
+
+/// Relays the logged events whenever the channel is notified. Notifications are only used as a
+/// signal, the events are read from the log, so none are lost while reconnecting. Ephemeral
+/// events are relayed from their notification, and the ones sent while reconnecting are lost.
+async fn relay(postgres: Postgres, tx: Sender<EventEnvelope>, mut last_relayed: i64) {
+    loop {
+        let mut listener = match postgres.listen(&[CHANNEL, EPHEMERAL_CHANNEL]).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                tracing::error!("failed to listen to {}: {}", CHANNEL, e);
+                sleep(RECONNECT_DELAY).await;
+                continue;
+            }
+        };
+        loop {
+            last_relayed = relay_logged(&postgres, &tx, last_relayed).await;
+            match listener.try_recv().await {
+                Ok(Some(notification)) if notification.channel() == EPHEMERAL_CHANNEL => {
+                    match serde_json::from_str::<EventEnvelope>(notification.payload()) {
+                        // Fails only when nobody is subscribed.
+                        Ok(event) => {
+                            let _ = tx.send(event);
+                        }
+                        Err(e) => tracing::error!("invalid ephemeral event: {}", e),
+                    }
+                }
+                Ok(Some(_)) => {}
+                Ok(None) => {
+                    tracing::warn!("lost the connection listening to {}", CHANNEL);
+                    break;
+                }
+                Err(e) => {
+                    tracing::error!("failed to receive from {}: {}", CHANNEL, e);
+                    sleep(RECONNECT_DELAY).await;
+                    break;
+                }
+            }
+        }
+    }
+}
+
+impl WebsiteNotifier for PgNotifier {
+    async fn contact_fetched(&self, contact: &ContactEvent) -> Result<usize, WebsiteEventError> {
+        tracing::debug!("Sending event for contact_fetched");
+        self.publish(WebsiteEvent::FetchedContact(contact.to_owned()))
+            .await
+    }
+
+    fn get_receiver(&self) -> Receiver<EventEnvelope> {
+        self.tx.subscribe()
+    }
+
+    async fn website_added(&self, website: &Website) -> Result<usize, WebsiteEventError> {
+        tracing::debug!("Sending event for website_added");
+        self.publish(WebsiteEvent::WebsiteAdded(Box::new(website.to_owned())))
+            .await
+    }
+
+    async fn website_generated(
+        &self,
+        generated_website: GeneratedWebsiteEvent,
+    ) -> Result<usize, WebsiteEventError> {
+        tracing::debug!("Sending event for website_generated");
+        self.publish(WebsiteEvent::GeneratedWebsite(generated_website))
+            .await
+    }
+
+    async fn stage_started(&self, stage: &StageEvent) -> Result<usize, WebsiteEventError> {
+        tracing::debug!("Sending event for stage_started");
+        self.publish(WebsiteEvent::StageStarted(stage.to_owned()))
+            .await
+    }
+
+    async fn stage_progress(
+        &self,
+        progress: &StageProgressEvent,
+    ) -> Result<usize, WebsiteEventError> {
+        tracing::debug!("Sending event for stage_progress");
+        self.publish(WebsiteEvent::StageProgress(progress.to_owned()))
+            .await
+    }
+
+    async fn stage_failed(&self, failure: &StageFailedEvent) -> Result<usize, WebsiteEventError> {
+        tracing::debug!("Sending event for stage_failed");
+        self.publish(WebsiteEvent::StageFailed(failure.to_owned()))
+            .await
+    }
+
+    async fn contact_updated(&self, contact: &ContactEvent) -> Result<usize, WebsiteEventError> {
+        tracing::debug!("Sending event for contact_updated");
+        self.publish(WebsiteEvent::ContactUpdated(contact.to_owned()))
+            .await
+    }
+
+    async fn website_deleted(
+        &self,
+        deleted: &WebsiteDeletedEvent,
+    ) -> Result<usize, WebsiteEventError> {
+        tracing::debug!("Sending event for website_deleted");
+        self.publish(WebsiteEvent::WebsiteDeleted(deleted.to_owned()))
+            .await
+    }
+}
@@ -1,15 +1,104 @@
 use anyhow::Context;
-use sqlx::{PgPool, postgres::PgConnectOptions};
-use std::str::FromStr;
+use chrono::{DateTime, Utc};
+use sqlx::{
+    Decode, PgConnection, PgExecutor, PgPool, Type,
+    error::BoxDynError,
+    postgres::{PgConnectOptions, PgListener, PgTypeInfo, PgValueRef},
+    types::Json,
+};
+use std::{collections::BTreeMap, str::FromStr, time::Duration};
+use url::Url;
+use uuid::Uuid;
 
 use crate::domain::website::{
-    models::website::{
-        Contact, CreateWebsiteError, CreateWebsiteRequest, GeneratedWebsite, GetWebsitesError,
-        UpdateContactError, UpdateGeneratedWebsiteError, Website,
+    models::{
+        automation_failure::{
+            AutomationArtifact, AutomationFailure, GetAutomationArtifactError,
+            ListAutomationFailuresError, RecordAutomationFailureError,
+            RecordAutomationFailureRequest,
+        },
+        business_profile::{BusinessProfile, UpdateBusinessProfileError},
+        event::{AppendEventError, EVENT_SCHEMA_VERSION, EventEnvelope, GetEventsError},
+        outbox::{OutboxEntry, OutboxError},
+        page::{GetPagesError, PageSnapshot, SavePagesError},
+        preview::{
+            CreatePreviewError, CreatePreviewRequest, GetPreviewError, ListPreviewsError, Preview,
+        },
+        prompt::{
+            CreatePromptVersionError, CreatePromptVersionRequest, GetPromptError, ListPromptsError,
+            PromptName, PromptTemplate,
+        },
+        quota::{GetMonthlyUsageError, MonthlyUsage, RecordGenerationError},
+        screenshot::{
+            GetScreenshotsError, SaveScreenshotError, SaveScreenshotRequest, Screenshot,
+            ScreenshotSubject, Viewport,
+        },
+        usage::{
+            GetUsageError, GetUsageRequest, LlmCallOutcome, LlmUsage, ModelUsage, RecordUsageError,
+            UsageReport, UsageTotals, WebsiteUsage,
+        },
+        webhook::{
+            CreateWebhookDeliveryRequest, CreateWebhookError, CreateWebhookRequest, DeliveryStatus,
+            GetWebhookDeliveryError, GetWebhookError, ListWebhooksError, Webhook, WebhookAttempt,
+            WebhookDelivery, WebhookDeliveryError,
+        },
+        website::{
+            Contact, ContactEvent, CorrectContactError, CrawlSummary, CreateWebsiteError,
+            CreateWebsiteRequest, DeleteWebsiteError, ExtractionMethod, GeneratedWebsite,
+            GeneratedWebsiteEvent, GetWebsiteError, GetWebsitesError, RenderMode, Social,
+            UpdateContactError, UpdateGeneratedWebsiteError, UpdateRenderModeError, Website,
+            WebsiteDeletedEvent, WebsiteEvent, WebsiteEventType,
+        },
+    },
+    ports::{
+        AutomationFailureRepository, EventRepository, OutboxRepository, PreviewRepository,
+        PromptRepository, ScreenshotRepository, UsageRepository, WebhookRepository,
+        WebsiteRepository,
     },
-    ports::WebsiteRepository,
 };
 
+/// Advisory lock held while appending to the event log, so events are committed in id order and
+/// readers following the log by id never skip one.
+const EVENT_LOG_LOCK: i64 = 0x6576656e7473;
+
+/// Advisory lock held from adding an entry to the outbox until the transaction commits, so entries
+/// are committed in id order.
+const OUTBOX_LOCK: i64 = 0x6f7574626f78;
+
+/// [BusinessProfile] is stored as JSONB.
+impl Type<sqlx::Postgres> for BusinessProfile {
+    fn type_info() -> PgTypeInfo {
+        <Json<Self> as Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <Json<Self> as Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> Decode<'r, sqlx::Postgres> for BusinessProfile {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(<Json<Self> as Decode<sqlx::Postgres>>::decode(value)?.0)
+    }
+}
+
+/// [Social] is stored as JSONB.
+impl Type<sqlx::Postgres> for Social {
+    fn type_info() -> PgTypeInfo {
+        <Json<Self> as Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <Json<Self> as Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> Decode<'r, sqlx::Postgres> for Social {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(<Json<Self> as Decode<sqlx::Postgres>>::decode(value)?.0)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Postgres {
     pool: PgPool,
@@ -25,6 +114,213 @@ impl Postgres {
 
         Ok(Self { pool })
     }
+
+    /// Stores the default template of every prompt that has no version yet.
+    pub async fn seed_prompts(&self) -> anyhow::Result<()> {
+        for name in PromptName::ALL {
+            sqlx::query!(
+                r#"INSERT INTO prompt_templates(name, version, template) SELECT $1, 1, $2
+                WHERE NOT EXISTS (SELECT 1 FROM prompt_templates WHERE name = $1)"#,
+                name as PromptName,
+                name.default_template()
+            )
+            .execute(&self.pool)
+            .await
+            .with_context(|| format!("failed to seed prompt {name}"))?;
+        }
+        Ok(())
+    }
+
+    /// Appends the event to the log and notifies `channel` with its id once committed. Appends
+    /// are serialized across instances, so events are committed and notified in id order.
+    pub async fn append_and_notify_event(
+        &self,
+        event: &WebsiteEvent,
+        channel: &str,
+    ) -> Result<EventEnvelope, AppendEventError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| AppendEventError::Unknown(e.into()))?;
+        sqlx::query!("SELECT pg_advisory_xact_lock($1)", EVENT_LOG_LOCK)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppendEventError::Unknown(e.into()))?;
+        let envelope = insert_event(&mut tx, event).await?;
+        sqlx::query!(
+            "SELECT pg_notify($1, $2)",
+            channel,
+            envelope.event_id.to_string()
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppendEventError::Unknown(e.into()))?;
+        tx.commit()
+            .await
+            .map_err(|e| AppendEventError::Unknown(e.into()))?;
+        Ok(envelope)
+    }
+
+    /// Notifies `channel` with an ephemeral event, in its envelope, without logging it.
+    pub async fn notify_ephemeral_event(
+        &self,
+        event: &WebsiteEvent,
+        channel: &str,
+    ) -> Result<EventEnvelope, AppendEventError> {
+        let envelope = wrap_event(&self.pool, event).await?;
+        let payload =
+            serde_json::to_string(&envelope).map_err(|e| AppendEventError::Unknown(e.into()))?;
+        sqlx::query!("SELECT pg_notify($1, $2)", channel, payload)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppendEventError::Unknown(e.into()))?;
+        Ok(envelope)
+    }
+
+    /// The id of the latest logged event, 0 if there is none.
+    pub async fn last_event_id(&self) -> Result<i64, GetEventsError> {
+        sqlx::query_scalar!(
+            r#"SELECT COALESCE(MAX(event_id), 0) as "event_id!" FROM website_events"#
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| GetEventsError::Unknown(e.into()))
+    }
+
+    pub async fn listen(&self, channels: &[&str]) -> Result<PgListener, sqlx::Error> {
+        let mut listener = PgListener::connect_with(&self.pool).await?;
+        listener.listen_all(channels.iter().copied()).await?;
+        Ok(listener)
+    }
+}
+
+/// Appends `event` to the log, along with the website it is about and the owner of the website.
+/// The owner of a deleted website is taken from its previous events.
+async fn insert_event(
+    connection: &mut PgConnection,
+    event: &WebsiteEvent,
+) -> Result<EventEnvelope, AppendEventError> {
+    let website_id = event.website_id();
+    let row = sqlx::query!(
+        r#"INSERT INTO website_events(schema_version, website_id, tenant, event)
+        VALUES ($1, $2, COALESCE(
+            (SELECT owner_id FROM websites WHERE website_id = $2),
+            (SELECT tenant FROM website_events WHERE website_id = $2 ORDER BY event_id DESC LIMIT 1)
+        ), $3)
+        RETURNING event_id, occurred_at, tenant"#,
+        EVENT_SCHEMA_VERSION as i32,
+        website_id,
+        Json(event) as _
+    )
+    .fetch_one(&mut *connection)
+    .await
+    .map_err(|e| AppendEventError::Unknown(e.into()))?;
+    Ok(EventEnvelope {
+        event_id: row.event_id,
+        schema_version: EVENT_SCHEMA_VERSION,
+        occurred_at: row.occurred_at,
+        website_id,
+        tenant: row.tenant,
+        event: event.clone(),
+    })
+}
+
+/// Puts an ephemeral `event` in an envelope without logging it, with the id of the latest logged
+/// event and the owner of the website, found as [insert_event] does.
+async fn wrap_event(
+    executor: impl PgExecutor<'_>,
+    event: &WebsiteEvent,
+) -> Result<EventEnvelope, AppendEventError> {
+    let website_id = event.website_id();
+    let row = sqlx::query!(
+        r#"SELECT COALESCE((SELECT MAX(event_id) FROM website_events), 0) as "event_id!",
+        NOW() as "occurred_at!",
+        COALESCE(
+            (SELECT owner_id FROM websites WHERE website_id = $1),
+            (SELECT tenant FROM website_events WHERE website_id = $1 ORDER BY event_id DESC LIMIT 1)
+        ) as tenant"#,
+        website_id
+    )
+    .fetch_one(executor)
+    .await
+    .map_err(|e| AppendEventError::Unknown(e.into()))?;
+    Ok(EventEnvelope {
+        event_id: row.event_id,
+        schema_version: EVENT_SCHEMA_VERSION,
+        occurred_at: row.occurred_at,
+        website_id,
+        tenant: row.tenant,
+        event: event.clone(),
+    })
+}
+
+/// Reads up to `limit` events logged after `last_event_id`, oldest first.
+async fn select_events_after(
+    executor: impl PgExecutor<'_>,
+    last_event_id: i64,
+    limit: i64,
+) -> Result<Vec<EventEnvelope>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"SELECT event_id, schema_version, occurred_at, website_id, tenant,
+        event as "event: Json<WebsiteEvent>" FROM website_events
+        WHERE event_id > $1 ORDER BY event_id LIMIT $2"#,
+        last_event_id,
+        limit
+    )
+    .fetch_all(executor)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| EventEnvelope {
+            event_id: row.event_id,
+            schema_version: row.schema_version as u32,
+            occurred_at: row.occurred_at,
+            website_id: row.website_id,
+            tenant: row.tenant,
+            event: row.event.0,
+        })
+        .collect())
+}
+
+/// Stores `contact` as the contact of the website, returning whether the website exists.
+async fn write_contact(
+    connection: &mut PgConnection,
+    website_id: i64,
+    contact: &Contact,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"UPDATE websites SET contact_email = $1, contact_name = $2, contact_phone = $3, contact_email_method = $4, contact_name_method = $5, contact_phone_method = $6, contact_prompt_version = $7, social_links = $8 WHERE website_id = $9"#,
+        contact.contact_email,
+        contact.contact_name,
+        contact.contact_phone,
+        contact.contact_email_method as Option<ExtractionMethod>,
+        contact.contact_name_method as Option<ExtractionMethod>,
+        contact.contact_phone_method as Option<ExtractionMethod>,
+        contact.prompt_version,
+        contact.social_links.as_ref().map(Json) as _,
+        website_id
+    )
+    .execute(connection)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Adds `event` to the outbox, to be published once the transaction commits.
+async fn insert_outbox_event(
+    connection: &mut PgConnection,
+    event: &WebsiteEvent,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!("SELECT pg_advisory_xact_lock($1)", OUTBOX_LOCK)
+        .execute(&mut *connection)
+        .await?;
+    sqlx::query!(
+        "INSERT INTO website_outbox(event) VALUES ($1)",
+        Json(event) as _
+    )
+    .execute(&mut *connection)
+    .await?;
+    Ok(())
 }
 
 impl WebsiteRepository for Postgres {
@@ -38,28 +334,38 @@ impl WebsiteRepository for Postgres {
             .await
             .map_err(CreateWebsiteError::FailedTransaction)?;
         let inserted_website = sqlx::query!(
-            r#"WITH inserted_website AS (INSERT INTO websites(source_address) VALUES ($1) RETURNING website_id, source_address)
-            SELECT inserted_website.website_id, inserted_website.source_address FROM inserted_website"#,
-            req.source_address.to_string()
+            r#"WITH inserted_website AS (INSERT INTO websites(source_address, owner_id) VALUES ($1, $2) RETURNING website_id, source_address, owner_id)
+            SELECT inserted_website.website_id, inserted_website.source_address, inserted_website.owner_id FROM inserted_website"#,
+            req.source_address.to_string(),
+            req.owner_id
         )
         .fetch_one(&mut *tx)
         .await
         .map_err(|e| CreateWebsiteError::Unknown(e.into()))?;
-        tx.commit()
-            .await
-            .map_err(|e| CreateWebsiteError::Unknown(e.into()))?;
         let website = Website::new(
             inserted_website.website_id,
             &inserted_website.source_address,
+            inserted_website.owner_id,
         );
+        insert_outbox_event(
+            &mut tx,
+            &WebsiteEvent::WebsiteAdded(Box::new(website.clone())),
+        )
+        .await
+        .map_err(|e| CreateWebsiteError::Unknown(e.into()))?;
+        tx.commit()
+            .await
+            .map_err(|e| CreateWebsiteError::Unknown(e.into()))?;
         Ok(website)
     }
 
-    async fn get_websites(&self) -> Result<Vec<Website>, GetWebsitesError> {
+    async fn get_websites(&self, owner_id: &str) -> Result<Vec<Website>, GetWebsitesError> {
         let websites = sqlx::query_as!(
             Website,
             r#"
-            SELECT website_id as id, source_address, contact_name, contact_email, generated_website_link, generated_website_name FROM websites"#
+            SELECT website_id as id, source_address, owner_id, contact_name, contact_email, contact_phone, contact_email_method as "contact_email_method: ExtractionMethod", contact_name_method as "contact_name_method: ExtractionMethod", contact_phone_method as "contact_phone_method: ExtractionMethod", contact_prompt_version, social_links as "social_links: Social", generated_website_link, generated_website_name, generated_website_prompt_version, render_mode as "render_mode: RenderMode", business_profile as "business_profile: BusinessProfile" FROM websites
+            WHERE owner_id = $1 ORDER BY created_at"#,
+            owner_id
         )
         .fetch_all(&self.pool)
         .await
@@ -67,6 +373,19 @@ impl WebsiteRepository for Postgres {
         Ok(websites)
     }
 
+    async fn get_website(&self, website_id: i64) -> Result<Website, GetWebsiteError> {
+        sqlx::query_as!(
+            Website,
+            r#"
+            SELECT website_id as id, source_address, owner_id, contact_name, contact_email, contact_phone, contact_email_method as "contact_email_method: ExtractionMethod", contact_name_method as "contact_name_method: ExtractionMethod", contact_phone_method as "contact_phone_method: ExtractionMethod", contact_prompt_version, social_links as "social_links: Social", generated_website_link, generated_website_name, generated_website_prompt_version, render_mode as "render_mode: RenderMode", business_profile as "business_profile: BusinessProfile" FROM websites WHERE website_id = $1"#,
+            website_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| GetWebsiteError::Unknown(e.into()))?
+        .ok_or(GetWebsiteError::NotFound { id: website_id })
+    }
+
     async fn update_generated_website(
         &self,
         website_id: i64,
@@ -79,15 +398,26 @@ impl WebsiteRepository for Postgres {
             .begin()
             .await
             .map_err(|e| UpdateGeneratedWebsiteError::Unknown(e.into()))?;
-        sqlx::query!(
-            r#"UPDATE websites SET generated_website_name = $1, generated_website_link = $2 WHERE website_id = $3"#,
+        let result = sqlx::query!(
+            r#"UPDATE websites SET generated_website_name = $1, generated_website_link = $2, generated_website_prompt_version = $3 WHERE website_id = $4"#,
             generated_website.name,
             generated_website.url.to_string(),
+            generated_website.prompt_version,
             website_id
         )
         .execute(&mut *tx)
         .await
         .map_err(|e| UpdateGeneratedWebsiteError::Unknown(e.into()))?;
+        if result.rows_affected() == 0 {
+            return Err(UpdateGeneratedWebsiteError::NotFound { id: website_id });
+        }
+        let event = WebsiteEvent::GeneratedWebsite(GeneratedWebsiteEvent {
+            website_id,
+            generated_website: generated_website.clone(),
+        });
+        insert_outbox_event(&mut tx, &event)
+            .await
+            .map_err(|e| UpdateGeneratedWebsiteError::Unknown(e.into()))?;
         tx.commit()
             .await
             .map_err(|e| UpdateGeneratedWebsiteError::Unknown(e.into()))?;
@@ -105,18 +435,924 @@ impl WebsiteRepository for Postgres {
             .begin()
             .await
             .map_err(UpdateContactError::FailedTransaction)?;
-        sqlx::query!(
-            r#"UPDATE websites SET contact_email = $1, contact_name = $2 WHERE website_id = $3"#,
-            contact.contact_email,
-            contact.contact_name,
+        if !write_contact(&mut tx, website_id, contact)
+            .await
+            .map_err(|e| UpdateContactError::Unknown(e.into()))?
+        {
+            return Err(UpdateContactError::NotFound { id: website_id });
+        }
+        let event = WebsiteEvent::FetchedContact(ContactEvent {
+            website_id,
+            contact: contact.clone(),
+        });
+        insert_outbox_event(&mut tx, &event)
+            .await
+            .map_err(|e| UpdateContactError::Unknown(e.into()))?;
+        tx.commit()
+            .await
+            .map_err(|e| UpdateContactError::Unknown(e.into()))?;
+        Ok(())
+    }
+
+    async fn correct_contact(
+        &self,
+        website_id: i64,
+        contact: &Contact,
+    ) -> Result<(), CorrectContactError> {
+        tracing::debug!("Correcting contact for {}", website_id);
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CorrectContactError::Unknown(e.into()))?;
+        if !write_contact(&mut tx, website_id, contact)
+            .await
+            .map_err(|e| CorrectContactError::Unknown(e.into()))?
+        {
+            return Err(CorrectContactError::NotFound { id: website_id });
+        }
+        let event = WebsiteEvent::ContactUpdated(ContactEvent {
+            website_id,
+            contact: contact.clone(),
+        });
+        insert_outbox_event(&mut tx, &event)
+            .await
+            .map_err(|e| CorrectContactError::Unknown(e.into()))?;
+        tx.commit()
+            .await
+            .map_err(|e| CorrectContactError::Unknown(e.into()))?;
+        Ok(())
+    }
+
+    async fn delete_website(&self, website_id: i64) -> Result<(), DeleteWebsiteError> {
+        tracing::debug!("Deleting website {}", website_id);
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DeleteWebsiteError::Unknown(e.into()))?;
+        // Pages, previews, screenshots and automation failures are deleted by cascade, the LLM
+        // usage and generations are kept for the quotas. The logged events are removed once the
+        // deletion is logged.
+        let result = sqlx::query!("DELETE FROM websites WHERE website_id = $1", website_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DeleteWebsiteError::Unknown(e.into()))?;
+        if result.rows_affected() == 0 {
+            return Err(DeleteWebsiteError::NotFound { id: website_id });
+        }
+        insert_outbox_event(
+            &mut tx,
+            &WebsiteEvent::WebsiteDeleted(WebsiteDeletedEvent { website_id }),
+        )
+        .await
+        .map_err(|e| DeleteWebsiteError::Unknown(e.into()))?;
+        tx.commit()
+            .await
+            .map_err(|e| DeleteWebsiteError::Unknown(e.into()))?;
+        Ok(())
+    }
+
+    async fn get_pages(&self, website_id: i64) -> Result<Vec<PageSnapshot>, GetPagesError> {
+        let rows = sqlx::query!(
+            r#"SELECT url, status, headers as "headers: Json<BTreeMap<String, String>>", body_hash, body, fetched_at, rendered FROM pages WHERE website_id = $1 ORDER BY page_id"#,
             website_id
         )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| GetPagesError::Unknown(e.into()))?;
+        rows.into_iter()
+            .map(|row| {
+                Ok(PageSnapshot {
+                    url: Url::parse(&row.url)
+                        .with_context(|| format!("invalid stored page url {}", row.url))?,
+                    status: row.status as u16,
+                    headers: row.headers.0,
+                    body_hash: row.body_hash,
+                    body: row.body,
+                    fetched_at: row.fetched_at,
+                    rendered: row.rendered,
+                })
+            })
+            .collect()
+    }
+
+    async fn get_crawl_summary(&self, website_id: i64) -> Result<CrawlSummary, GetPagesError> {
+        sqlx::query_as!(
+            CrawlSummary,
+            r#"SELECT COUNT(*) as "pages!", COUNT(*) FILTER (WHERE rendered) as "rendered_pages!", MAX(fetched_at) as last_fetched_at
+            FROM pages WHERE website_id = $1"#,
+            website_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| GetPagesError::Unknown(e.into()))
+    }
+
+    async fn save_pages(
+        &self,
+        website_id: i64,
+        pages: &[PageSnapshot],
+    ) -> Result<(), SavePagesError> {
+        tracing::debug!("Saving {} pages for {}", pages.len(), website_id);
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(SavePagesError::FailedTransaction)?;
+        // Pages the crawl no longer found are dropped, so the snapshot matches the site.
+        let urls: Vec<String> = pages.iter().map(|page| page.url.to_string()).collect();
+        sqlx::query!(
+            "DELETE FROM pages WHERE website_id = $1 AND url <> ALL($2)",
+            website_id,
+            &urls
+        )
         .execute(&mut *tx)
         .await
-        .map_err(|e| UpdateContactError::Unknown(e.into()))?;
+        .map_err(|e| SavePagesError::Unknown(e.into()))?;
+        for page in pages {
+            sqlx::query!(
+                r#"INSERT INTO pages(website_id, url, status, headers, body_hash, body, fetched_at, rendered) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                ON CONFLICT (website_id, url) DO UPDATE SET status = EXCLUDED.status, headers = EXCLUDED.headers, body_hash = EXCLUDED.body_hash, body = EXCLUDED.body, fetched_at = EXCLUDED.fetched_at, rendered = EXCLUDED.rendered"#,
+                website_id,
+                page.url.to_string(),
+                page.status as i16,
+                Json(&page.headers) as _,
+                page.body_hash,
+                page.body,
+                page.fetched_at,
+                page.rendered
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| SavePagesError::Unknown(e.into()))?;
+        }
         tx.commit()
             .await
-            .map_err(|e| UpdateContactError::Unknown(e.into()))?;
+            .map_err(|e| SavePagesError::Unknown(e.into()))?;
+        Ok(())
+    }
+
+    async fn update_business_profile(
+        &self,
+        website_id: i64,
+        business_profile: &BusinessProfile,
+    ) -> Result<(), UpdateBusinessProfileError> {
+        tracing::debug!("Updating business profile for {}", website_id);
+        let result = sqlx::query!(
+            r#"UPDATE websites SET business_profile = $1 WHERE website_id = $2"#,
+            Json(business_profile) as _,
+            website_id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| UpdateBusinessProfileError::Unknown(e.into()))?;
+        if result.rows_affected() == 0 {
+            return Err(UpdateBusinessProfileError::NotFound { id: website_id });
+        }
+        Ok(())
+    }
+
+    async fn update_render_mode(
+        &self,
+        website_id: i64,
+        render_mode: RenderMode,
+    ) -> Result<(), UpdateRenderModeError> {
+        tracing::debug!("Updating render mode for {}", website_id);
+        sqlx::query!(
+            r#"UPDATE websites SET render_mode = $1 WHERE website_id = $2"#,
+            render_mode as RenderMode,
+            website_id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| UpdateRenderModeError::Unknown(e.into()))?;
+        Ok(())
+    }
+}
+
+impl UsageRepository for Postgres {
+    async fn record_llm_usage(&self, usage: &LlmUsage) -> Result<(), RecordUsageError> {
+        sqlx::query!(
+            r#"INSERT INTO llm_usage(website_id, owner_id, operation, provider, model, prompt_tokens, completion_tokens, latency_ms, estimated_cost_usd, outcome) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)"#,
+            usage.website_id,
+            usage.owner_id,
+            usage.operation,
+            usage.provider,
+            usage.model,
+            usage.prompt_tokens,
+            usage.completion_tokens,
+            usage.latency_ms,
+            usage.estimated_cost_usd,
+            usage.outcome as LlmCallOutcome
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RecordUsageError::Unknown(e.into()))?;
+        Ok(())
+    }
+
+    async fn get_llm_usage(&self, req: &GetUsageRequest) -> Result<UsageReport, GetUsageError> {
+        let total = sqlx::query_as!(
+            UsageTotals,
+            r#"SELECT COUNT(*) as "calls!", COUNT(*) FILTER (WHERE outcome <> 'success') as "failed_calls!", COALESCE(SUM(prompt_tokens), 0)::BIGINT as "prompt_tokens!", COALESCE(SUM(completion_tokens), 0)::BIGINT as "completion_tokens!", COALESCE(SUM(estimated_cost_usd), 0)::DOUBLE PRECISION as "estimated_cost_usd!"
+            FROM llm_usage WHERE owner_id = $1 AND created_at >= $2 AND created_at < $3"#,
+            req.owner_id,
+            req.from,
+            req.to
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| GetUsageError::Unknown(e.into()))?;
+
+        let websites = sqlx::query!(
+            r#"SELECT u.website_id as "website_id!", w.source_address, COUNT(*) as "calls!", COUNT(*) FILTER (WHERE u.outcome <> 'success') as "failed_calls!", COALESCE(SUM(u.prompt_tokens), 0)::BIGINT as "prompt_tokens!", COALESCE(SUM(u.completion_tokens), 0)::BIGINT as "completion_tokens!", COALESCE(SUM(u.estimated_cost_usd), 0)::DOUBLE PRECISION as "estimated_cost_usd!"
+            FROM llm_usage u JOIN websites w ON w.website_id = u.website_id
+            WHERE u.owner_id = $1 AND u.created_at >= $2 AND u.created_at < $3
+            GROUP BY u.website_id, w.source_address ORDER BY 7 DESC, u.website_id"#,
+            req.owner_id,
+            req.from,
+            req.to
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| GetUsageError::Unknown(e.into()))?
+        .into_iter()
+        .map(|row| WebsiteUsage {
+            website_id: row.website_id,
+            source_address: row.source_address,
+            totals: UsageTotals {
+                calls: row.calls,
+                failed_calls: row.failed_calls,
+                prompt_tokens: row.prompt_tokens,
+                completion_tokens: row.completion_tokens,
+                estimated_cost_usd: row.estimated_cost_usd,
+            },
+        })
+        .collect();
+
+        let models = sqlx::query!(
+            r#"SELECT provider, model, COUNT(*) as "calls!", COUNT(*) FILTER (WHERE outcome <> 'success') as "failed_calls!", COALESCE(SUM(prompt_tokens), 0)::BIGINT as "prompt_tokens!", COALESCE(SUM(completion_tokens), 0)::BIGINT as "completion_tokens!", COALESCE(SUM(estimated_cost_usd), 0)::DOUBLE PRECISION as "estimated_cost_usd!"
+            FROM llm_usage WHERE owner_id = $1 AND created_at >= $2 AND created_at < $3
+            GROUP BY provider, model ORDER BY provider, model"#,
+            req.owner_id,
+            req.from,
+            req.to
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| GetUsageError::Unknown(e.into()))?
+        .into_iter()
+        .map(|row| ModelUsage {
+            provider: row.provider,
+            model: row.model,
+            totals: UsageTotals {
+                calls: row.calls,
+                failed_calls: row.failed_calls,
+                prompt_tokens: row.prompt_tokens,
+                completion_tokens: row.completion_tokens,
+                estimated_cost_usd: row.estimated_cost_usd,
+            },
+        })
+        .collect();
+
+        Ok(UsageReport {
+            total,
+            websites,
+            models,
+        })
+    }
+
+    async fn record_generation(&self, website: &Website) -> Result<(), RecordGenerationError> {
+        sqlx::query!(
+            r#"INSERT INTO generations(website_id, owner_id) VALUES ($1, $2)"#,
+            website.id,
+            website.owner_id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RecordGenerationError::Unknown(e.into()))?;
         Ok(())
     }
+
+    async fn get_monthly_usage(
+        &self,
+        owner_id: &str,
+        since: DateTime<Utc>,
+    ) -> Result<MonthlyUsage, GetMonthlyUsageError> {
+        sqlx::query_as!(
+            MonthlyUsage,
+            r#"SELECT
+            (SELECT COALESCE(SUM(estimated_cost_usd), 0)::DOUBLE PRECISION FROM llm_usage WHERE owner_id = $1 AND created_at >= $2) as "llm_usd!",
+            (SELECT COUNT(*) FROM generations WHERE owner_id = $1 AND created_at >= $2) as "generations!",
+            (SELECT COUNT(*) FROM websites WHERE owner_id = $1 AND created_at >= $2) as "websites!""#,
+            owner_id,
+            since
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| GetMonthlyUsageError::Unknown(e.into()))
+    }
+}
+
+impl PromptRepository for Postgres {
+    async fn get_prompt(&self, name: PromptName) -> Result<PromptTemplate, GetPromptError> {
+        sqlx::query_as!(
+            PromptTemplate,
+            r#"SELECT name as "name: PromptName", version, template, created_by, created_at FROM prompt_templates WHERE name = $1 ORDER BY version DESC LIMIT 1"#,
+            name as PromptName
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| GetPromptError::Unknown(e.into()))?
+        .ok_or(GetPromptError::NotFound { name })
+    }
+
+    async fn list_prompts(&self) -> Result<Vec<PromptTemplate>, ListPromptsError> {
+        sqlx::query_as!(
+            PromptTemplate,
+            r#"SELECT DISTINCT ON (name) name as "name: PromptName", version, template, created_by, created_at FROM prompt_templates ORDER BY name, version DESC"#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ListPromptsError::Unknown(e.into()))
+    }
+
+    async fn get_prompt_versions(
+        &self,
+        name: PromptName,
+    ) -> Result<Vec<PromptTemplate>, GetPromptError> {
+        let versions = sqlx::query_as!(
+            PromptTemplate,
+            r#"SELECT name as "name: PromptName", version, template, created_by, created_at FROM prompt_templates WHERE name = $1 ORDER BY version DESC"#,
+            name as PromptName
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| GetPromptError::Unknown(e.into()))?;
+        if versions.is_empty() {
+            return Err(GetPromptError::NotFound { name });
+        }
+        Ok(versions)
+    }
+
+    async fn create_prompt_version(
+        &self,
+        req: &CreatePromptVersionRequest,
+    ) -> Result<PromptTemplate, CreatePromptVersionError> {
+        sqlx::query_as!(
+            PromptTemplate,
+            r#"INSERT INTO prompt_templates(name, version, template, created_by)
+            SELECT $1, COALESCE(MAX(version), 0) + 1, $2, $3 FROM prompt_templates WHERE name = $1
+            RETURNING name as "name: PromptName", version, template, created_by, created_at"#,
+            req.name as PromptName,
+            req.template,
+            req.created_by
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| CreatePromptVersionError::Unknown(e.into()))
+    }
+}
+
+impl PreviewRepository for Postgres {
+    async fn create_preview(
+        &self,
+        req: &CreatePreviewRequest,
+    ) -> Result<Preview, CreatePreviewError> {
+        sqlx::query_as!(
+            Preview,
+            r#"INSERT INTO previews(slug, website_id, expires_at) VALUES ($1, $2, $3)
+            ON CONFLICT (slug) DO UPDATE SET website_id = EXCLUDED.website_id, created_at = NOW(), expires_at = EXCLUDED.expires_at
+            RETURNING slug, website_id, created_at, expires_at"#,
+            req.slug,
+            req.website_id,
+            req.expires_at
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| CreatePreviewError::Unknown(e.into()))
+    }
+
+    async fn get_preview(&self, slug: &str) -> Result<Preview, GetPreviewError> {
+        sqlx::query_as!(
+            Preview,
+            r#"SELECT slug, website_id, created_at, expires_at FROM previews WHERE slug = $1"#,
+            slug
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| GetPreviewError::Unknown(e.into()))?
+        .ok_or_else(|| GetPreviewError::NotFound {
+            slug: slug.to_string(),
+        })
+    }
+
+    async fn list_previews(&self, website_id: i64) -> Result<Vec<Preview>, ListPreviewsError> {
+        sqlx::query_as!(
+            Preview,
+            r#"SELECT slug, website_id, created_at, expires_at FROM previews WHERE website_id = $1 ORDER BY created_at"#,
+            website_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ListPreviewsError::Unknown(e.into()))
+    }
+}
+
+impl AutomationFailureRepository for Postgres {
+    async fn record_automation_failure(
+        &self,
+        req: &RecordAutomationFailureRequest,
+    ) -> Result<AutomationFailure, RecordAutomationFailureError> {
+        sqlx::query_as!(
+            AutomationFailure,
+            r#"INSERT INTO automation_failures(website_id, run_id, error, url, page_source, screenshot)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING automation_failure_id as id, website_id, run_id, error, url,
+            page_source IS NOT NULL as "has_page_source!", screenshot IS NOT NULL as "has_screenshot!", created_at"#,
+            req.website_id,
+            req.run_id,
+            req.error,
+            req.url,
+            req.page_source,
+            req.screenshot
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RecordAutomationFailureError::Unknown(e.into()))
+    }
+
+    async fn list_automation_failures(
+        &self,
+        website_id: i64,
+    ) -> Result<Vec<AutomationFailure>, ListAutomationFailuresError> {
+        sqlx::query_as!(
+            AutomationFailure,
+            r#"SELECT automation_failure_id as id, website_id, run_id, error, url,
+            page_source IS NOT NULL as "has_page_source!", screenshot IS NOT NULL as "has_screenshot!", created_at
+            FROM automation_failures WHERE website_id = $1 ORDER BY created_at DESC"#,
+            website_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ListAutomationFailuresError::Unknown(e.into()))
+    }
+
+    async fn get_automation_artifact(
+        &self,
+        id: i64,
+        artifact: AutomationArtifact,
+    ) -> Result<Vec<u8>, GetAutomationArtifactError> {
+        let row = sqlx::query!(
+            r#"SELECT page_source, screenshot FROM automation_failures WHERE automation_failure_id = $1"#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| GetAutomationArtifactError::Unknown(e.into()))?;
+        let content = row.and_then(|row| match artifact {
+            AutomationArtifact::Screenshot => row.screenshot,
+            AutomationArtifact::PageSource => row.page_source.map(String::into_bytes),
+        });
+        content.ok_or(GetAutomationArtifactError::NotFound { id, artifact })
+    }
+}
+
+impl ScreenshotRepository for Postgres {
+    async fn save_screenshot(
+        &self,
+        req: &SaveScreenshotRequest,
+    ) -> Result<Screenshot, SaveScreenshotError> {
+        sqlx::query_as!(
+            Screenshot,
+            r#"INSERT INTO screenshots(website_id, subject, viewport, url, image_key, thumbnail_key)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (website_id, subject, viewport) DO UPDATE SET url = EXCLUDED.url, image_key = EXCLUDED.image_key,
+            thumbnail_key = EXCLUDED.thumbnail_key, captured_at = NOW()
+            RETURNING website_id, subject as "subject: ScreenshotSubject", viewport as "viewport: Viewport", url, image_key, thumbnail_key, captured_at"#,
+            req.website_id,
+            req.subject as ScreenshotSubject,
+            req.viewport as Viewport,
+            req.url,
+            req.image_key,
+            req.thumbnail_key
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| SaveScreenshotError::Unknown(e.into()))
+    }
+
+    async fn get_screenshots(
+        &self,
+        website_ids: &[i64],
+    ) -> Result<Vec<Screenshot>, GetScreenshotsError> {
+        sqlx::query_as!(
+            Screenshot,
+            r#"SELECT website_id, subject as "subject: ScreenshotSubject", viewport as "viewport: Viewport", url, image_key, thumbnail_key, captured_at
+            FROM screenshots WHERE website_id = ANY($1) ORDER BY website_id, subject, viewport"#,
+            website_ids
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| GetScreenshotsError::Unknown(e.into()))
+    }
+}
+
+impl EventRepository for Postgres {
+    async fn append_event(&self, event: &WebsiteEvent) -> Result<EventEnvelope, AppendEventError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| AppendEventError::Unknown(e.into()))?;
+        sqlx::query!("SELECT pg_advisory_xact_lock($1)", EVENT_LOG_LOCK)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppendEventError::Unknown(e.into()))?;
+        let envelope = insert_event(&mut tx, event).await?;
+        tx.commit()
+            .await
+            .map_err(|e| AppendEventError::Unknown(e.into()))?;
+        Ok(envelope)
+    }
+
+    async fn get_events_after(
+        &self,
+        last_event_id: i64,
+        limit: i64,
+    ) -> Result<Vec<EventEnvelope>, GetEventsError> {
+        select_events_after(&self.pool, last_event_id, limit)
+            .await
+            .map_err(|e| GetEventsError::Unknown(e.into()))
+    }
+
+    async fn wrap_ephemeral_event(
+        &self,
+        event: &WebsiteEvent,
+    ) -> Result<EventEnvelope, AppendEventError> {
+        wrap_event(&self.pool, event).await
+    }
+
+    async fn get_tenant_events_after(
+        &self,
+        tenant: &str,
+        last_event_id: i64,
+        limit: i64,
+    ) -> Result<Vec<EventEnvelope>, GetEventsError> {
+        let rows = sqlx::query!(
+            r#"SELECT event_id, schema_version, occurred_at, website_id, tenant,
+            event as "event: Json<WebsiteEvent>" FROM website_events
+            WHERE tenant = $1 AND event_id > $2 AND (event->>'type' = 'WebsiteDeleted'
+                OR EXISTS (SELECT 1 FROM websites WHERE websites.website_id = website_events.website_id))
+            ORDER BY event_id LIMIT $3"#,
+            tenant,
+            last_event_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| GetEventsError::Unknown(e.into()))?;
+        Ok(rows
+            .into_iter()
+            .map(|row| EventEnvelope {
+                event_id: row.event_id,
+                schema_version: row.schema_version as u32,
+                occurred_at: row.occurred_at,
+                website_id: row.website_id,
+                tenant: row.tenant,
+                event: row.event.0,
+            })
+            .collect())
+    }
+
+    async fn get_last_event(
+        &self,
+        website_id: i64,
+        event_types: &[WebsiteEventType],
+    ) -> Result<Option<EventEnvelope>, GetEventsError> {
+        // Matched against the `type` tag of the stored events.
+        let tags = event_types
+            .iter()
+            .map(|event_type| {
+                serde_json::to_value(event_type)?
+                    .as_str()
+                    .map(str::to_string)
+                    .context("event types are serialized as strings")
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let row = sqlx::query!(
+            r#"SELECT event_id, schema_version, occurred_at, website_id, tenant,
+            event as "event: Json<WebsiteEvent>" FROM website_events
+            WHERE website_id = $1 AND event->>'type' = ANY($2) ORDER BY event_id DESC LIMIT 1"#,
+            website_id,
+            &tags
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| GetEventsError::Unknown(e.into()))?;
+        Ok(row.map(|row| EventEnvelope {
+            event_id: row.event_id,
+            schema_version: row.schema_version as u32,
+            occurred_at: row.occurred_at,
+            website_id: row.website_id,
+            tenant: row.tenant,
+            event: row.event.0,
+        }))
+    }
+}
+
+struct WebhookRow {
+    id: i64,
+    tenant: String,
+    url: String,
+    event_types: Vec<WebsiteEventType>,
+    secret: String,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<WebhookRow> for Webhook {
+    type Error = anyhow::Error;
+
+    fn try_from(row: WebhookRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            url: Url::parse(&row.url)
+                .with_context(|| format!("invalid stored webhook url {}", row.url))?,
+            id: row.id,
+            tenant: row.tenant,
+            event_types: row.event_types,
+            secret: row.secret,
+            created_at: row.created_at,
+        })
+    }
+}
+
+impl OutboxRepository for Postgres {
+    async fn claim_outbox(
+        &self,
+        relay_id: Uuid,
+        lease: Duration,
+        limit: i64,
+    ) -> Result<Vec<OutboxEntry>, OutboxError> {
+        let rows = sqlx::query!(
+            r#"WITH lease AS (
+                UPDATE outbox_relay SET relay_id = $1, lease_until = NOW() + make_interval(secs => $2)
+                WHERE relay_id = $1 OR lease_until < NOW() RETURNING relay_id)
+            SELECT outbox_id, event as "event: Json<WebsiteEvent>", created_at FROM website_outbox
+            WHERE EXISTS (SELECT 1 FROM lease) ORDER BY outbox_id LIMIT $3"#,
+            relay_id,
+            lease.as_secs_f64(),
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| OutboxError::Unknown(e.into()))?;
+        Ok(rows
+            .into_iter()
+            .map(|row| OutboxEntry {
+                id: row.outbox_id,
+                event: row.event.0,
+                created_at: row.created_at,
+            })
+            .collect())
+    }
+
+    async fn delete_outbox_entry(&self, outbox_id: i64) -> Result<(), OutboxError> {
+        sqlx::query!("DELETE FROM website_outbox WHERE outbox_id = $1", outbox_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| OutboxError::Unknown(e.into()))?;
+        Ok(())
+    }
+}
+
+impl WebhookRepository for Postgres {
+    async fn create_webhook(
+        &self,
+        req: &CreateWebhookRequest,
+    ) -> Result<Webhook, CreateWebhookError> {
+        let row = sqlx::query_as!(
+            WebhookRow,
+            r#"INSERT INTO webhooks(tenant, url, event_types, secret) VALUES ($1, $2, $3, $4)
+            RETURNING webhook_id as id, tenant, url, event_types as "event_types: Vec<WebsiteEventType>", secret, created_at"#,
+            req.tenant,
+            req.url.as_str(),
+            &req.event_types as &[WebsiteEventType],
+            req.secret
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| CreateWebhookError::Unknown(e.into()))?;
+        Ok(row.try_into()?)
+    }
+
+    async fn list_webhooks(&self, tenant: &str) -> Result<Vec<Webhook>, ListWebhooksError> {
+        let rows = sqlx::query_as!(
+            WebhookRow,
+            r#"SELECT webhook_id as id, tenant, url, event_types as "event_types: Vec<WebsiteEventType>", secret, created_at
+            FROM webhooks WHERE tenant = $1 ORDER BY webhook_id"#,
+            tenant
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ListWebhooksError::Unknown(e.into()))?;
+        Ok(rows
+            .into_iter()
+            .map(Webhook::try_from)
+            .collect::<Result<_, _>>()?)
+    }
+
+    async fn get_webhook(&self, tenant: &str, webhook_id: i64) -> Result<Webhook, GetWebhookError> {
+        let row = sqlx::query_as!(
+            WebhookRow,
+            r#"SELECT webhook_id as id, tenant, url, event_types as "event_types: Vec<WebsiteEventType>", secret, created_at
+            FROM webhooks WHERE tenant = $1 AND webhook_id = $2"#,
+            tenant,
+            webhook_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| GetWebhookError::Unknown(e.into()))?
+        .ok_or(GetWebhookError::NotFound { id: webhook_id })?;
+        Ok(row.try_into()?)
+    }
+
+    async fn delete_webhook(&self, tenant: &str, webhook_id: i64) -> Result<(), GetWebhookError> {
+        let result = sqlx::query!(
+            "DELETE FROM webhooks WHERE tenant = $1 AND webhook_id = $2",
+            tenant,
+            webhook_id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| GetWebhookError::Unknown(e.into()))?;
+        if result.rows_affected() == 0 {
+            return Err(GetWebhookError::NotFound { id: webhook_id });
+        }
+        Ok(())
+    }
+
+    async fn list_webhook_deliveries(
+        &self,
+        webhook_id: i64,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, WebhookDeliveryError> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"SELECT delivery_id as id, webhook_id, event_id, replay_of, payload, status as "status: DeliveryStatus",
+            attempts, next_attempt_at, last_response_status, last_error, created_at, delivered_at
+            FROM webhook_deliveries WHERE webhook_id = $1 ORDER BY delivery_id DESC LIMIT $2"#,
+            webhook_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| WebhookDeliveryError::Unknown(e.into()))
+    }
+
+    async fn get_webhook_delivery(
+        &self,
+        webhook_id: i64,
+        delivery_id: i64,
+    ) -> Result<WebhookDelivery, GetWebhookDeliveryError> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"SELECT delivery_id as id, webhook_id, event_id, replay_of, payload, status as "status: DeliveryStatus",
+            attempts, next_attempt_at, last_response_status, last_error, created_at, delivered_at
+            FROM webhook_deliveries WHERE webhook_id = $1 AND delivery_id = $2"#,
+            webhook_id,
+            delivery_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| GetWebhookDeliveryError::Unknown(e.into()))?
+        .ok_or(GetWebhookDeliveryError::NotFound { id: delivery_id })
+    }
+
+    async fn create_webhook_delivery(
+        &self,
+        req: &CreateWebhookDeliveryRequest,
+    ) -> Result<WebhookDelivery, WebhookDeliveryError> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"INSERT INTO webhook_deliveries(webhook_id, event_id, replay_of, payload) VALUES ($1, $2, $3, $4)
+            RETURNING delivery_id as id, webhook_id, event_id, replay_of, payload, status as "status: DeliveryStatus",
+            attempts, next_attempt_at, last_response_status, last_error, created_at, delivered_at"#,
+            req.webhook_id,
+            req.event_id,
+            req.replay_of,
+            req.payload
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| WebhookDeliveryError::Unknown(e.into()))
+    }
+
+    async fn dispatch_webhook_events(&self, limit: i64) -> Result<usize, WebhookDeliveryError> {
+        let dispatched = async {
+            let mut tx = self.pool.begin().await?;
+            // Locked until committed, so instances dispatch the events one after the other.
+            let last_event_id =
+                sqlx::query_scalar!("SELECT last_event_id FROM webhook_dispatch FOR UPDATE")
+                    .fetch_one(&mut *tx)
+                    .await?;
+            let events = select_events_after(&mut *tx, last_event_id, limit).await?;
+            let Some(last) = events.last() else {
+                return Ok(0);
+            };
+            for event in &events {
+                let Some(tenant) = &event.tenant else {
+                    continue;
+                };
+                sqlx::query!(
+                    r#"INSERT INTO webhook_deliveries(webhook_id, event_id, payload, next_attempt_at)
+                    SELECT webhook_id, $1, $2, NOW() FROM webhooks WHERE tenant = $3 AND $4 = ANY(event_types)"#,
+                    event.event_id,
+                    serde_json::to_string(event)?,
+                    tenant,
+                    event.event.event_type() as WebsiteEventType
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+            sqlx::query!(
+                "UPDATE webhook_dispatch SET last_event_id = $1",
+                last.event_id
+            )
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await?;
+            anyhow::Ok(events.len())
+        };
+        Ok(dispatched.await?)
+    }
+
+    async fn claim_webhook_deliveries(
+        &self,
+        limit: i64,
+        lease: Duration,
+    ) -> Result<Vec<(Webhook, WebhookDelivery)>, WebhookDeliveryError> {
+        let deliveries = sqlx::query_as!(
+            WebhookDelivery,
+            r#"UPDATE webhook_deliveries SET next_attempt_at = NOW() + make_interval(secs => $2)
+            WHERE delivery_id IN (SELECT delivery_id FROM webhook_deliveries WHERE status = 'pending' AND next_attempt_at <= NOW()
+                ORDER BY next_attempt_at LIMIT $1 FOR UPDATE SKIP LOCKED)
+            RETURNING delivery_id as id, webhook_id, event_id, replay_of, payload, status as "status: DeliveryStatus",
+            attempts, next_attempt_at, last_response_status, last_error, created_at, delivered_at"#,
+            limit,
+            lease.as_secs_f64()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| WebhookDeliveryError::Unknown(e.into()))?;
+        let webhook_ids: Vec<i64> = deliveries
+            .iter()
+            .map(|delivery| delivery.webhook_id)
+            .collect();
+        let webhooks = sqlx::query_as!(
+            WebhookRow,
+            r#"SELECT webhook_id as id, tenant, url, event_types as "event_types: Vec<WebsiteEventType>", secret, created_at
+            FROM webhooks WHERE webhook_id = ANY($1)"#,
+            &webhook_ids
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| WebhookDeliveryError::Unknown(e.into()))?
+        .into_iter()
+        .map(Webhook::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+        Ok(deliveries
+            .into_iter()
+            .filter_map(|delivery| {
+                let webhook = webhooks
+                    .iter()
+                    .find(|webhook| webhook.id == delivery.webhook_id)?;
+                Some((webhook.clone(), delivery))
+            })
+            .collect())
+    }
+
+    async fn record_webhook_attempt(
+        &self,
+        delivery_id: i64,
+        attempt: &WebhookAttempt,
+    ) -> Result<WebhookDelivery, WebhookDeliveryError> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"UPDATE webhook_deliveries SET status = $2, attempts = attempts + 1, next_attempt_at = $3,
+            last_response_status = $4, last_error = $5,
+            delivered_at = CASE WHEN $2 = 'succeeded'::webhook_delivery_status THEN NOW() END
+            WHERE delivery_id = $1
+            RETURNING delivery_id as id, webhook_id, event_id, replay_of, payload, status as "status: DeliveryStatus",
+            attempts, next_attempt_at, last_response_status, last_error, created_at, delivered_at"#,
+            delivery_id,
+            attempt.status as DeliveryStatus,
+            attempt.next_attempt_at,
+            attempt.response_status.map(i32::from),
+            attempt.error
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| WebhookDeliveryError::Unknown(e.into()))
+    }
 }
@@ -0,0 +1,643 @@
+/*!
+   Module `rule_based` extracts contact details without an LLM, from the places where most
+   websites put them anyway: `mailto:`/`tel:` links, schema.org JSON-LD and microdata, the
+   impressum and (possibly obfuscated) addresses in the page text. Business profiles are read
+   from JSON-LD and OpenGraph in the same way.
+*/
+
+mod business_profile;
+
+use std::{collections::BTreeSet, sync::LazyLock};
+
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+use serde_json::{Map, Value};
+
+use crate::domain::website::{
+    models::{
+        business_profile::{BusinessProfile, ExtractBusinessProfileError},
+        page::{CrawledWebsite, PageSnapshot},
+        website::{Contact, ExtractContactError, ExtractionMethod, Website},
+    },
+    ports::{BusinessProfileExtractor, ContactExtractor},
+};
+
+static EMAIL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b([a-z0-9._%+-]+)\s*(?:@|\[at\]|\(at\)|\{at\})\s*([a-z0-9-]+(?:\s*(?:\.|\[dot\]|\(dot\)|\{dot\})\s*[a-z0-9-]+)*\s*(?:\.|\[dot\]|\(dot\)|\{dot\})\s*[a-z]{2,24})\b",
+    )
+    .unwrap()
+});
+static DOT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\s*(?:\[dot\]|\(dot\)|\{dot\}|\.)\s*").unwrap());
+static PHONE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\+?\(?\d[\d\s()/.-]{5,}\d").unwrap());
+
+/// Labels introducing the owner's name in an impressum.
+const OWNER_LABELS: [&str; 9] = [
+    "inhaber",
+    "inhaberin",
+    "geschäftsführer",
+    "geschäftsführerin",
+    "vertreten durch",
+    "vertretungsberechtigt",
+    "owner",
+    "managing director",
+    "titolare",
+];
+/// Labels introducing a phone number in an impressum.
+const PHONE_LABELS: [&str; 5] = ["telefon", "tel.", "tel", "phone", "telefono"];
+/// Url path fragments identifying an impressum or legal notice page.
+const IMPRESSUM_PATHS: [&str; 5] = ["impressum", "imprint", "legal", "note-legali", "mentions"];
+/// File extensions that look like an email domain but come from asset names like `logo@2x.png`.
+const ASSET_EXTENSIONS: [&str; 6] = ["png", "jpg", "jpeg", "gif", "svg", "webp"];
+
+/// A value found on the site together with the rule that found it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Candidate {
+    value: String,
+    method: ExtractionMethod,
+}
+
+#[derive(Debug, Default)]
+struct Candidates {
+    emails: Vec<Candidate>,
+    names: Vec<Candidate>,
+    /// Business names, only used when no person could be found.
+    organization_names: Vec<Candidate>,
+    phones: Vec<Candidate>,
+}
+
+impl Candidates {
+    fn email(&mut self, value: &str, method: ExtractionMethod) {
+        let value = value.trim().trim_start_matches("mailto:");
+        let value = value.split('?').next().unwrap_or_default().to_lowercase();
+        let is_asset = value
+            .rsplit('.')
+            .next()
+            .is_some_and(|extension| ASSET_EXTENSIONS.contains(&extension));
+        if value.contains('@') && !is_asset {
+            self.emails.push(Candidate { value, method });
+        }
+    }
+
+    fn name(&mut self, value: &str, method: ExtractionMethod) {
+        if let Some(candidate) = name_candidate(value, method) {
+            self.names.push(candidate);
+        }
+    }
+
+    fn organization_name(&mut self, value: &str, method: ExtractionMethod) {
+        if let Some(candidate) = name_candidate(value, method) {
+            self.organization_names.push(candidate);
+        }
+    }
+
+    fn phone(&mut self, value: &str, method: ExtractionMethod) {
+        let value = value.trim().trim_start_matches("tel:").trim();
+        if value.chars().filter(char::is_ascii_digit).count() >= 6 {
+            self.phones.push(Candidate {
+                value: value.to_string(),
+                method,
+            });
+        }
+    }
+}
+
+fn name_candidate(value: &str, method: ExtractionMethod) -> Option<Candidate> {
+    let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!value.is_empty() && value.len() <= 100).then_some(Candidate { value, method })
+}
+
+/// Runs the extraction rules first and asks the `fallback` extractor only for the fields the rules
+/// could not determine unambiguously.
+#[derive(Clone)]
+pub struct RuleBasedExtractor<E> {
+    fallback: E,
+}
+
+impl<E> RuleBasedExtractor<E> {
+    pub fn new(fallback: E) -> Self {
+        Self { fallback }
+    }
+}
+
+/// The text a visitor would see, one line per text node, skipping scripts and styles.
+fn visible_lines(document: &Html) -> Vec<String> {
+    document
+        .root_element()
+        .descendants()
+        .filter(|node| {
+            !node.ancestors().any(|ancestor| {
+                ancestor.value().as_element().is_some_and(|element| {
+                    matches!(element.name(), "script" | "style" | "noscript" | "template")
+                })
+            })
+        })
+        .filter_map(|node| node.value().as_text())
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+        .collect()
+}
+
+fn is_impressum(page: &PageSnapshot) -> bool {
+    let path = page.url.path().to_lowercase();
+    IMPRESSUM_PATHS
+        .iter()
+        .any(|fragment| path.contains(fragment))
+}
+
+/// The value following `label:` on the same line, or on the next line when the label stands
+/// alone (e.g. `<strong>Inhaber:</strong> Max Mustermann`).
+fn labelled_values<'a>(lines: &'a [String], labels: &[&str]) -> Vec<&'a str> {
+    let mut values = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let Some((label, rest)) = line.split_once(':') else {
+            continue;
+        };
+        if !labels.contains(&label.trim().to_lowercase().as_str()) {
+            continue;
+        }
+        let rest = rest.trim();
+        if !rest.is_empty() {
+            values.push(rest);
+        } else if let Some(next) = lines.get(i + 1) {
+            values.push(next.as_str());
+        }
+    }
+    values
+}
+
+/// Every JSON-LD document embedded in the page.
+fn json_ld(document: &Html) -> Vec<Value> {
+    let selector = Selector::parse(r#"script[type="application/ld+json"]"#).unwrap();
+    document
+        .select(&selector)
+        .filter_map(|script| serde_json::from_str(&script.text().collect::<String>()).ok())
+        .collect()
+}
+
+/// The schema.org types of a JSON-LD object.
+fn json_ld_types(object: &Map<String, Value>) -> Vec<&str> {
+    match object.get("@type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(Value::as_str).collect(),
+        _ => vec![],
+    }
+}
+
+/// Whether a JSON-LD object describes a business rather than a page, product or person.
+fn is_business(object: &Map<String, Value>) -> bool {
+    let types = json_ld_types(object);
+    !types.contains(&"Person")
+        && (object.contains_key("address")
+            || types
+                .iter()
+                .any(|t| t.contains("Organization") || t.contains("Business")))
+}
+
+fn collect_json_ld(value: &Value, candidates: &mut Candidates) {
+    match value {
+        Value::Array(values) => {
+            for value in values {
+                collect_json_ld(value, candidates);
+            }
+        }
+        Value::Object(object) => {
+            let types = json_ld_types(object);
+            if let Some(Value::String(email)) = object.get("email") {
+                candidates.email(email, ExtractionMethod::JsonLd);
+            }
+            if let Some(Value::String(telephone)) = object.get("telephone") {
+                candidates.phone(telephone, ExtractionMethod::JsonLd);
+            }
+            if types.contains(&"Person")
+                && let Some(Value::String(name)) = object.get("name")
+            {
+                candidates.name(name, ExtractionMethod::JsonLd);
+            }
+            for key in ["founder", "owner", "employee", "contactPoint", "@graph"] {
+                if let Some(nested) = object.get(key) {
+                    collect_json_ld(nested, candidates);
+                }
+            }
+            if is_business(object)
+                && let Some(Value::String(name)) = object.get("name")
+            {
+                candidates.organization_name(name, ExtractionMethod::JsonLd);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn microdata_value(element: ElementRef) -> String {
+    element
+        .value()
+        .attr("content")
+        .or_else(|| element.value().attr("href"))
+        .map(str::to_string)
+        .unwrap_or_else(|| element.text().collect())
+}
+
+fn collect_page(page: &PageSnapshot, candidates: &mut Candidates) {
+    let document = Html::parse_document(&page.body);
+
+    for value in json_ld(&document) {
+        collect_json_ld(&value, candidates);
+    }
+
+    let email = Selector::parse(r#"[itemprop="email"]"#).unwrap();
+    for element in document.select(&email) {
+        candidates.email(&microdata_value(element), ExtractionMethod::Microdata);
+    }
+    let telephone = Selector::parse(r#"[itemprop="telephone"]"#).unwrap();
+    for element in document.select(&telephone) {
+        candidates.phone(&microdata_value(element), ExtractionMethod::Microdata);
+    }
+    let name = Selector::parse(
+        r#"[itemtype*="Person"] [itemprop="name"], [itemprop="founder"] [itemprop="name"]"#,
+    )
+    .unwrap();
+    for element in document.select(&name) {
+        candidates.name(&microdata_value(element), ExtractionMethod::Microdata);
+    }
+
+    let mailto = Selector::parse(r#"a[href^="mailto:"]"#).unwrap();
+    for link in document.select(&mailto) {
+        if let Some(href) = link.value().attr("href") {
+            candidates.email(href, ExtractionMethod::Mailto);
+        }
+    }
+    let tel = Selector::parse(r#"a[href^="tel:"]"#).unwrap();
+    for link in document.select(&tel) {
+        if let Some(href) = link.value().attr("href") {
+            candidates.phone(href, ExtractionMethod::Tel);
+        }
+    }
+
+    let lines = visible_lines(&document);
+    let impressum = is_impressum(page);
+    let text_method = if impressum {
+        ExtractionMethod::Impressum
+    } else {
+        ExtractionMethod::Text
+    };
+    for line in lines.iter() {
+        for captures in EMAIL.captures_iter(line) {
+            let domain = DOT.replace_all(&captures[2], ".");
+            candidates.email(&format!("{}@{}", &captures[1], domain), text_method);
+        }
+    }
+    if impressum {
+        for value in labelled_values(&lines, &OWNER_LABELS) {
+            candidates.name(value, ExtractionMethod::Impressum);
+        }
+        for value in labelled_values(&lines, &PHONE_LABELS) {
+            if let Some(phone) = PHONE.find(value) {
+                candidates.phone(phone.as_str(), ExtractionMethod::Impressum);
+            }
+        }
+    }
+}
+
+/// Picks the value found by the most reliable rule. Returns `None` when nothing was found, or when
+/// the most reliable rule found several different values and none of them matches `domain`.
+fn resolve(
+    candidates: &[Candidate],
+    priority: &[ExtractionMethod],
+    domain: Option<&str>,
+) -> Option<Candidate> {
+    let best = priority.iter().find_map(|method| {
+        let values: BTreeSet<&str> = candidates
+            .iter()
+            .filter(|candidate| candidate.method == *method)
+            .map(|candidate| candidate.value.as_str())
+            .collect();
+        (!values.is_empty()).then_some((*method, values))
+    });
+    let (method, values) = best?;
+    let value = match values.len() {
+        1 => values.first().copied(),
+        _ => {
+            let on_domain: Vec<&str> = values
+                .iter()
+                .copied()
+                .filter(|value| {
+                    domain.is_some_and(|domain| {
+                        value
+                            .rsplit_once('@')
+                            .is_some_and(|(_, value_domain)| value_domain == domain)
+                    })
+                })
+                .collect();
+            match on_domain.as_slice() {
+                [value] => Some(*value),
+                _ => None,
+            }
+        }
+    }?;
+    Some(Candidate {
+        value: value.to_string(),
+        method,
+    })
+}
+
+/// Extracts what the rules can determine on their own.
+fn extract(website: &CrawledWebsite) -> Contact {
+    let mut candidates = Candidates::default();
+    for page in website.pages.iter() {
+        collect_page(page, &mut candidates);
+    }
+    let domain = website
+        .pages
+        .first()
+        .and_then(|page| page.url.host_str())
+        .map(|host| host.trim_start_matches("www.").to_string());
+
+    use ExtractionMethod::*;
+    let email = resolve(
+        &candidates.emails,
+        &[JsonLd, Microdata, Impressum, Mailto, Text],
+        domain.as_deref(),
+    );
+    // Several people in the same source (e.g. a team page) do not make the name ambiguous, the
+    // first one listed is usually the owner.
+    let name = [JsonLd, Impressum, Microdata]
+        .iter()
+        .find_map(|method| {
+            candidates
+                .names
+                .iter()
+                .find(|candidate| candidate.method == *method)
+        })
+        .or(candidates.organization_names.first())
+        .cloned();
+    let phone = resolve(
+        &candidates.phones,
+        &[JsonLd, Microdata, Tel, Impressum],
+        None,
+    );
+
+    Contact {
+        contact_email: email.as_ref().map(|c| c.value.clone()),
+        contact_email_method: email.map(|c| c.method),
+        contact_name: name.as_ref().map(|c| c.value.clone()),
+        contact_name_method: name.map(|c| c.method),
+        contact_phone: phone.as_ref().map(|c| c.value.clone()),
+        contact_phone_method: phone.map(|c| c.method),
+        prompt_version: None,
+        social_links: None,
+    }
+}
+
+impl<E: ContactExtractor> ContactExtractor for RuleBasedExtractor<E> {
+    async fn get_contact(
+        &self,
+        website: &Website,
+        crawled: &CrawledWebsite,
+    ) -> Result<Contact, ExtractContactError> {
+        let contact = extract(crawled);
+        if contact.contact_email.is_some() && contact.contact_name.is_some() {
+            tracing::debug!("contact extracted without llm: {:?}", contact);
+            return Ok(contact);
+        }
+
+        tracing::debug!("rules found {:?}, escalating to llm", contact);
+        match self.fallback.get_contact(website, crawled).await {
+            Ok(fallback) => Ok(contact.or(fallback)),
+            Err(e) if contact.contact_email.is_some() || contact.contact_name.is_some() => {
+                tracing::warn!("llm extraction failed, keeping rule based contact: {}", e);
+                Ok(contact)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl<E: BusinessProfileExtractor> BusinessProfileExtractor for RuleBasedExtractor<E> {
+    async fn get_business_profile(
+        &self,
+        website: &Website,
+        crawled: &CrawledWebsite,
+    ) -> Result<BusinessProfile, ExtractBusinessProfileError> {
+        let profile = business_profile::extract(crawled);
+        if profile.is_complete() {
+            tracing::debug!("business profile extracted without llm: {:?}", profile);
+            return Ok(profile);
+        }
+
+        tracing::debug!("structured data found {:?}, escalating to llm", profile);
+        match self.fallback.get_business_profile(website, crawled).await {
+            Ok(fallback) => Ok(profile.or(fallback)),
+            Err(e) if profile != BusinessProfile::default() => {
+                tracing::warn!(
+                    "llm extraction failed, keeping structured data profile: {}",
+                    e
+                );
+                Ok(profile)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use url::Url;
+
+    use crate::domain::website::models::{
+        page::{CrawledWebsite, PageSnapshot},
+        website::{ExtractionMethod, RenderMode},
+    };
+
+    use super::{Candidate, extract, resolve};
+
+    fn crawled(pages: &[(&str, &str)]) -> CrawledWebsite {
+        CrawledWebsite {
+            pages: pages
+                .iter()
+                .map(|(url, body)| {
+                    PageSnapshot::new(
+                        Url::parse(url).unwrap(),
+                        200,
+                        BTreeMap::new(),
+                        body.to_string(),
+                    )
+                })
+                .collect(),
+            external_links: vec![],
+            render_mode: RenderMode::Static,
+        }
+    }
+
+    fn candidate(value: &str, method: ExtractionMethod) -> Candidate {
+        Candidate {
+            value: value.to_string(),
+            method,
+        }
+    }
+
+    #[test]
+    fn resolve_prefers_the_most_reliable_method() {
+        let candidates = [
+            candidate("text@example.com", ExtractionMethod::Text),
+            candidate("mailto@example.com", ExtractionMethod::Mailto),
+        ];
+        let resolved = resolve(
+            &candidates,
+            &[ExtractionMethod::Mailto, ExtractionMethod::Text],
+            None,
+        );
+        assert_eq!(
+            resolved,
+            Some(candidate("mailto@example.com", ExtractionMethod::Mailto))
+        );
+    }
+
+    #[test]
+    fn resolve_picks_the_only_value_on_the_domain_of_the_site() {
+        let candidates = [
+            candidate("info@example.com", ExtractionMethod::Mailto),
+            candidate("agency@webdesign.com", ExtractionMethod::Mailto),
+        ];
+        let resolved = resolve(
+            &candidates,
+            &[ExtractionMethod::Mailto],
+            Some("example.com"),
+        );
+        assert_eq!(
+            resolved,
+            Some(candidate("info@example.com", ExtractionMethod::Mailto))
+        );
+    }
+
+    #[test]
+    fn resolve_does_not_take_a_longer_domain_for_the_domain_of_the_site() {
+        let candidates = [
+            candidate("info@evilexample.com", ExtractionMethod::Mailto),
+            candidate("agency@webdesign.com", ExtractionMethod::Mailto),
+        ];
+        let resolved = resolve(
+            &candidates,
+            &[ExtractionMethod::Mailto],
+            Some("example.com"),
+        );
+        assert_eq!(resolved, None);
+    }
+
+    #[test]
+    fn resolve_gives_up_on_ambiguous_values() {
+        let candidates = [
+            candidate("info@example.com", ExtractionMethod::Mailto),
+            candidate("sales@example.com", ExtractionMethod::Mailto),
+        ];
+        let resolved = resolve(
+            &candidates,
+            &[ExtractionMethod::Mailto],
+            Some("example.com"),
+        );
+        assert_eq!(resolved, None);
+    }
+
+    #[test]
+    fn extract_reads_mailto_and_tel_links() {
+        let contact = extract(&crawled(&[(
+            "https://www.example.com/",
+            r#"<a href="mailto:Info@Example.com?subject=Hello">Mail</a>
+            <a href="tel:+41 44 123 45 67">Call</a>"#,
+        )]));
+        assert_eq!(contact.contact_email.as_deref(), Some("info@example.com"));
+        assert_eq!(contact.contact_email_method, Some(ExtractionMethod::Mailto));
+        assert_eq!(contact.contact_phone.as_deref(), Some("+41 44 123 45 67"));
+        assert_eq!(contact.contact_phone_method, Some(ExtractionMethod::Tel));
+    }
+
+    #[test]
+    fn extract_reads_obfuscated_addresses() {
+        let contact = extract(&crawled(&[(
+            "https://example.com/",
+            "<p>Write to hello [at] example [dot] com</p>",
+        )]));
+        assert_eq!(contact.contact_email.as_deref(), Some("hello@example.com"));
+        assert_eq!(contact.contact_email_method, Some(ExtractionMethod::Text));
+    }
+
+    #[test]
+    fn extract_ignores_asset_names() {
+        let contact = extract(&crawled(&[("https://example.com/", "<p>logo@2x.png</p>")]));
+        assert_eq!(contact.contact_email, None);
+    }
+
+    #[test]
+    fn extract_reads_json_ld() {
+        let contact = extract(&crawled(&[(
+            "https://example.com/",
+            r#"<script type="application/ld+json">{
+                "@type": "LocalBusiness",
+                "name": "Bäckerei Muster",
+                "email": "backstube@example.com",
+                "telephone": "+49 30 1234567",
+                "founder": {"@type": "Person", "name": "Erika Muster"}
+            }</script>
+            <a href="mailto:other@example.com">Mail</a>"#,
+        )]));
+        assert_eq!(
+            contact.contact_email.as_deref(),
+            Some("backstube@example.com")
+        );
+        assert_eq!(contact.contact_email_method, Some(ExtractionMethod::JsonLd));
+        assert_eq!(contact.contact_name.as_deref(), Some("Erika Muster"));
+        assert_eq!(contact.contact_name_method, Some(ExtractionMethod::JsonLd));
+        assert_eq!(contact.contact_phone.as_deref(), Some("+49 30 1234567"));
+    }
+
+    #[test]
+    fn extract_falls_back_to_the_business_name() {
+        let contact = extract(&crawled(&[(
+            "https://example.com/",
+            r#"<script type="application/ld+json">{"@type": "Organization", "name": "Muster AG"}</script>"#,
+        )]));
+        assert_eq!(contact.contact_name.as_deref(), Some("Muster AG"));
+    }
+
+    #[test]
+    fn extract_reads_the_impressum() {
+        let contact = extract(&crawled(&[
+            ("https://example.com/", "<p>Welcome</p>"),
+            (
+                "https://example.com/impressum",
+                "<p><strong>Inhaber:</strong></p><p>Max Mustermann</p>
+                <p>Telefon: 030 / 123 456 78</p>
+                <p>E-Mail: max@example.com</p>",
+            ),
+        ]));
+        assert_eq!(contact.contact_name.as_deref(), Some("Max Mustermann"));
+        assert_eq!(
+            contact.contact_name_method,
+            Some(ExtractionMethod::Impressum)
+        );
+        assert_eq!(contact.contact_phone.as_deref(), Some("030 / 123 456 78"));
+        assert_eq!(
+            contact.contact_phone_method,
+            Some(ExtractionMethod::Impressum)
+        );
+        assert_eq!(contact.contact_email.as_deref(), Some("max@example.com"));
+        assert_eq!(
+            contact.contact_email_method,
+            Some(ExtractionMethod::Impressum)
+        );
+    }
+
+    #[test]
+    fn extract_leaves_ambiguous_emails_to_the_fallback() {
+        let contact = extract(&crawled(&[(
+            "https://example.com/",
+            r#"<a href="mailto:info@example.com">Info</a>
+            <a href="mailto:jobs@example.com">Jobs</a>"#,
+        )]));
+        assert_eq!(contact.contact_email, None);
+        assert_eq!(contact.contact_email_method, None);
+    }
+}
@@ -0,0 +1,233 @@
+use scraper::{Html, Selector};
+use serde_json::{Map, Value};
+
+use crate::domain::website::models::{
+    business_profile::{BusinessProfile, DayOfWeek, OpeningHours, PostalAddress},
+    page::CrawledWebsite,
+};
+
+use super::{is_business, json_ld, json_ld_types};
+
+/// schema.org types too generic to be used as the business category.
+const GENERIC_TYPES: [&str; 4] = ["Organization", "LocalBusiness", "Corporation", "Place"];
+
+/// Reads the business profile from the JSON-LD and OpenGraph metadata of every page. Values found
+/// on earlier pages win.
+pub(super) fn extract(website: &CrawledWebsite) -> BusinessProfile {
+    let mut profile = BusinessProfile::default();
+    for page in website.pages.iter() {
+        let document = Html::parse_document(&page.body);
+        for value in json_ld(&document) {
+            collect_json_ld(&value, &mut profile);
+        }
+        profile = profile.or(open_graph(&document));
+    }
+    profile
+}
+
+fn collect_json_ld(value: &Value, profile: &mut BusinessProfile) {
+    match value {
+        Value::Array(values) => {
+            for value in values {
+                collect_json_ld(value, profile);
+            }
+        }
+        Value::Object(object) => {
+            if let Some(graph) = object.get("@graph") {
+                collect_json_ld(graph, profile);
+            }
+            if is_business(object) {
+                let business = business_profile(object);
+                *profile = std::mem::take(profile).or(business);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn business_profile(object: &Map<String, Value>) -> BusinessProfile {
+    let mut phone_numbers: Vec<String> = strings(object.get("telephone"));
+    for contact_point in objects(object.get("contactPoint")) {
+        phone_numbers.extend(strings(contact_point.get("telephone")));
+    }
+
+    let mut opening_hours: Vec<OpeningHours> = objects(object.get("openingHoursSpecification"))
+        .flat_map(|specification| {
+            let opens = string(specification.get("opens"));
+            let closes = string(specification.get("closes"));
+            strings(specification.get("dayOfWeek"))
+                .into_iter()
+                .filter_map(|day| day.parse().ok())
+                .filter_map(move |day_of_week| {
+                    Some(OpeningHours {
+                        day_of_week,
+                        opens: opens.clone()?,
+                        closes: closes.clone()?,
+                    })
+                })
+        })
+        .collect();
+    if opening_hours.is_empty() {
+        opening_hours = strings(object.get("openingHours"))
+            .iter()
+            .flat_map(|hours| parse_opening_hours(hours))
+            .collect();
+    }
+
+    let category = json_ld_types(object)
+        .into_iter()
+        .find(|t| !GENERIC_TYPES.contains(t))
+        .map(str::to_string);
+
+    let mut services: Vec<String> = objects(object.get("makesOffer"))
+        .chain(
+            objects(object.get("hasOfferCatalog"))
+                .flat_map(|catalog| objects(catalog.get("itemListElement")).collect::<Vec<_>>()),
+        )
+        .filter_map(|offer| {
+            string(offer.get("name")).or_else(|| {
+                objects(offer.get("itemOffered"))
+                    .next()
+                    .and_then(|item| string(item.get("name")))
+            })
+        })
+        .collect();
+    services.extend(strings(object.get("serviceType")));
+    services.dedup();
+
+    BusinessProfile {
+        address: object.get("address").and_then(postal_address),
+        phone_numbers,
+        opening_hours,
+        category,
+        services,
+    }
+}
+
+fn postal_address(value: &Value) -> Option<PostalAddress> {
+    let address = match value {
+        Value::String(street_address) => PostalAddress {
+            street_address: Some(street_address.trim().to_string()),
+            ..Default::default()
+        },
+        Value::Object(address) => PostalAddress {
+            street_address: string(address.get("streetAddress")),
+            postal_code: string(address.get("postalCode")),
+            locality: string(address.get("addressLocality")),
+            region: string(address.get("addressRegion")),
+            country: string(address.get("addressCountry")).or_else(|| {
+                objects(address.get("addressCountry"))
+                    .next()
+                    .and_then(|country| string(country.get("name")))
+            }),
+        },
+        Value::Array(addresses) => return addresses.iter().find_map(postal_address),
+        _ => return None,
+    };
+    (address != PostalAddress::default()).then_some(address)
+}
+
+/// Parses the compact `openingHours` syntax, e.g. `Mo-Fr 09:00-18:00` or `Sa,Su 10:00-14:00`.
+fn parse_opening_hours(hours: &str) -> Vec<OpeningHours> {
+    let Some((days, times)) = hours.trim().split_once(' ') else {
+        return vec![];
+    };
+    let Some((opens, closes)) = times.trim().split_once('-') else {
+        return vec![];
+    };
+    let mut opening_hours = Vec::new();
+    for range in days.split(',') {
+        let (first, last) = range.split_once('-').unwrap_or((range, range));
+        let (Ok(first), Ok(last)) = (first.parse::<DayOfWeek>(), last.parse::<DayOfWeek>()) else {
+            continue;
+        };
+        for day_of_week in DayOfWeek::ALL
+            .into_iter()
+            .filter(|day| (first..=last).contains(day))
+        {
+            opening_hours.push(OpeningHours {
+                day_of_week,
+                opens: opens.trim().to_string(),
+                closes: closes.trim().to_string(),
+            });
+        }
+    }
+    opening_hours
+}
+
+/// Reads the OpenGraph and Facebook `business:` meta tags.
+fn open_graph(document: &Html) -> BusinessProfile {
+    let meta = |properties: &[&str]| -> Vec<String> {
+        let mut values = Vec::new();
+        for property in properties {
+            let selector = Selector::parse(&format!(r#"meta[property="{property}"]"#)).unwrap();
+            values.extend(
+                document
+                    .select(&selector)
+                    .filter_map(|element| element.value().attr("content"))
+                    .map(|content| content.trim().to_string())
+                    .filter(|content| !content.is_empty()),
+            );
+        }
+        values
+    };
+    let first = |properties: &[&str]| meta(properties).into_iter().next();
+
+    let address = PostalAddress {
+        street_address: first(&["business:contact_data:street_address", "og:street-address"]),
+        postal_code: first(&["business:contact_data:postal_code", "og:postal-code"]),
+        locality: first(&["business:contact_data:locality", "og:locality"]),
+        region: first(&["business:contact_data:region", "og:region"]),
+        country: first(&["business:contact_data:country_name", "og:country-name"]),
+    };
+
+    let days = meta(&["business:hours:day"]);
+    let starts = meta(&["business:hours:start"]);
+    let ends = meta(&["business:hours:end"]);
+    let opening_hours = days
+        .iter()
+        .zip(starts.iter())
+        .zip(ends.iter())
+        .filter_map(|((day, opens), closes)| {
+            Some(OpeningHours {
+                day_of_week: day.parse().ok()?,
+                opens: opens.clone(),
+                closes: closes.clone(),
+            })
+        })
+        .collect();
+
+    BusinessProfile {
+        address: (address != PostalAddress::default()).then_some(address),
+        phone_numbers: meta(&["business:contact_data:phone_number", "og:phone_number"]),
+        opening_hours,
+        category: None,
+        services: vec![],
+    }
+}
+
+fn string(value: Option<&Value>) -> Option<String> {
+    value
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+/// A string or an array of strings.
+fn strings(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::Array(values)) => values.iter().filter_map(|v| string(Some(v))).collect(),
+        value => string(value).into_iter().collect(),
+    }
+}
+
+/// An object or an array of objects.
+fn objects(value: Option<&Value>) -> impl Iterator<Item = &Map<String, Value>> {
+    let values: Vec<&Map<String, Value>> = match value {
+        Some(Value::Array(values)) => values.iter().filter_map(Value::as_object).collect(),
+        Some(Value::Object(object)) => vec![object],
+        _ => vec![],
+    };
+    values.into_iter()
+}
@@ -0,0 +1,46 @@
+/*!
+   Module `site_generator` selects the [SiteGenerator] implementation at runtime, from the
+   configuration.
+*/
+
+use crate::domain::website::{
+    models::{
+        preview::DeletePreviewError,
+        website::{GenerateWebsiteError, GeneratedWebsite, Website},
+    },
+    ports::{
+        AutomationFailureRepository, CopyWriter, PreviewRepository, PromptRepository, SiteGenerator,
+    },
+};
+
+use super::{lovable::Lovable, static_site::StaticSite};
+
+/// One of the available [SiteGenerator] implementations.
+#[derive(Clone)]
+pub enum ConfiguredSiteGenerator<R, W> {
+    Lovable(Lovable<R>),
+    Static(StaticSite<W, R>),
+}
+
+impl<R, W> SiteGenerator for ConfiguredSiteGenerator<R, W>
+where
+    R: PromptRepository + PreviewRepository + AutomationFailureRepository,
+    W: CopyWriter,
+{
+    async fn generate_new_single_page(
+        &self,
+        website: &Website,
+    ) -> Result<GeneratedWebsite, GenerateWebsiteError> {
+        match self {
+            Self::Lovable(lovable) => lovable.generate_new_single_page(website).await,
+            Self::Static(static_site) => static_site.generate_new_single_page(website).await,
+        }
+    }
+
+    async fn delete_preview(&self, slug: &str) -> Result<(), DeletePreviewError> {
+        match self {
+            Self::Lovable(lovable) => lovable.delete_preview(slug).await,
+            Self::Static(static_site) => static_site.delete_preview(slug).await,
+        }
+    }
+}
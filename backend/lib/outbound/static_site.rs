@@ -0,0 +1,360 @@
+/*!
+   Module `static_site` generates landing pages locally from the bundled templates in
+   `templates/landing_page`, without a third-party site builder, and hosts them as previews
+   served by the HTTP server under `/preview/{slug}/`.
+*/
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use chrono::{TimeDelta, Utc};
+use url::Url;
+use uuid::Uuid;
+
+use crate::domain::website::{
+    models::{
+        business_profile::{BusinessProfile, DayOfWeek},
+        landing_page::{LandingPageCopy, ServiceCopy},
+        preview::{CreatePreviewRequest, DeletePreviewError},
+        website::{GenerateWebsiteError, GeneratedWebsite, Website},
+    },
+    ports::{CopyWriter, PreviewRepository, SiteGenerator},
+};
+
+const INDEX_TEMPLATE: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/templates/landing_page/index.html"
+));
+const STYLES: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/templates/landing_page/styles.css"
+));
+
+/// Renders a landing page from the contact and business profile of a website and writes it as
+/// static files to `{output_dir}/{slug}/`. The copy is written by the [CopyWriter] when one is
+/// configured, and derived from the business profile otherwise.
+///
+/// Every generated site is registered as a preview expiring after `preview_ttl`, replacing the
+/// files of the previous site of the website, and its [GeneratedWebsite::url] is the hosted
+/// `{public_base_url}/preview/{slug}/`.
+#[derive(Clone)]
+pub struct StaticSite<W, P> {
+    output_dir: PathBuf,
+    public_base_url: Url,
+    preview_ttl: Option<TimeDelta>,
+    copy_writer: Option<W>,
+    previews: P,
+}
+
+impl<W: CopyWriter, P: PreviewRepository> StaticSite<W, P> {
+    pub fn new(
+        output_dir: impl Into<PathBuf>,
+        public_base_url: Url,
+        preview_ttl: Option<TimeDelta>,
+        copy_writer: Option<W>,
+        previews: P,
+    ) -> Self {
+        Self {
+            output_dir: output_dir.into(),
+            public_base_url,
+            preview_ttl,
+            copy_writer,
+            previews,
+        }
+    }
+
+    /// The url `slug` is hosted at.
+    pub fn preview_url(&self, slug: &str) -> Result<Url, url::ParseError> {
+        self.public_base_url.join(&format!("preview/{slug}/"))
+    }
+
+    /// Directory the pages of `slug` are written to.
+    pub fn site_dir(&self, slug: &str) -> PathBuf {
+        self.output_dir.join(slug)
+    }
+
+    /// Removes the files of the sites generated for `website_id` before `slug`, which is hosted
+    /// in their place. Failures are only logged, the previews expire anyway.
+    async fn delete_previous_sites(&self, website_id: i64, slug: &str) {
+        let previews = match self.previews.list_previews(website_id).await {
+            Ok(previews) => previews,
+            Err(e) => {
+                tracing::warn!("failed to list the previews of website {website_id}: {e:?}");
+                return;
+            }
+        };
+        for preview in previews.iter().filter(|preview| preview.slug != slug) {
+            if let Err(e) = self.delete_preview(&preview.slug).await {
+                tracing::warn!("failed to delete preview {}: {e:?}", preview.slug);
+            }
+        }
+    }
+
+    async fn copy(&self, website: &Website) -> LandingPageCopy {
+        let Some(copy_writer) = &self.copy_writer else {
+            return fallback_copy(website);
+        };
+        match copy_writer.write_landing_page_copy(website).await {
+            Ok(copy) => copy,
+            Err(e) => {
+                tracing::warn!(
+                    "failed to write copy for website {}, using the business profile: {e}",
+                    website.id
+                );
+                fallback_copy(website)
+            }
+        }
+    }
+}
+
+/// A filesystem and url friendly name for a generated site of `website`, e.g.
+/// `42-example-com-5f0c2a9e41b7`. The random suffix keeps the previews of other websites from
+/// being guessed.
+pub fn slug(website: &Website) -> String {
+    let host = Url::parse(&website.source_address)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_default();
+    let host = host.trim_start_matches("www.");
+    let mut slug = website.id.to_string();
+    for part in host
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+    {
+        slug.push('-');
+        slug.push_str(&part.to_ascii_lowercase());
+    }
+    slug.push('-');
+    slug.push_str(&Uuid::new_v4().simple().to_string()[..12]);
+    slug
+}
+
+fn business_name(website: &Website) -> String {
+    website.contact_name.clone().unwrap_or_else(|| {
+        Url::parse(&website.source_address)
+            .ok()
+            .and_then(|url| {
+                url.host_str()
+                    .map(|host| host.trim_start_matches("www.").to_string())
+            })
+            .unwrap_or_else(|| website.source_address.clone())
+    })
+}
+
+/// Copy derived from the business profile alone, used without a [CopyWriter].
+fn fallback_copy(website: &Website) -> LandingPageCopy {
+    let profile = website.business_profile.clone().unwrap_or_default();
+    let name = business_name(website);
+    let locality = profile
+        .address
+        .as_ref()
+        .and_then(|address| address.locality.clone());
+    let subheadline = match (&profile.category, &locality) {
+        (Some(category), Some(locality)) => format!("{category} in {locality}"),
+        (Some(category), None) => category.clone(),
+        (None, Some(locality)) => format!("Your local partner in {locality}"),
+        (None, None) => String::from("Quality you can count on"),
+    };
+    let about = if profile.services.is_empty() {
+        format!("{name} is here to help. Get in touch to find out what we can do for you.")
+    } else {
+        format!(
+            "{name} offers {}. Get in touch to find out what we can do for you.",
+            profile.services.join(", ")
+        )
+    };
+    LandingPageCopy {
+        headline: name,
+        subheadline,
+        about,
+        call_to_action: String::from("Contact us"),
+        services: profile
+            .services
+            .iter()
+            .map(|service| ServiceCopy {
+                name: service.clone(),
+                description: String::new(),
+            })
+            .collect(),
+        prompt_version: None,
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn services_section(services: &[ServiceCopy]) -> String {
+    if services.is_empty() {
+        return String::new();
+    }
+    let cards: String = services
+        .iter()
+        .map(|service| {
+            format!(
+                "<li><h3>{}</h3><p>{}</p></li>",
+                escape(&service.name),
+                escape(&service.description)
+            )
+        })
+        .collect();
+    format!(
+        r#"<section class="services"><div class="container"><h2>What we offer</h2><ul class="cards">{cards}</ul></div></section>"#
+    )
+}
+
+fn contact_details(website: &Website, profile: &BusinessProfile) -> String {
+    let mut lines = Vec::new();
+    if let Some(address) = &profile.address {
+        let address = [
+            &address.street_address,
+            &address.postal_code,
+            &address.locality,
+            &address.country,
+        ]
+        .into_iter()
+        .flatten()
+        .map(|part| escape(part))
+        .collect::<Vec<_>>()
+        .join(", ");
+        lines.push(format!("<p>{address}</p>"));
+    }
+    let phones = profile
+        .phone_numbers
+        .iter()
+        .chain(website.contact_phone.iter())
+        .fold(Vec::<&String>::new(), |mut phones, phone| {
+            if !phones.contains(&phone) {
+                phones.push(phone);
+            }
+            phones
+        });
+    for phone in phones {
+        lines.push(format!(
+            r#"<p><a href="tel:{0}">{0}</a></p>"#,
+            escape(phone)
+        ));
+    }
+    if let Some(email) = &website.contact_email {
+        lines.push(format!(
+            r#"<p><a href="mailto:{0}">{0}</a></p>"#,
+            escape(email)
+        ));
+    }
+    lines.concat()
+}
+
+fn opening_hours_table(profile: &BusinessProfile) -> String {
+    if profile.opening_hours.is_empty() {
+        return String::new();
+    }
+    let rows: String = DayOfWeek::ALL
+        .into_iter()
+        .flat_map(|day| {
+            profile
+                .opening_hours
+                .iter()
+                .filter(move |hours| hours.day_of_week == day)
+        })
+        .map(|hours| {
+            format!(
+                "<tr><td>{:?}</td><td>{} – {}</td></tr>",
+                hours.day_of_week,
+                escape(&hours.opens),
+                escape(&hours.closes)
+            )
+        })
+        .collect();
+    format!(r#"<h3>Opening hours</h3><table class="hours">{rows}</table>"#)
+}
+
+/// Fills the `{{placeholders}}` of the index template. Every value is escaped or built from
+/// escaped parts.
+fn render_index(website: &Website, copy: &LandingPageCopy) -> String {
+    let profile = website.business_profile.clone().unwrap_or_default();
+    let values = [
+        ("title", escape(&business_name(website))),
+        (
+            "category",
+            escape(profile.category.as_deref().unwrap_or("")),
+        ),
+        ("headline", escape(&copy.headline)),
+        ("subheadline", escape(&copy.subheadline)),
+        ("about", escape(&copy.about)),
+        ("call_to_action", escape(&copy.call_to_action)),
+        ("services", services_section(&copy.services)),
+        ("contact_details", contact_details(website, &profile)),
+        ("opening_hours", opening_hours_table(&profile)),
+    ];
+    values
+        .iter()
+        .fold(INDEX_TEMPLATE.to_string(), |page, (name, value)| {
+            page.replace(&format!("{{{{{name}}}}}"), value)
+        })
+}
+
+async fn write_site(dir: &Path, index: &str) -> anyhow::Result<()> {
+    tokio::fs::create_dir_all(dir)
+        .await
+        .with_context(|| format!("failed to create {}", dir.display()))?;
+    let index_path = dir.join("index.html");
+    tokio::fs::write(&index_path, index)
+        .await
+        .with_context(|| format!("failed to write {}", index_path.display()))?;
+    tokio::fs::write(dir.join("styles.css"), STYLES)
+        .await
+        .with_context(|| format!("failed to write the styles of {}", dir.display()))?;
+    Ok(())
+}
+
+impl<W: CopyWriter, P: PreviewRepository> SiteGenerator for StaticSite<W, P> {
+    async fn generate_new_single_page(
+        &self,
+        website: &Website,
+    ) -> Result<GeneratedWebsite, GenerateWebsiteError> {
+        let slug = slug(website);
+        tracing::debug!("generating static site {slug}");
+        let copy = self.copy(website).await;
+        let index = render_index(website, &copy);
+        write_site(&self.site_dir(&slug), &index).await?;
+        let url = self
+            .preview_url(&slug)
+            .with_context(|| format!("invalid preview url for {slug}"))?;
+        self.previews
+            .create_preview(&CreatePreviewRequest {
+                slug: slug.clone(),
+                website_id: website.id,
+                expires_at: self.preview_ttl.map(|ttl| Utc::now() + ttl),
+            })
+            .await
+            .map_err(|e| GenerateWebsiteError::Unknown(e.into()))?;
+        self.delete_previous_sites(website.id, &slug).await;
+        Ok(GeneratedWebsite {
+            name: slug,
+            url,
+            prompt_version: copy.prompt_version,
+        })
+    }
+
+    async fn delete_preview(&self, slug: &str) -> Result<(), DeletePreviewError> {
+        let dir = self.site_dir(slug);
+        match tokio::fs::remove_dir_all(&dir).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(DeletePreviewError::Unknown(
+                anyhow::Error::new(e).context(format!("failed to remove {}", dir.display())),
+            )),
+        }
+    }
+}
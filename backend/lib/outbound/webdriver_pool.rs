@@ -0,0 +1,145 @@
+use std::{sync::Arc, time::Duration};
+
+use fantoccini::{Client, ClientBuilder, error::NewSessionError};
+use thiserror::Error;
+use tokio::{
+    sync::{Mutex, OwnedSemaphorePermit, Semaphore},
+    time::Instant,
+};
+
+#[derive(Debug, Error)]
+pub enum AcquireSessionError {
+    #[error("failed to start a webdriver session")]
+    Connect(#[from] NewSessionError),
+    #[error("the webdriver session pool is closed")]
+    Closed,
+}
+
+struct IdleSession {
+    client: Client,
+    since: Instant,
+}
+
+/// Keeps up to `max_sessions` WebDriver sessions open, handing them out one run at a time.
+/// Sessions are reused while they are healthy and have been idle less than `idle_timeout`, so
+/// state like logins survives between runs.
+#[derive(Clone)]
+pub struct WebdriverPool {
+    webdriver_address: String,
+    idle_timeout: Duration,
+    permits: Arc<Semaphore>,
+    idle: Arc<Mutex<Vec<IdleSession>>>,
+}
+
+impl WebdriverPool {
+    pub fn new(webdriver_address: &str, max_sessions: usize, idle_timeout: Duration) -> Self {
+        Self {
+            webdriver_address: webdriver_address.to_string(),
+            idle_timeout,
+            permits: Arc::new(Semaphore::new(max_sessions)),
+            idle: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Waits for a free slot, then hands out the most recently used idle session that is still
+    /// alive, or a new one. Stale and broken idle sessions are closed along the way.
+    pub async fn acquire(&self) -> Result<PooledSession, AcquireSessionError> {
+        let permit = self
+            .permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|_| AcquireSessionError::Closed)?;
+        loop {
+            let Some(idle) = self.idle.lock().await.pop() else {
+                break;
+            };
+            if idle.since.elapsed() > self.idle_timeout {
+                tracing::debug!(
+                    "closing webdriver session idle for {:?}",
+                    idle.since.elapsed()
+                );
+                close(idle.client).await;
+                continue;
+            }
+            // Any command addressed to the session fails once the browser is gone.
+            match idle.client.current_url().await {
+                Ok(_) => {
+                    return Ok(PooledSession {
+                        client: Some(idle.client),
+                        fresh: false,
+                        pool: self.clone(),
+                        _permit: permit,
+                    });
+                }
+                Err(e) => {
+                    tracing::warn!("dropping unhealthy webdriver session: {}", e);
+                    close(idle.client).await;
+                }
+            }
+        }
+        let client = ClientBuilder::native()
+            .connect(&self.webdriver_address)
+            .await?;
+        Ok(PooledSession {
+            client: Some(client),
+            fresh: true,
+            pool: self.clone(),
+            _permit: permit,
+        })
+    }
+}
+
+async fn close(client: Client) {
+    if let Err(e) = client.close().await {
+        tracing::debug!("failed to close webdriver session: {}", e);
+    }
+}
+
+/// A session checked out of a [WebdriverPool]. Hand it back with [PooledSession::release] once
+/// the run succeeded, or close it with [PooledSession::discard] when its state is unknown.
+/// Sessions dropped without either are closed in the background.
+pub struct PooledSession {
+    client: Option<Client>,
+    fresh: bool,
+    pool: WebdriverPool,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl PooledSession {
+    pub fn client(&self) -> &Client {
+        self.client
+            .as_ref()
+            .expect("the client is only taken when the session is consumed")
+    }
+
+    /// Whether the session was just started, rather than reused from an earlier run.
+    pub fn is_fresh(&self) -> bool {
+        self.fresh
+    }
+
+    pub async fn release(mut self) {
+        if let Some(client) = self.client.take() {
+            self.pool.idle.lock().await.push(IdleSession {
+                client,
+                since: Instant::now(),
+            });
+        }
+    }
+
+    pub async fn discard(mut self) {
+        if let Some(client) = self.client.take() {
+            close(client).await;
+        }
+    }
+}
+
+impl Drop for PooledSession {
+    fn drop(&mut self) {
+        if let Some(client) = self.client.take()
+            && let Ok(runtime) = tokio::runtime::Handle::try_current()
+        {
+            runtime.spawn(close(client));
+        }
+    }
+}
@@ -0,0 +1,122 @@
+/*!
+   Module `webhook_sender` POSTs the webhook deliveries over HTTP.
+*/
+
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::Context;
+use chrono::Utc;
+use http::{HeaderName, header::CONTENT_TYPE};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use tokio::net::lookup_host;
+use url::{Host, Url};
+
+use crate::domain::website::{
+    models::webhook::{SendWebhookError, Webhook, WebhookDelivery, is_public_address},
+    ports::WebhookSender,
+};
+
+/// The id of the delivery, the same across its retries.
+pub const DELIVERY_HEADER: HeaderName = HeaderName::from_static("x-mithrilforge-delivery");
+/// When the request was sent, in seconds since the epoch.
+pub const TIMESTAMP_HEADER: HeaderName = HeaderName::from_static("x-mithrilforge-timestamp");
+/// The [Webhook::signature] of the timestamp and body.
+pub const SIGNATURE_HEADER: HeaderName = HeaderName::from_static("x-mithrilforge-signature");
+
+/// Attempts taking longer are failed, and retried.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Resolves the hosts of the webhooks for the http client, refusing the ones with an address
+/// that is not public. Checking when connecting keeps a host from switching to a private address
+/// once [HttpWebhookSender::send] checked it.
+#[derive(Debug, Clone, Copy)]
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = lookup_host((name.as_str(), 0)).await?.collect();
+            if let Some(addr) = addrs.iter().find(|addr| !is_public_address(addr.ip())) {
+                return Err(format!(
+                    "{} resolves to the non-public address {}",
+                    name.as_str(),
+                    addr.ip()
+                )
+                .into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// The first address of the host of `url` that is not public, if any.
+async fn forbidden_address(url: &Url) -> anyhow::Result<Option<IpAddr>> {
+    let addresses = match url.host().context("webhook url has no host")? {
+        Host::Ipv4(address) => vec![IpAddr::V4(address)],
+        Host::Ipv6(address) => vec![IpAddr::V6(address)],
+        Host::Domain(domain) => lookup_host((domain, 0))
+            .await
+            .with_context(|| format!("failed to resolve {domain}"))?
+            .map(|addr| addr.ip())
+            .collect(),
+    };
+    Ok(addresses
+        .into_iter()
+        .find(|address| !is_public_address(*address)))
+}
+
+#[derive(Debug, Clone)]
+pub struct HttpWebhookSender {
+    http: reqwest::Client,
+}
+
+impl HttpWebhookSender {
+    pub fn new() -> anyhow::Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(TIMEOUT)
+            // Redirects would send the signed payload to a url the tenant did not register.
+            .redirect(reqwest::redirect::Policy::none())
+            .dns_resolver(Arc::new(PublicResolver))
+            .build()
+            .context("failed to build webhook http client")?;
+        Ok(Self { http })
+    }
+}
+
+impl WebhookSender for HttpWebhookSender {
+    async fn send(
+        &self,
+        webhook: &Webhook,
+        delivery: &WebhookDelivery,
+    ) -> Result<u16, SendWebhookError> {
+        if let Some(address) = forbidden_address(&webhook.url).await? {
+            return Err(SendWebhookError::ForbiddenAddress { address });
+        }
+        let timestamp = Utc::now().timestamp();
+        let response = self
+            .http
+            .post(webhook.url.clone())
+            .header(CONTENT_TYPE, "application/json")
+            .header(DELIVERY_HEADER, delivery.id)
+            .header(TIMESTAMP_HEADER, timestamp)
+            .header(
+                SIGNATURE_HEADER,
+                webhook.signature(timestamp, &delivery.payload),
+            )
+            .body(delivery.payload.clone())
+            .send()
+            .await
+            .with_context(|| format!("failed to POST to {}", webhook.url))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(SendWebhookError::Rejected {
+                status: status.as_u16(),
+            });
+        }
+        Ok(status.as_u16())
+    }
+}
@@ -1,9 +1,15 @@
 use dotenv::dotenv;
 use mithrilforge::{
-    config::Config,
+    config::{Config, NotifierConfig, SiteGeneratorConfig},
     domain::website::service::Service,
     inbound::http::{HttpServer, HttpServerConfig},
-    outbound::{ai::Ai, event_publisher::EventPublisher, postgres::Postgres},
+    outbound::{
+        ai::Ai, crawler::Crawler, event_publisher::EventPublisher, llm::Llm,
+        local_storage::LocalObjectStorage, lovable::Lovable, notifier::ConfiguredNotifier,
+        pg_notifier::PgNotifier, postgres::Postgres, rule_based::RuleBasedExtractor,
+        site_generator::ConfiguredSiteGenerator, static_site::StaticSite,
+        webdriver_pool::WebdriverPool, webhook_sender::HttpWebhookSender,
+    },
 };
 
 #[tokio::main]
@@ -14,16 +20,62 @@ async fn main() -> anyhow::Result<()> {
     dotenv().ok();
     let config = Config::from_config()?;
     let pgsql = Postgres::new(&config.database_url).await?;
-    let notifier = EventPublisher::default();
-    let ai = Ai::new(
-        &config.webdriver_address,
-        &config.lovable_user,
-        &config.lovable_password,
+    pgsql.seed_prompts().await?;
+    let notifier = match config.notifier {
+        NotifierConfig::Local => ConfiguredNotifier::Local(EventPublisher::new(pgsql.clone())),
+        NotifierConfig::Postgres => {
+            ConfiguredNotifier::Postgres(PgNotifier::new(pgsql.clone()).await?)
+        }
+    };
+    let crawler = Crawler::new(&config.webdriver_address, config.render_javascript);
+    let ai = Ai::new(Llm::new(config.llm.clone())?, pgsql.clone());
+    let site_generator = match &config.site_generator {
+        SiteGeneratorConfig::Lovable {
+            user,
+            password,
+            script,
+            max_sessions,
+            session_idle_timeout,
+        } => ConfiguredSiteGenerator::Lovable(Lovable::new(
+            WebdriverPool::new(
+                &config.webdriver_address,
+                max_sessions.get(),
+                *session_idle_timeout,
+            ),
+            script.clone(),
+            user,
+            password,
+            pgsql.clone(),
+        )),
+        SiteGeneratorConfig::Static { llm_copy } => {
+            ConfiguredSiteGenerator::Static(StaticSite::new(
+                &config.preview.dir,
+                config.preview.public_base_url.clone(),
+                config.preview.ttl,
+                llm_copy.then(|| ai.clone()),
+                pgsql.clone(),
+            ))
+        }
+    };
+    let contact_extractor = RuleBasedExtractor::new(ai);
+    let website_service = Service::new(
+        pgsql,
+        notifier,
+        crawler,
+        contact_extractor,
+        site_generator,
+        LocalObjectStorage::new(&config.object_storage_dir),
+        HttpWebhookSender::new()?,
+        config.quotas,
     );
-    let website_service = Service::new(pgsql, notifier, ai);
+    website_service.spawn_outbox_relay();
+    website_service.spawn_webhook_dispatcher();
     let server_config = HttpServerConfig {
         port: &config.server_port,
         jwks: &config.jwks,
+        admin_user_ids: &config.admin_user_ids,
+        preview_dir: &config.preview.dir,
+        preview_banner_text: &config.preview.banner_text,
     };
     let http_server = HttpServer::new(website_service, server_config).await?;
     http_server.run().await
@@ -2,43 +2,94 @@ import { createEffect, createResource, For, onCleanup } from "solid-js";
 import { API_URL } from ".";
 import { useAuth0 } from "@rturnq/solid-auth0";
 import { createStore } from "solid-js/store";
-
-
-type Website = {
-  id: number,
-  source_address: string,
-  contact_name?: string;
-  contact_email?: string;
-  gpt_prompt?: string;
-  generated_website_name?: string;
-  generated_website_link?: string;
+import type { ControlMessage } from "./bindings/ControlMessage";
+import type { EventEnvelope } from "./bindings/EventEnvelope";
+import type { GetWebsiteResponseData } from "./bindings/GetWebsiteResponseData";
+import type { PipelineStage } from "./bindings/PipelineStage";
+import type { WebsiteEvent } from "./bindings/WebsiteEvent";
+import type { WebsiteResponseData } from "./bindings/WebsiteResponseData";
+
+
+type Website = Pick<WebsiteResponseData, "id" | "source_address">
+  & Partial<WebsiteResponseData>
+  & { status?: string, failed?: boolean };
+
+const STAGE_NAMES: Record<PipelineStage, string> = {
+  crawl: "Crawling",
+  contact_extraction: "Extracting contact",
+  business_profile: "Extracting business profile",
+  generation: "Generating website",
 };
-type GetWebsites = { "websites": Website[] };
 
 export const Websites = () => {
   const [state, setState] = createStore([] as Website[]);
   const auth = useAuth0();
 
-  createEffect(async () => {
+  const fetchWebsites = async () => {
     const token = await auth?.getToken();
-    if (!token)
-      return;
+    const response = await fetch(`${API_URL}/websites`, {
+      headers: { "Content-Type": "application/json", "Authorization": `Bearer ${token}` },
+    });
+    const websites = (await response.json()) as GetWebsiteResponseData;
+    setState(websites.websites)
+  };
 
-    const ws = new WebSocket(`${API_URL}/events?token=${encodeURIComponent(token)}`);
+  createEffect(async () => {
+    let ws: WebSocket | undefined;
+    let lastEventId: number | undefined;
+    let closed = false;
+
+    const connect = async () => {
+      const token = await auth?.getToken();
+      if (!token || closed)
+        return;
+
+      const replay = lastEventId === undefined ? "" : `&last_event_id=${lastEventId}`;
+      ws = new WebSocket(`${API_URL}/events?token=${encodeURIComponent(token)}${replay}`);
+      ws.onmessage = ({ data }) => handleMessage(JSON.parse(data));
+      // Reconnect, replaying the events missed in the meantime.
+      ws.onclose = () => {
+        if (!closed)
+          setTimeout(connect, 1000);
+      };
+    };
 
-    ws.onmessage = ({ data }) => {
-      const message = JSON.parse(data);
-      switch (message.type) {
+    const handleMessage = (message: EventEnvelope | ControlMessage) => {
+      if ("type" in message) {
+        switch (message.type) {
+          case "Resync": {
+            fetchWebsites();
+            break;
+          }
+          case "Snapshot": {
+            const { website } = message;
+            setState(w => w.id === website.id, website);
+            break;
+          }
+          case "Error": {
+            console.warn(message.message);
+            break;
+          }
+        }
+        return;
+      }
+      lastEventId = message.event_id;
+      handleEvent(message.event);
+    };
+
+    const handleEvent = (event: WebsiteEvent) => {
+      switch (event.type) {
         case "WebsiteAdded": {
-          const { id, source_address } = message;
+          const { id, source_address } = event;
           setState(websites => [{ id, source_address }, ...websites]);
           break;
         }
-        case "FetchedContact": {
+        case "FetchedContact":
+        case "ContactUpdated": {
           const {
             website_id,
             contact: { contact_email, contact_name },
-          } = message;
+          } = event;
 
           setState(
             w => w.id === website_id,
@@ -50,55 +101,76 @@ export const Websites = () => {
           const {
             website_id,
             generated_website: { name, url },
-          } = message;
+          } = event;
 
           setState(
             w => w.id === website_id,
-            { generated_website_link: url, generated_website_name: name },
+            { generated_website_link: url, generated_website_name: name, status: undefined },
           );
           break;
         }
-
+        case "StageStarted": {
+          const { website_id, stage } = event;
+          setState(w => w.id === website_id, { status: `${STAGE_NAMES[stage]}…`, failed: false });
+          break;
+        }
+        case "StageProgress": {
+          const { website_id, stage, elapsed_secs, pages_crawled } = event;
+          const pages = pages_crawled == null ? "" : `, ${pages_crawled} pages`;
+          setState(w => w.id === website_id, { status: `${STAGE_NAMES[stage]}… (${elapsed_secs}s${pages})` });
+          break;
+        }
+        case "StageFailed": {
+          const { website_id, message } = event;
+          setState(w => w.id === website_id, { status: `Failed: ${message}`, failed: true });
+          break;
+        }
+        case "WebsiteDeleted": {
+          const { website_id } = event;
+          setState(websites => websites.filter(w => w.id !== website_id));
+          break;
+        }
       }
     };
 
-    onCleanup(() => ws.close());
+    onCleanup(() => {
+      closed = true;
+      ws?.close();
+    });
+    await connect();
   });
 
 
-  createResource(async () => {
-    const token = await auth?.getToken();
-    const response = await fetch(`${API_URL}/websites`, {
-      headers: { "Content-Type": "application/json", "Authorization": `Bearer ${token}` },
-    });
-    const websites = (await response.json()) as GetWebsites;
-    setState(websites.websites)
-  });
+  createResource(fetchWebsites);
+  const spinner = (website: Website) => website.failed
+    ? <span class="ml-3">-</span>
+    : <div class="spinner" role="status" aria-label="Loading"></div>;
   return <For each={state}>{(website) => <div class="card"><h2>{website.source_address}</h2>
+    {website.status && <p class="status">{website.status}</p>}
     <div class="content">
       <div class="website">
         <p><strong>Contact email: </strong></p>
-        {website.contact_email ? <span class="ml-3">{website.contact_email}</span> : <div class="spinner" role="status" aria-label="Loading"></div>}
+        {website.contact_email ? <span class="ml-3">{website.contact_email}</span> : spinner(website)}
       </div>
 
       <div class="website">
         <p><strong>Contact name: </strong></p>
-        {website.contact_name ? <span class="ml-3">{website.contact_name}</span> : <div class="spinner" role="status" aria-label="Loading"></div>}
+        {website.contact_name ? <span class="ml-3">{website.contact_name}</span> : spinner(website)}
       </div>
 
       <div class="website">
         <p><strong>GPT prompt:</strong></p>
-        <div class="spinner" role="status" aria-label="Loading"></div></div>
+        {spinner(website)}</div>
 
       <div class="website">
         <p><strong>Generated website name:</strong></p>
-        {website.generated_website_name ? <span class="ml-3">{website.generated_website_name}</span> : <div class="spinner" role="status" aria-label="Loading"></div>}
+        {website.generated_website_name ? <span class="ml-3">{website.generated_website_name}</span> : spinner(website)}
       </div>
 
 
       <div class="website">
         <p><strong>Generated website link:</strong></p>
-        {website.generated_website_link ? <span class="ml-3">{website.generated_website_link}</span> : <div class="spinner" role="status" aria-label="Loading"></div>}
+        {website.generated_website_link ? <span class="ml-3">{website.generated_website_link}</span> : spinner(website)}
       </div>
     </div>
   </div>}</For>;
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type AutomationFailureResponseData = { id: number, website_id: number, run_id: string, error: string, url: string | null, 
+/**
+ * Where to download the artifacts from, `None` if they could not be captured.
+ */
+screenshot_link: string | null, page_source_link: string | null, created_at: string, };
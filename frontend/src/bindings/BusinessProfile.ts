@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OpeningHours } from "./OpeningHours";
+import type { PostalAddress } from "./PostalAddress";
+
+/**
+ * What we know about the business behind a [super::website::Website], used to personalise
+ * pitches and generated pages.
+ */
+export type BusinessProfile = { address: PostalAddress | null, 
+/**
+ * Phone numbers in E.164 format, e.g. `+493012345678`.
+ */
+phone_numbers: Array<string>, opening_hours: Array<OpeningHours>, 
+/**
+ * Industry category, e.g. `Bakery` or `Dentist`.
+ */
+category: string | null, services: Array<string>, };
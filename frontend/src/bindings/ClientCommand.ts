@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WebsiteEventType } from "./WebsiteEventType";
+
+/**
+ * Messages clients send over the websocket. Until the first [ClientCommand::Subscribe], every
+ * event is sent.
+ */
+export type ClientCommand = { "type": "Subscribe", website_ids: Array<number>, event_types: Array<WebsiteEventType>, } | { "type": "Unsubscribe", website_ids: Array<number>, event_types: Array<WebsiteEventType>, } | { "type": "Ping", nonce: string | null, } | { "type": "Snapshot", website_id: number, };
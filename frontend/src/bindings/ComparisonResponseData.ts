@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ScreenshotResponseData } from "./ScreenshotResponseData";
+
+/**
+ * The screenshots of the original site next to the ones of the generated site.
+ */
+export type ComparisonResponseData = { website_id: number, original: Array<ScreenshotResponseData>, generated: Array<ScreenshotResponseData>, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExtractionMethod } from "./ExtractionMethod";
+import type { Social } from "./Social";
+
+export type Contact = { contact_email: string | null, contact_name: string | null, contact_phone: string | null, contact_email_method: ExtractionMethod | null, contact_name_method: ExtractionMethod | null, contact_phone_method: ExtractionMethod | null, 
+/**
+ * Version of the [super::prompt::PromptName::ContactExtraction] prompt that produced the
+ * contact, `None` if the LLM was not used.
+ */
+prompt_version: number | null, social_links: Social | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WebsiteResponseData } from "./WebsiteResponseData";
+
+/**
+ * Messages about the stream itself, sent next to the events.
+ */
+export type ControlMessage = { "type": "Resync", last_event_id: number | null, } | { "type": "Pong", nonce: string | null, } | { "type": "Snapshot", website: WebsiteResponseData, } | { "type": "Error", message: string, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What the last crawl of a [Website] stored.
+ */
+export type CrawlSummary = { pages: number, 
+/**
+ * Pages whose body is the DOM rendered by the browser.
+ */
+rendered_pages: number, 
+/**
+ * When the last page was downloaded, `None` if the website was never crawled.
+ */
+last_fetched_at: string | null, };
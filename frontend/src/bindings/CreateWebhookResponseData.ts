@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WebsiteEventType } from "./WebsiteEventType";
+
+/**
+ * The created webhook, along with its secret. The secret is not returned again.
+ */
+export type CreateWebhookResponseData = { secret: string, id: number, url: string, event_types: Array<WebsiteEventType>, created_at: string, };
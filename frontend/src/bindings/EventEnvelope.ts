@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WebsiteEvent } from "./WebsiteEvent";
+
+/**
+ * A [WebsiteEvent] as kept in the event log and sent to clients. Event ids only grow, so
+ * clients can ask for the events after the last one they saw.
+ */
+export type EventEnvelope = { event_id: number, schema_version: number, occurred_at: string, website_id: number, 
+/**
+ * The owner of the website, `None` for websites added before owners were tracked.
+ */
+tenant: string | null, event: WebsiteEvent, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The rule that produced a [Contact] field.
+ */
+export type ExtractionMethod = "mailto" | "tel" | "json_ld" | "microdata" | "impressum" | "text" | "llm" | "manual";
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type GeneratedWebsite = { name: string, url: string, 
+/**
+ * Version of the [super::prompt::PromptName::LandingPage] prompt the site was generated
+ * from.
+ */
+prompt_version: number | null, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GeneratedWebsite } from "./GeneratedWebsite";
+
+export type GeneratedWebsiteEvent = { website_id: number, generated_website: GeneratedWebsite, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ModelUsage } from "./ModelUsage";
+import type { UsageTotals } from "./UsageTotals";
+import type { WebsiteUsage } from "./WebsiteUsage";
+
+export type GetUsageResponseData = { from: string, to: string, total: UsageTotals, websites: Array<WebsiteUsage>, models: Array<ModelUsage>, };
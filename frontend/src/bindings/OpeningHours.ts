@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DayOfWeek } from "./DayOfWeek";
+
+/**
+ * The hours a business is open on a given day, as `HH:MM` local time.
+ */
+export type OpeningHours = { day_of_week: DayOfWeek, opens: string, closes: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A step of the pipeline run for every added or refreshed [Website].
+ */
+export type PipelineStage = "crawl" | "contact_extraction" | "business_profile" | "generation";
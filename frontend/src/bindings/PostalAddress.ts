@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PostalAddress = { street_address: string | null, postal_code: string | null, locality: string | null, region: string | null, 
+/**
+ * ISO 3166-1 alpha-2 code when known, otherwise the country name as found on the site.
+ */
+country: string | null, };
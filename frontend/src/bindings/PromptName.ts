@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The prompts sent to the LLM and the site builder.
+ */
+export type PromptName = "contact_extraction" | "business_profile_extraction" | "landing_page" | "landing_page_copy";
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PromptName } from "./PromptName";
+
+export type PromptResponseData = { name: PromptName, version: number, template: string, 
+/**
+ * The variables the template may reference, as `{name}`.
+ */
+variables: Array<string>, created_by: string | null, created_at: string, };
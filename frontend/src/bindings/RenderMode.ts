@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How the pages of a [Website] were obtained by the last crawl.
+ */
+export type RenderMode = "static" | "browser";
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Viewport } from "./Viewport";
+
+export type ScreenshotResponseData = { viewport: Viewport, url: string, image_link: string, thumbnail_link: string, captured_at: string, };
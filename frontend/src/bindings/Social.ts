@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The social and review pages linked from a [Website].
+ */
+export type Social = { instagram: string | null, facebook: string | null, google_maps: string | null, google_reviews: string | null, };
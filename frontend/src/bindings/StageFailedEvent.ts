@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PipelineStage } from "./PipelineStage";
+
+export type StageFailedEvent = { website_id: number, stage: PipelineStage, 
+/**
+ * Why the stage failed, safe to show to users.
+ */
+message: string, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PipelineStage } from "./PipelineStage";
+
+/**
+ * Sent periodically while a stage runs.
+ */
+export type StageProgressEvent = { website_id: number, stage: PipelineStage, elapsed_secs: number, 
+/**
+ * Pages downloaded so far, only set while crawling.
+ */
+pages_crawled?: number | null, };
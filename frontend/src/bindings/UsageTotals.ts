@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Aggregated LLM usage.
+ */
+export type UsageTotals = { calls: number, failed_calls: number, prompt_tokens: number, completion_tokens: number, estimated_cost_usd: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The browser window size a [Screenshot] is taken at.
+ */
+export type Viewport = "desktop" | "mobile";
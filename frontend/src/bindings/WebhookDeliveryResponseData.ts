@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DeliveryStatus } from "./DeliveryStatus";
+
+export type WebhookDeliveryResponseData = { id: number, webhook_id: number, 
+/**
+ * `null` for pings.
+ */
+event_id: number | null, replay_of: number | null, payload: string, status: DeliveryStatus, attempts: number, next_attempt_at: string | null, last_response_status: number | null, last_error: string | null, created_at: string, delivered_at: string | null, };
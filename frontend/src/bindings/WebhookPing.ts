@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The body of the deliveries sent to check that a [Webhook] is reachable.
+ */
+export type WebhookPing = { "type": "Ping", webhook_id: number, occurred_at: string, };
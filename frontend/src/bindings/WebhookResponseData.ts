@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WebsiteEventType } from "./WebsiteEventType";
+
+export type WebhookResponseData = { id: number, url: string, event_types: Array<WebsiteEventType>, created_at: string, };
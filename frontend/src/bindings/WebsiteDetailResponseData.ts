@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BusinessProfile } from "./BusinessProfile";
+import type { CrawlSummary } from "./CrawlSummary";
+import type { ExtractionMethod } from "./ExtractionMethod";
+import type { RenderMode } from "./RenderMode";
+import type { Social } from "./Social";
+import type { WebsiteStatus } from "./WebsiteStatus";
+
+export type WebsiteDetailResponseData = { status: WebsiteStatus, crawl: CrawlSummary, id: number, source_address: string, contact_email: string | null, contact_name: string | null, contact_phone: string | null, contact_email_method: ExtractionMethod | null, contact_name_method: ExtractionMethod | null, contact_phone_method: ExtractionMethod | null, contact_prompt_version: number | null, social_links: Social | null, generated_website_link: string | null, generated_website_name: string | null, generated_website_prompt_version: number | null, render_mode: RenderMode | null, business_profile: BusinessProfile | null, 
+/**
+ * Desktop screenshot thumbnails of the original and generated sites.
+ */
+original_thumbnail_link: string | null, generated_thumbnail_link: string | null, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ContactEvent } from "./ContactEvent";
+import type { GeneratedWebsiteEvent } from "./GeneratedWebsiteEvent";
+import type { StageEvent } from "./StageEvent";
+import type { StageFailedEvent } from "./StageFailedEvent";
+import type { StageProgressEvent } from "./StageProgressEvent";
+import type { Website } from "./Website";
+import type { WebsiteDeletedEvent } from "./WebsiteDeletedEvent";
+
+export type WebsiteEvent = { "type": "WebsiteAdded" } & Website | { "type": "FetchedContact" } & ContactEvent | { "type": "GeneratedWebsite" } & GeneratedWebsiteEvent | { "type": "StageStarted" } & StageEvent | { "type": "StageProgress" } & StageProgressEvent | { "type": "StageFailed" } & StageFailedEvent | { "type": "ContactUpdated" } & ContactEvent | { "type": "WebsiteDeleted" } & WebsiteDeletedEvent;
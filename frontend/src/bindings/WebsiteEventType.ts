@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The kind of a [WebsiteEvent], as found in its `type` field.
+ */
+export type WebsiteEventType = "WebsiteAdded" | "FetchedContact" | "GeneratedWebsite" | "StageStarted" | "StageProgress" | "StageFailed" | "ContactUpdated" | "WebsiteDeleted";
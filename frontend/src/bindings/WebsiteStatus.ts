@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PipelineStage } from "./PipelineStage";
+
+/**
+ * Where the pipeline of a [Website] is at, derived from the last of its events of
+ * [WebsiteStatus::EVENT_TYPES].
+ */
+export type WebsiteStatus = { "state": "pending" } | { "state": "running", stage: PipelineStage, } | { "state": "failed", stage: PipelineStage, message: string, } | { "state": "completed" };
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type WebsiteUsage = { website_id: number, source_address: string, calls: number, failed_calls: number, prompt_tokens: number, completion_tokens: number, estimated_cost_usd: number, };
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS pages (
+    page_id BIGSERIAL PRIMARY KEY,
+    website_id BIGINT NOT NULL REFERENCES websites (
+        website_id
+    ) ON DELETE CASCADE,
+    url VARCHAR NOT NULL,
+    status SMALLINT NOT NULL,
+    headers JSONB NOT NULL DEFAULT '{}',
+    body_hash VARCHAR(64) NOT NULL,
+    body TEXT NOT NULL,
+    fetched_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (website_id, url)
+);
@@ -0,0 +1,5 @@
+CREATE TYPE render_mode AS ENUM ('static', 'browser');
+
+ALTER TABLE websites ADD COLUMN render_mode render_mode;
+
+ALTER TABLE pages ADD COLUMN rendered BOOLEAN NOT NULL DEFAULT FALSE;
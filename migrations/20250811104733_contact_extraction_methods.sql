@@ -0,0 +1,8 @@
+CREATE TYPE extraction_method AS ENUM (
+    'mailto', 'tel', 'json_ld', 'microdata', 'impressum', 'text', 'llm'
+);
+
+ALTER TABLE websites ADD COLUMN contact_phone VARCHAR(64),
+ADD COLUMN contact_email_method extraction_method,
+ADD COLUMN contact_name_method extraction_method,
+ADD COLUMN contact_phone_method extraction_method;
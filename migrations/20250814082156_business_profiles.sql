@@ -0,0 +1 @@
+ALTER TABLE websites ADD COLUMN business_profile JSONB;
@@ -0,0 +1,24 @@
+ALTER TABLE websites ADD COLUMN owner_id VARCHAR(255);
+
+CREATE INDEX websites_owner_id_idx ON websites (owner_id);
+
+CREATE TYPE llm_call_outcome AS ENUM ('success', 'no_tool_call', 'failed');
+
+CREATE TABLE IF NOT EXISTS llm_usage (
+    llm_usage_id BIGSERIAL PRIMARY KEY,
+    -- Kept when the website is deleted so past spend still adds up.
+    website_id BIGINT REFERENCES websites (website_id) ON DELETE SET NULL,
+    owner_id VARCHAR(255),
+    operation VARCHAR(255) NOT NULL,
+    provider VARCHAR(64) NOT NULL,
+    model VARCHAR(255) NOT NULL,
+    prompt_tokens BIGINT NOT NULL,
+    completion_tokens BIGINT NOT NULL,
+    latency_ms BIGINT NOT NULL,
+    estimated_cost_usd DOUBLE PRECISION,
+    outcome llm_call_outcome NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX llm_usage_owner_id_created_at_idx ON llm_usage (owner_id, created_at);
+CREATE INDEX llm_usage_website_id_idx ON llm_usage (website_id);
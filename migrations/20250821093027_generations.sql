@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS generations (
+    generation_id BIGSERIAL PRIMARY KEY,
+    website_id BIGINT REFERENCES websites (website_id) ON DELETE SET NULL,
+    owner_id VARCHAR(255),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX generations_owner_id_created_at_idx ON generations (owner_id, created_at);
+CREATE INDEX websites_owner_id_created_at_idx ON websites (owner_id, created_at);
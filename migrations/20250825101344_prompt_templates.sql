@@ -0,0 +1,16 @@
+CREATE TYPE prompt_name AS ENUM (
+    'contact_extraction', 'business_profile_extraction', 'landing_page'
+);
+
+CREATE TABLE IF NOT EXISTS prompt_templates (
+    prompt_template_id BIGSERIAL PRIMARY KEY,
+    name prompt_name NOT NULL,
+    version INTEGER NOT NULL,
+    template TEXT NOT NULL,
+    created_by VARCHAR(255),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (name, version)
+);
+
+ALTER TABLE websites ADD COLUMN contact_prompt_version INTEGER,
+ADD COLUMN generated_website_prompt_version INTEGER;
@@ -0,0 +1 @@
+ALTER TYPE prompt_name ADD VALUE 'landing_page_copy';
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS previews (
+    slug VARCHAR(255) PRIMARY KEY,
+    website_id BIGINT NOT NULL REFERENCES websites (website_id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ
+);
+
+CREATE INDEX previews_website_id_idx ON previews (website_id);
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS automation_failures (
+    automation_failure_id BIGSERIAL PRIMARY KEY,
+    website_id BIGINT NOT NULL REFERENCES websites (website_id) ON DELETE CASCADE,
+    run_id UUID NOT NULL,
+    error TEXT NOT NULL,
+    url TEXT,
+    page_source TEXT,
+    screenshot BYTEA,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX automation_failures_website_id_created_at_idx ON automation_failures (website_id, created_at);
@@ -0,0 +1,13 @@
+CREATE TYPE screenshot_subject AS ENUM ('original', 'generated');
+CREATE TYPE viewport AS ENUM ('desktop', 'mobile');
+
+CREATE TABLE IF NOT EXISTS screenshots (
+    website_id BIGINT NOT NULL REFERENCES websites (website_id) ON DELETE CASCADE,
+    subject screenshot_subject NOT NULL,
+    viewport viewport NOT NULL,
+    url TEXT NOT NULL,
+    image_key TEXT NOT NULL,
+    thumbnail_key TEXT NOT NULL,
+    captured_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (website_id, subject, viewport)
+);
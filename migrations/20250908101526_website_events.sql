@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS website_events (
+    event_id BIGSERIAL PRIMARY KEY,
+    event JSONB NOT NULL,
+    emitted_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,10 @@
+ALTER TABLE website_events RENAME COLUMN emitted_at TO occurred_at;
+ALTER TABLE website_events ADD COLUMN schema_version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE website_events ADD COLUMN website_id BIGINT;
+ALTER TABLE website_events ADD COLUMN tenant VARCHAR(255);
+
+UPDATE website_events SET website_id = COALESCE((event->>'website_id')::BIGINT, (event->>'id')::BIGINT);
+UPDATE website_events SET tenant = websites.owner_id FROM websites WHERE websites.website_id = website_events.website_id;
+
+ALTER TABLE website_events ALTER COLUMN website_id SET NOT NULL;
+ALTER TABLE website_events ALTER COLUMN schema_version DROP DEFAULT;
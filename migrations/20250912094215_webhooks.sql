@@ -0,0 +1,50 @@
+CREATE TYPE website_event_type AS ENUM (
+    'website_added',
+    'fetched_contact',
+    'generated_website',
+    'stage_started',
+    'stage_progress',
+    'stage_failed'
+);
+
+CREATE TABLE IF NOT EXISTS webhooks (
+    webhook_id BIGSERIAL PRIMARY KEY,
+    tenant VARCHAR(255) NOT NULL,
+    url TEXT NOT NULL,
+    event_types website_event_type[] NOT NULL,
+    secret TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX webhooks_tenant_idx ON webhooks (tenant);
+
+CREATE TYPE webhook_delivery_status AS ENUM ('pending', 'succeeded', 'failed');
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    delivery_id BIGSERIAL PRIMARY KEY,
+    webhook_id BIGINT NOT NULL REFERENCES webhooks (webhook_id) ON DELETE CASCADE,
+    -- NULL for test deliveries.
+    event_id BIGINT REFERENCES website_events (event_id),
+    replay_of BIGINT REFERENCES webhook_deliveries (delivery_id) ON DELETE SET NULL,
+    -- Sent as is, so replays are signed over the same body.
+    payload TEXT NOT NULL,
+    status webhook_delivery_status NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    -- NULL while the delivery is not scheduled.
+    next_attempt_at TIMESTAMPTZ,
+    last_response_status INTEGER,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    delivered_at TIMESTAMPTZ
+);
+
+CREATE INDEX webhook_deliveries_webhook_id_created_at_idx ON webhook_deliveries (webhook_id, created_at);
+CREATE INDEX webhook_deliveries_due_idx ON webhook_deliveries (next_attempt_at) WHERE status = 'pending';
+
+-- The last event turned into deliveries. Events logged before webhooks existed are not delivered.
+CREATE TABLE IF NOT EXISTS webhook_dispatch (
+    singleton BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (singleton),
+    last_event_id BIGINT NOT NULL
+);
+
+INSERT INTO webhook_dispatch (last_event_id) SELECT COALESCE(MAX(event_id), 0) FROM website_events;
@@ -0,0 +1,17 @@
+-- Events written in the same transaction as the website changes they describe, until the relay
+-- publishes and deletes them.
+CREATE TABLE IF NOT EXISTS website_outbox (
+    outbox_id BIGSERIAL PRIMARY KEY,
+    event JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- The relay currently publishing the outbox. Only one relay at a time, so events are published in
+-- order.
+CREATE TABLE IF NOT EXISTS outbox_relay (
+    singleton BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (singleton),
+    relay_id UUID,
+    lease_until TIMESTAMPTZ NOT NULL DEFAULT '-infinity'
+);
+
+INSERT INTO outbox_relay DEFAULT VALUES;
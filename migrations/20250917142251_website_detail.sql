@@ -0,0 +1,9 @@
+ALTER TYPE website_event_type ADD VALUE 'contact_updated';
+ALTER TYPE website_event_type ADD VALUE 'website_deleted';
+
+-- Contact fields corrected by the owner, kept when the contact is extracted again.
+ALTER TYPE extraction_method ADD VALUE 'manual';
+
+ALTER TABLE websites ADD COLUMN social_links JSONB;
+
+CREATE INDEX website_events_website_id_event_id_idx ON website_events (website_id, event_id);
@@ -0,0 +1,2 @@
+-- Events are replayed to the tenant owning the websites they are about.
+CREATE INDEX website_events_tenant_event_id_idx ON website_events (tenant, event_id);
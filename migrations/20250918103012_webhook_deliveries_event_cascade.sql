@@ -0,0 +1,5 @@
+-- The events of deleted websites are removed from the log, along with their deliveries.
+ALTER TABLE webhook_deliveries
+    DROP CONSTRAINT webhook_deliveries_event_id_fkey,
+    ADD CONSTRAINT webhook_deliveries_event_id_fkey
+        FOREIGN KEY (event_id) REFERENCES website_events (event_id) ON DELETE CASCADE;
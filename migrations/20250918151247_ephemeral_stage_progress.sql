@@ -0,0 +1,2 @@
+-- Stage progress is only sent to the connected clients, it is no longer logged.
+DELETE FROM website_events WHERE event->>'type' = 'StageProgress';
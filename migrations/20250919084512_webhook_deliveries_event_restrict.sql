@@ -0,0 +1,5 @@
+-- Events are kept when their website is deleted, deliveries keep referencing them.
+ALTER TABLE webhook_deliveries
+    DROP CONSTRAINT webhook_deliveries_event_id_fkey,
+    ADD CONSTRAINT webhook_deliveries_event_id_fkey
+        FOREIGN KEY (event_id) REFERENCES website_events (event_id);